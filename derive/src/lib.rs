@@ -0,0 +1,756 @@
+#![deny(warnings)]
+#![deny(missing_docs)]
+
+//! Derive macro for [mint-std140](https://docs.rs/mint-std140). Use it through the `derive`
+//! feature of that crate instead of depending on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenTree};
+use quote::quote;
+use syn::parse::ParseStream;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, Ident, Lit, LitStr, Meta, NestedMeta,
+    Token,
+};
+
+/// Derive `AsStd140`, `Std140Sized`, `Std140Bytes`, `Std140Layout`, `FromStd140` and
+/// `Std140Validate` for a struct with named fields that implement them. The fields of non-generic
+/// structs only need `Std140Bytes` and `Std140Layout`, so they can also be the 8-bit and 16-bit
+/// scalars of `GL_EXT_shader_explicit_arithmetic_types`, such as `F16` and `mint::Vector2<i16>`.
+///
+/// The std140 type of the struct is a generated `#[repr(C, align(16))]` struct named after the
+/// input struct with a `Std140` suffix. It holds every field as its packed bytes in native byte
+/// order, at the std140 offset of the field, followed by a `_padN` member. The std140 type of a
+/// generic struct holds the std140 types of its fields instead. These only follow the layout if
+/// no member is packed after a vec3, so `as_std140` fails to compile for instantiations where
+/// one is, and arrays of generic structs do not implement `AsStd140`. The std140 type and the
+/// `AsStd140` impl are only generated with the `std` feature of mint-std140.
+///
+/// Add `#[std140(quantize = "unorm8")]` or `#[std140(quantize = "half")]` to a float or float
+/// vector field to store it packed into a `uint` or `uvec2`, like GLSL's `packUnorm4x8` and
+/// `packHalf2x16`. Use `glsl_unpack` to generate the shader code reading such fields.
+///
+/// Add `#[std140(range = 0.0..=1.0)]` or any other range of `f64` bounds to a numeric field to
+/// check every scalar in it, and `#[std140(assert_finite)]` to reject infinities and NaNs. The
+/// derived `Std140Validate` reports the first violation, including those in nested structs and
+/// arrays of them, and packing panics on one in builds with debug assertions.
+///
+/// The derived `FromStd140` reads fields that are cut off from the end of the input as their
+/// `#[std140(default = ...)]` value, such as `#[std140(default = 1.0)]`. Add `#[std140(default)]`
+/// to the struct to also derive `Default` from these values, using `Default::default()` for
+/// fields without one.
+///
+/// Add `#[std140(max_size = N)]` to fail compilation if the std140 size of the struct exceeds `N`
+/// bytes. `#[std140(max_size)]` uses 16384, the minimum `GL_MAX_UNIFORM_BLOCK_SIZE` and
+/// `maxUniformBufferRange` every OpenGL and Vulkan implementation supports. Generic structs are
+/// checked for each instantiation whose size is used.
+///
+/// Add `#[std140(layout_version)]` to a `u32` field of a non-generic struct to implement
+/// `Std140LayoutVersion` and always pack the layout fingerprint of the struct, truncated to 32
+/// bits, in place of its value. The fingerprint is computed on first use and cached in a static.
+/// Use `glsl_layout_version_check` to generate the shader code comparing it.
+///
+/// Add `#[std140(repr)]` to a non-generic struct to also generate a plain `#[repr(C)]` mirror
+/// named after it with a `Repr` suffix, built from the types of `mint_std140::repr` with an
+/// explicit `_padN` member after every field, and implement `AsStd140Repr` for it. This needs the
+/// `repr` feature of mint-std140, and the fields of nested structs must use it too.
+#[proc_macro_derive(AsStd140, attributes(std140))]
+pub fn derive_as_std140(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// The guaranteed minimum uniform block size of OpenGL and Vulkan.
+const DEFAULT_MAX_SIZE: usize = 16384;
+
+/// The options given in the `#[std140(...)]` attributes of a struct.
+#[derive(Default)]
+struct StructOptions {
+    max_size: Option<usize>,
+    default: bool,
+    repr: bool,
+}
+
+fn struct_options(input: &DeriveInput) -> Result<StructOptions, Error> {
+    let mut options = StructOptions::default();
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("std140")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected `#[std140(max_size = ...)]`")),
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("max_size") => {
+                    options.max_size = Some(DEFAULT_MAX_SIZE)
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                    options.default = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("repr") => options.repr = true,
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("max_size") =>
+                {
+                    match &name_value.lit {
+                        Lit::Int(lit) => options.max_size = Some(lit.base10_parse()?),
+                        lit => return Err(Error::new_spanned(lit, "expected an integer size")),
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "unknown std140 attribute")),
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// The options given in the `#[std140(...)]` attributes of a field.
+#[derive(Default)]
+struct FieldOptions {
+    quantize: Option<String>,
+    range: Option<proc_macro2::TokenStream>,
+    assert_finite: bool,
+    default: Option<proc_macro2::TokenStream>,
+    layout_version: bool,
+}
+
+/// Parse `= <expression>` up to the next option. Expressions are kept as tokens, since parsing
+/// them needs syn's `full` feature.
+fn parse_expression(input: ParseStream, name: &Ident) -> Result<proc_macro2::TokenStream, Error> {
+    input.parse::<Token![=]>()?;
+    let mut expression = proc_macro2::TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![,]) {
+        expression.extend(Some(input.parse::<TokenTree>()?));
+    }
+    if expression.is_empty() {
+        return Err(Error::new(name.span(), format!("expected an expression after `{} =`", name)));
+    }
+    Ok(expression)
+}
+
+fn field_options(field: &syn::Field) -> Result<FieldOptions, Error> {
+    let mut options = FieldOptions::default();
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("std140")) {
+        attr.parse_args_with(|input: ParseStream| {
+            while !input.is_empty() {
+                let name: Ident = input.parse()?;
+                if name == "assert_finite" {
+                    options.assert_finite = true;
+                } else if name == "layout_version" {
+                    options.layout_version = true;
+                } else if name == "quantize" {
+                    input.parse::<Token![=]>()?;
+                    let lit: LitStr = input.parse()?;
+                    if lit.value() != "unorm8" && lit.value() != "half" {
+                        return Err(Error::new_spanned(lit, "expected \"unorm8\" or \"half\""));
+                    }
+                    options.quantize = Some(lit.value());
+                } else if name == "range" {
+                    options.range = Some(parse_expression(input, &name)?);
+                } else if name == "default" {
+                    options.default = Some(parse_expression(input, &name)?);
+                } else {
+                    return Err(Error::new(name.span(), "unknown std140 field attribute"));
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "AsStd140 can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "AsStd140 can only be derived for structs",
+            ))
+        }
+    };
+
+    let krate = quote!(::mint_std140);
+    let StructOptions { max_size, default, repr } = struct_options(input)?;
+    if repr && !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "#[std140(repr)] is not supported on generic structs",
+        ));
+    }
+    let vis = &input.vis;
+    let name = &input.ident;
+    let name_string = name.to_string();
+    let std140_name = Ident::new(&format!("{}Std140", name), Span::call_site());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let field_names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+    let field_name_strings: Vec<_> =
+        field_names.iter().map(|name| name.as_ref().unwrap().to_string()).collect();
+    let field_vises: Vec<_> = fields.iter().map(|field| &field.vis).collect();
+    // Quantized fields are stored as their packed type.
+    let mut storage_types = Vec::new();
+    let mut storage_values = Vec::new();
+    let mut quantizations = Vec::new();
+    let mut extra_bounds = Vec::new();
+    let mut checks = Vec::new();
+    let mut debug_check = false;
+    let mut reads = Vec::new();
+    let mut defaults = Vec::new();
+    let mut field_defaults = Vec::new();
+    let mut version_field = None;
+    for field in fields {
+        let ty = &field.ty;
+        let name = &field.ident;
+        let name_string = name.as_ref().unwrap().to_string();
+        let options = field_options(field)?;
+        if options.layout_version {
+            if version_field.is_some() {
+                return Err(Error::new_spanned(name, "only one field can hold the layout version"));
+            }
+            if options.quantize.is_some() {
+                return Err(Error::new_spanned(name, "the layout version cannot be quantized"));
+            }
+            if !input.generics.params.is_empty() {
+                return Err(Error::new_spanned(
+                    name,
+                    "the layout version is cached per struct, so it needs a non-generic struct",
+                ));
+            }
+            version_field = Some(name_string.clone());
+        }
+
+        if options.assert_finite {
+            checks.push(quote! {
+                if let ::core::option::Option::Some(value) = #krate::Std140Scalars::find_scalar(
+                    &self.#name,
+                    &mut |value: f64| !value.is_finite(),
+                ) {
+                    return ::core::result::Result::Err(#krate::Std140Error::OutOfRange {
+                        field: ::core::convert::Into::into(#name_string),
+                        value,
+                        expected: "a finite value",
+                    });
+                }
+            });
+        }
+        if let Some(range) = &options.range {
+            let expected = range.to_string().replace(' ', "");
+            checks.push(quote! {
+                let range = #range;
+                if let ::core::option::Option::Some(value) = #krate::Std140Scalars::find_scalar(
+                    &self.#name,
+                    &mut |value: f64| !::core::ops::RangeBounds::contains(&range, &value),
+                ) {
+                    return ::core::result::Result::Err(#krate::Std140Error::OutOfRange {
+                        field: ::core::convert::Into::into(#name_string),
+                        value,
+                        expected: #expected,
+                    });
+                }
+            });
+        }
+        if options.assert_finite || options.range.is_some() {
+            extra_bounds.push(quote!(#ty: #krate::Std140Scalars));
+            debug_check = true;
+        }
+        // Nested structs and arrays of them check their own fields.
+        checks.push(quote! {
+            #krate::__private::validate_field(&self.#name, #name_string)?;
+        });
+
+        // Reads the stored value from `bytes`, which starts at the field.
+        let read = |storage: proc_macro2::TokenStream| quote!(<#storage as #krate::FromStd140>::from_std140_bytes(bytes, endian));
+        match options.quantize.as_deref() {
+            Some("unorm8") => {
+                let packed = read(quote!(<#ty as #krate::QuantizeUnorm8>::Packed));
+                reads.push(quote!(<#ty as #krate::QuantizeUnorm8>::dequantize_unorm8(&#packed)));
+            }
+            Some(_) => {
+                let packed = read(quote!(<#ty as #krate::QuantizeHalf>::Packed));
+                reads.push(quote!(<#ty as #krate::QuantizeHalf>::dequantize_half(&#packed)));
+            }
+            None if options.layout_version => reads.push(read(quote!(u32))),
+            None => reads.push(read(quote!(#ty))),
+        }
+        defaults.push(match &options.default {
+            Some(default) => quote!(#default),
+            None => quote! {
+                return ::core::result::Result::Err(#krate::Std140Error::TooShort {
+                    field: #name_string,
+                    required,
+                    available: all.len(),
+                })
+            },
+        });
+        field_defaults.push(options.default.clone());
+
+        // Quantized values are recorded under this path by the `audit` feature.
+        let path = format!("{}.{}", input.ident, name_string);
+        match options.quantize.as_deref() {
+            Some("unorm8") => {
+                storage_types.push(quote!(<#ty as #krate::QuantizeUnorm8>::Packed));
+                let quantization = quote! {
+                    #krate::Quantization::Unorm8(<#ty as #krate::QuantizeUnorm8>::COMPONENTS)
+                };
+                storage_values.push(quote! {{
+                    let packed = #krate::QuantizeUnorm8::quantize_unorm8(&self.#name);
+                    #krate::__private::audit_quantized(#path, #quantization, &self.#name, || {
+                        <#ty as #krate::QuantizeUnorm8>::dequantize_unorm8(&packed)
+                    });
+                    packed
+                }});
+                quantizations.push(quote!(::core::option::Option::Some(#quantization)));
+                extra_bounds.push(quote!(#ty: #krate::QuantizeUnorm8 + #krate::Std140Scalars));
+            }
+            Some(_) => {
+                storage_types.push(quote!(<#ty as #krate::QuantizeHalf>::Packed));
+                let quantization = quote! {
+                    #krate::Quantization::Half(<#ty as #krate::QuantizeHalf>::COMPONENTS)
+                };
+                storage_values.push(quote! {{
+                    let packed = #krate::QuantizeHalf::quantize_half(&self.#name);
+                    #krate::__private::audit_quantized(#path, #quantization, &self.#name, || {
+                        <#ty as #krate::QuantizeHalf>::dequantize_half(&packed)
+                    });
+                    packed
+                }});
+                quantizations.push(quote!(::core::option::Option::Some(#quantization)));
+                extra_bounds.push(quote!(#ty: #krate::QuantizeHalf + #krate::Std140Scalars));
+            }
+            None if options.layout_version => {
+                storage_types.push(quote!(u32));
+                storage_values.push(quote! {
+                    <Self as #krate::Std140LayoutVersion>::layout_version()
+                });
+                quantizations.push(quote!(::core::option::Option::None));
+            }
+            None => {
+                storage_types.push(quote!(#ty));
+                storage_values.push(quote!(self.#name));
+                quantizations.push(quote!(::core::option::Option::None));
+            }
+        }
+    }
+
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::WhereClause {
+        where_token: Default::default(),
+        predicates: Default::default(),
+    });
+    for bound in &extra_bounds {
+        where_clause.predicates.push(syn::parse_quote!(#bound));
+    }
+    for ty in &storage_types {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: #krate::Std140Bytes + #krate::Std140Layout));
+    }
+
+    // Only the std140 types of generic structs hold the std140 types of their fields, so the
+    // fields of other structs can be types without one, such as 16-bit floats.
+    let mut std140_where_clause = where_clause.clone();
+    if !input.generics.params.is_empty() {
+        for ty in &storage_types {
+            std140_where_clause.predicates.push(syn::parse_quote!(#ty: #krate::AsStd140));
+        }
+    }
+
+    let mut read_where_clause = where_clause.clone();
+    for ty in &storage_types {
+        read_where_clause.predicates.push(syn::parse_quote!(#ty: #krate::FromStd140));
+    }
+
+    let version_impl = match &version_field {
+        Some(field) => quote! {
+            #[automatically_derived]
+            impl #impl_generics #krate::Std140LayoutVersion for #name #ty_generics #where_clause {
+                const VERSION_FIELD: &'static str = #field;
+
+                fn layout_version() -> u32 {
+                    static VERSION: #krate::__private::LayoutVersionCache =
+                        #krate::__private::LayoutVersionCache::new();
+                    VERSION.get::<Self>()
+                }
+            }
+        },
+        None => quote!(),
+    };
+
+    let default_impl = if default {
+        let values = field_names.iter().zip(&field_defaults).map(|(name, default)| match default {
+            Some(default) => quote!(#name: #default),
+            None => quote!(#name: ::core::default::Default::default()),
+        });
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self { #(#values,)* }
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    // Offsets of every field followed by the end of the last field.
+    let field_count = fields.len();
+    let indices: Vec<_> = (0..field_count).collect();
+    let offsets = quote! {
+        #[doc(hidden)]
+        const __MINT_STD140_OFFSETS: [usize; #field_count + 1] = {
+            let mut offsets = [0usize; #field_count + 1];
+            let mut offset = 0usize;
+            #(
+                offset = #krate::__private::align_up(
+                    offset,
+                    <#storage_types as #krate::Std140Sized>::ALIGN,
+                );
+                offsets[#indices] = offset;
+                offset += <#storage_types as #krate::Std140Sized>::SIZE;
+            )*
+            offsets[#field_count] = offset;
+            offsets
+        };
+    };
+
+    let size = quote! {
+        #krate::__private::align_up(Self::__MINT_STD140_OFFSETS[#field_count], 16)
+    };
+    let (size, size_check) = match max_size {
+        Some(max_size) => {
+            let message =
+                format!("the std140 size of {} exceeds the max_size of {} bytes", name, max_size);
+            let size = quote! {{
+                let size = #size;
+                assert!(size <= #max_size, #message);
+                size
+            }};
+            // Unnamed constants are always evaluated, but can only name non-generic types.
+            let size_check = if input.generics.params.is_empty() {
+                quote!(const _: usize = <#name as #krate::Std140Sized>::SIZE;)
+            } else {
+                quote!()
+            };
+            (size, size_check)
+        }
+        None => (size, quote!()),
+    };
+
+    let pad_names: Vec<_> =
+        (0..field_count).map(|i| Ident::new(&format!("_pad{}", i), Span::call_site())).collect();
+    // Each field is followed by the padding up to the next field or the end of the struct.
+    let pad_lens: Vec<_> = (0..field_count)
+        .map(|i| {
+            let ty = &storage_types[i];
+            let next = if i + 1 < field_count {
+                quote!(#name::__MINT_STD140_OFFSETS[#i + 1])
+            } else {
+                quote!(<#name as #krate::Std140Sized>::SIZE)
+            };
+            quote! {
+                #next - #name::__MINT_STD140_OFFSETS[#i]
+                    - <#ty as #krate::Std140Sized>::SIZE
+            }
+        })
+        .collect();
+
+    let repr_impl = if repr {
+        let repr_name = Ident::new(&format!("{}Repr", name), Span::call_site());
+        let size_message = format!("the size of {} is not the std140 size of {}", repr_name, name);
+        quote! {
+            #[allow(missing_docs)]
+            #[repr(C)]
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #repr_name {
+                #(
+                    #field_vises #field_names: <#storage_types as #krate::repr::AsStd140Repr>::Repr,
+                    #pad_names: [u8; #pad_lens],
+                )*
+            }
+
+            #[automatically_derived]
+            impl #krate::repr::AsStd140Repr for #name {
+                type Repr = #repr_name;
+
+                fn as_std140_repr(&self) -> #repr_name {
+                    #repr_name {
+                        #(
+                            #field_names: #krate::repr::AsStd140Repr::as_std140_repr(
+                                &#storage_values,
+                            ),
+                            #pad_names: [0; #pad_lens],
+                        )*
+                    }
+                }
+            }
+
+            const _: () = {
+                assert!(
+                    ::core::mem::size_of::<#repr_name>() == <#name as #krate::Std140Sized>::SIZE,
+                    #size_message,
+                );
+                #(
+                    assert!(
+                        ::core::mem::offset_of!(#repr_name, #field_names)
+                            == #name::__MINT_STD140_OFFSETS[#indices]
+                    );
+                )*
+            };
+        }
+    } else {
+        quote!()
+    };
+
+    // Nested structs check themselves when they are written.
+    let debug_check = if !debug_check {
+        quote!()
+    } else {
+        quote! {
+            if cfg!(debug_assertions) {
+                if let ::core::result::Result::Err(error) = #krate::Std140Validate::validate(self) {
+                    panic!("{}", error);
+                }
+            }
+        }
+    };
+
+    // The std140 types of vec3 members are 16 bytes in Rust, so a struct of them puts a member
+    // packed after a vec3 at the wrong offset. Non-generic structs store every member as its
+    // packed bytes instead, at the offsets of the layout. The sizes of generic members cannot be
+    // used as array lengths, so generic structs keep the std140 types of their members, and
+    // `as_std140` fails to compile for every instantiation whose struct of them does not follow
+    // the layout.
+    let (std140_struct, as_std140) = if input.generics.params.is_empty() {
+        let size_message =
+            format!("the size of {} is not the std140 size of {}", std140_name, name);
+        let std140_struct = quote! {
+            #[allow(missing_docs)]
+            #[repr(C, align(16))]
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #std140_name {
+                #(
+                    #field_vises #field_names: [u8; <#storage_types as #krate::Std140Sized>::SIZE],
+                    #pad_names: [u8; #pad_lens],
+                )*
+            }
+
+            #[automatically_derived]
+            #[allow(unsafe_code)]
+            unsafe impl #krate::__private::std140::Std140Struct for #std140_name {}
+
+            const _: () = {
+                assert!(
+                    ::core::mem::size_of::<#std140_name>() == <#name as #krate::Std140Sized>::SIZE,
+                    #size_message,
+                );
+                #(
+                    assert!(
+                        ::core::mem::offset_of!(#std140_name, #field_names)
+                            == #name::__MINT_STD140_OFFSETS[#indices]
+                    );
+                )*
+            };
+        };
+        let as_std140 = quote! {
+            let mut std140 = #std140_name {
+                #(
+                    #field_names: [0; <#storage_types as #krate::Std140Sized>::SIZE],
+                    #pad_names: [0; #pad_lens],
+                )*
+            };
+            #(
+                #krate::Std140Bytes::write_std140(
+                    &#storage_values,
+                    &mut std140.#field_names,
+                    #krate::Endian::NATIVE,
+                );
+            )*
+            std140
+        };
+        (std140_struct, as_std140)
+    } else {
+        let layout_message = format!(
+            "the std140 members of {} do not follow its layout, since one is packed after a \
+             vec3; make the struct non-generic to pack it as bytes",
+            name
+        );
+        let std140_struct = quote! {
+            #[allow(missing_docs, non_camel_case_types)]
+            #[repr(C, align(16))]
+            #vis struct #std140_name #impl_generics #std140_where_clause {
+                #(
+                    #field_vises #field_names: <#storage_types as #krate::AsStd140>::Std140Type,
+                )*
+            }
+        };
+        let as_std140 = quote! {
+            const {
+                assert!(
+                    ::core::mem::size_of::<#std140_name #ty_generics>()
+                        == <Self as #krate::Std140Sized>::SIZE
+                        #(
+                            && ::core::mem::offset_of!(#std140_name #ty_generics, #field_names)
+                                == Self::__MINT_STD140_OFFSETS[#indices]
+                        )*,
+                    #layout_message,
+                );
+            }
+            #std140_name {
+                #(
+                    #field_names: #krate::AsStd140::as_std140(&#storage_values),
+                )*
+            }
+        };
+        (std140_struct, as_std140)
+    };
+
+    Ok(quote! {
+        // The std140 crate needs std, so the std140 type is only generated with the `std` feature
+        // of mint-std140.
+        #krate::__private::with_std140! {
+            #std140_struct
+
+            #[automatically_derived]
+            impl #impl_generics #krate::AsStd140 for #name #ty_generics #std140_where_clause {
+                type Std140Type = #std140_name #ty_generics;
+
+                fn as_std140(&self) -> Self::Std140Type {
+                    #as_std140
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #offsets
+        }
+
+        #(
+            #[automatically_derived]
+            impl #impl_generics #krate::__private::Std140Field<
+                { #krate::__private::field_id(#field_name_strings) },
+            > for #name #ty_generics #where_clause {
+                type Type = #storage_types;
+                const OFFSET: usize = Self::__MINT_STD140_OFFSETS[#indices];
+            }
+        )*
+
+        #[automatically_derived]
+        impl #impl_generics #krate::Std140Sized for #name #ty_generics #where_clause {
+            const SIZE: usize = #size;
+            const ALIGN: usize = 16;
+        }
+
+        #size_check
+
+        #[automatically_derived]
+        impl #impl_generics #krate::Std140Bytes for #name #ty_generics #where_clause {
+            fn write_std140(&self, bytes: &mut [u8], endian: #krate::Endian) {
+                #debug_check
+                #krate::__private::debug_check_len::<Self>(bytes);
+                let bytes = &mut bytes[..<Self as #krate::Std140Sized>::SIZE];
+                bytes.fill(0);
+                #(
+                    #krate::__private::debug_check_offset::<#storage_types>(
+                        Self::__MINT_STD140_OFFSETS[#indices],
+                    );
+                    debug_assert!(
+                        Self::__MINT_STD140_OFFSETS[#indices]
+                            + <#storage_types as #krate::Std140Sized>::SIZE
+                            <= <Self as #krate::Std140Sized>::SIZE,
+                        "field {} of {} ends past its size",
+                        #field_name_strings,
+                        ::core::any::type_name::<Self>(),
+                    );
+                    #krate::Std140Bytes::write_std140(
+                        &#storage_values,
+                        &mut bytes[Self::__MINT_STD140_OFFSETS[#indices]..],
+                        endian,
+                    );
+                )*
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #krate::FromStd140 for #name #ty_generics #read_where_clause {
+            fn from_std140_bytes(bytes: &[u8], endian: #krate::Endian) -> Self {
+                match <Self as #krate::FromStd140>::try_from_std140_bytes(bytes, endian) {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(error) => panic!("{}", error),
+                }
+            }
+
+            fn try_from_std140_bytes(
+                bytes: &[u8],
+                endian: #krate::Endian,
+            ) -> ::core::result::Result<Self, #krate::Std140Error> {
+                let all = bytes;
+                ::core::result::Result::Ok(Self {
+                    #(
+                        #field_names: {
+                            let required = Self::__MINT_STD140_OFFSETS[#indices]
+                                + <#storage_types as #krate::Std140Sized>::SIZE;
+                            if all.len() >= required {
+                                let bytes = &all[Self::__MINT_STD140_OFFSETS[#indices]..];
+                                #reads
+                            } else {
+                                #defaults
+                            }
+                        },
+                    )*
+                })
+            }
+        }
+
+        #default_impl
+
+        #repr_impl
+
+        #version_impl
+
+        #[automatically_derived]
+        impl #impl_generics #krate::Std140Validate for #name #ty_generics #where_clause {
+            fn validate(&self) -> ::core::result::Result<(), #krate::Std140Error> {
+                #(
+                    #checks
+                )*
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #krate::Std140Layout for #name #ty_generics #where_clause {
+            fn layout() -> #krate::Layout {
+                #krate::Layout::structure(
+                    #name_string,
+                    #krate::__private::vec![
+                        #(
+                            #krate::Field {
+                                name: #field_name_strings,
+                                offset: Self::__MINT_STD140_OFFSETS[#indices],
+                                layout: <#storage_types as #krate::Std140Layout>::layout(),
+                                quantization: #quantizations,
+                            },
+                        )*
+                    ],
+                )
+            }
+
+            fn validate_fields(&self) -> ::core::result::Result<(), #krate::Std140Error> {
+                #krate::Std140Validate::validate(self)
+            }
+        }
+    })
+}
@@ -0,0 +1,242 @@
+use crate::bytes::debug_check_offset;
+use crate::{AlignedStd140Buffer, Endian, Std140Bytes, Std140Error, Std140Sized};
+use core::any::type_name;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+/// A fixed-capacity byte region that many small std140 blocks are packed into.
+///
+/// Every allocation returns a [Handle] remembering where the block lives, so it can be rewritten
+/// later without keeping track of offsets by hand. Blocks start at multiples of 16 bytes, or of a
+/// larger alignment given to [Std140Arena::with_alignment], such as 64 to keep blocks on separate
+/// cache lines when threads fill them in parallel. The arena's storage is aligned the same way.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140Arena;
+///
+/// let mut arena = Std140Arena::new(1024);
+/// let color = arena.alloc(&mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 1.0 }).unwrap();
+/// let scale = arena.alloc(&mint::Vector2 { x: 1.0f32, y: 1.0 }).unwrap();
+/// assert_eq!(scale.offset(), 16);
+///
+/// arena.set(color, &mint::Vector4 { x: 0.0f32, y: 1.0, z: 0.0, w: 1.0 });
+/// arena.write_field(color, 12, &0.5f32);
+/// assert_eq!(arena.bytes_of(color)[12..16], 0.5f32.to_ne_bytes());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Std140Arena {
+    bytes: AlignedStd140Buffer,
+    used: usize,
+    endian: Endian,
+}
+
+/// The location of a block of type `T` in a [Std140Arena].
+pub struct Handle<T> {
+    offset: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn at(offset: usize) -> Self {
+        Handle { offset, marker: PhantomData }
+    }
+
+    /// The offset of the block from the start of the arena.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.offset.hash(state)
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handle").field(&self.offset).finish()
+    }
+}
+
+impl Std140Arena {
+    /// Create an arena of `capacity` zeroed bytes that packs in the host byte order.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_endian(capacity, Endian::NATIVE)
+    }
+
+    /// Create an arena of `capacity` zeroed bytes that packs in the given byte order.
+    pub fn with_endian(capacity: usize, endian: Endian) -> Self {
+        Self::with_alignment(capacity, 16, endian)
+    }
+
+    /// Create an arena of `capacity` zeroed bytes whose blocks start at multiples of `align`
+    /// bytes, and which packs in the given byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mint_std140::{Endian, Std140Arena};
+    ///
+    /// let mut arena = Std140Arena::with_alignment(1024, 64, Endian::NATIVE);
+    /// arena.alloc(&1.0f32).unwrap();
+    /// let second = arena.alloc(&2.0f32).unwrap();
+    /// assert_eq!(second.offset(), 64);
+    /// assert_eq!(arena.as_bytes().as_ptr() as usize % 64, 0);
+    /// ```
+    pub fn with_alignment(capacity: usize, align: usize, endian: Endian) -> Self {
+        assert!(align.is_power_of_two() && align >= 16, "invalid block alignment {}", align);
+        Std140Arena { bytes: AlignedStd140Buffer::new(capacity, align), used: 0, endian }
+    }
+
+    /// The alignment of the start of every block.
+    pub fn align(&self) -> usize {
+        self.bytes.align()
+    }
+
+    /// The total number of bytes in the arena.
+    pub fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// The number of bytes up to the end of the last allocated block.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Pack `value` into a new block, or return [Std140Error::Overflow] if the arena is full.
+    pub fn alloc<T: Std140Bytes>(&mut self, value: &T) -> Result<Handle<T>, Std140Error> {
+        let offset = self.used.next_multiple_of(self.align());
+        if offset + T::SIZE > self.bytes.len() {
+            return Err(Std140Error::Overflow {
+                field: type_name::<T>(),
+                offset,
+                required: offset + T::SIZE,
+                available: self.bytes.len(),
+            });
+        }
+        let bytes = self.bytes.as_mut_slice();
+        bytes[self.used..offset].fill(0);
+        debug_check_offset::<T>(offset);
+        value.write_std140(&mut bytes[offset..], self.endian);
+        self.used = offset + T::SIZE;
+        #[cfg(feature = "stats")]
+        {
+            crate::stats::record_block(type_name::<T>(), T::SIZE);
+            crate::stats::record_arena_usage(self.used);
+        }
+        Ok(Handle::at(offset))
+    }
+
+    /// Repack the whole block behind `handle`.
+    pub fn set<T: Std140Bytes>(&mut self, handle: Handle<T>, value: &T) {
+        value.write_std140(&mut self.bytes.as_mut_slice()[handle.offset..], self.endian);
+        #[cfg(feature = "stats")]
+        crate::stats::record_block(type_name::<T>(), T::SIZE);
+    }
+
+    /// Pack `value` at `offset` bytes into the block behind `handle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field does not fit inside the block.
+    pub fn write_field<T: Std140Sized, F: Std140Bytes>(
+        &mut self,
+        handle: Handle<T>,
+        offset: usize,
+        value: &F,
+    ) {
+        assert!(
+            offset + F::SIZE <= T::SIZE,
+            "writing {} at offset {} needs {} bytes, but the block is {} bytes",
+            type_name::<F>(),
+            offset,
+            offset + F::SIZE,
+            T::SIZE
+        );
+        debug_check_offset::<F>(offset);
+        value.write_std140(&mut self.bytes.as_mut_slice()[handle.offset + offset..], self.endian);
+    }
+
+    /// The packed bytes of the block behind `handle`.
+    pub fn bytes_of<T: Std140Sized>(&self, handle: Handle<T>) -> &[u8] {
+        &self.bytes.as_slice()[handle.offset..handle.offset + T::SIZE]
+    }
+
+    /// The packed bytes of the whole arena, including unused capacity.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Forget all blocks, invalidating every handle handed out so far.
+    pub fn reset(&mut self) {
+        self.bytes.as_mut_slice()[..self.used].fill(0);
+        self.used = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140Arena;
+    use crate::{Endian, Std140Error};
+
+    #[test]
+    fn alloc() {
+        let mut arena = Std140Arena::with_endian(48, Endian::Little);
+        let a = arena.alloc(&mint::Vector3 { x: 1u32, y: 2, z: 3 }).unwrap();
+        let b = arena.alloc(&mint::Vector2 { x: 4u32, y: 5 }).unwrap();
+        assert_eq!((a.offset(), b.offset()), (0, 16));
+        assert_eq!(arena.used(), 24);
+
+        arena.write_field(a, 4, &9u32);
+        assert_eq!(arena.bytes_of(a)[4..8], 9u32.to_le_bytes());
+        arena.set(b, &mint::Vector2 { x: 6u32, y: 7 });
+        assert_eq!(arena.bytes_of(b)[0..4], 6u32.to_le_bytes());
+
+        assert!(arena.alloc(&mint::Vector4 { x: 0u32, y: 0, z: 0, w: 0 }).is_ok());
+        assert!(matches!(
+            arena.alloc(&mint::Vector2 { x: 0u32, y: 0 }),
+            Err(Std140Error::Overflow { offset: 48, required: 56, available: 48, .. })
+        ));
+
+        arena.reset();
+        assert_eq!(arena.used(), 0);
+        assert!(arena.as_bytes().iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn alignment() {
+        let mut arena = Std140Arena::with_alignment(256, 128, Endian::Little);
+        let a = arena.alloc(&1u32).unwrap();
+        let b = arena.alloc(&[2u32; 2]).unwrap();
+        assert_eq!((a.offset(), b.offset(), arena.used()), (0, 128, 160));
+        assert!(arena.alloc(&3u32).is_err());
+
+        let copy = arena.clone();
+        assert_eq!(copy.as_bytes().as_ptr() as usize % 128, 0);
+        assert_eq!(copy.bytes_of(b), arena.bytes_of(b));
+    }
+}
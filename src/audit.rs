@@ -0,0 +1,174 @@
+//! A record of the lossy conversions performed while packing, for a one-time audit of the
+//! precision lost across a frame.
+//!
+//! While the `audit` feature is enabled, every derived struct records each field it quantizes with
+//! `#[std140(quantize = ...)]` whose packed value differs from the original, and
+//! [RelativeEye](crate::RelativeEye) records the values it narrows to `f32` with an error above
+//! [f32::EPSILON]. Conversions are aggregated per field path, such as `Material.roughness`,
+//! keeping the largest error seen. Call [end_frame] to take the record and start afresh.
+//!
+//! Recording takes a lock and unpacks every quantized value again, so the feature is meant for
+//! development builds.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mint_std140::{audit, AsStd140, Std140Bytes};
+//!
+//! #[derive(AsStd140)]
+//! struct Material {
+//!     #[std140(quantize = "unorm8")]
+//!     roughness: f32,
+//! }
+//!
+//! Material { roughness: 0.3 }.as_std140_bytes();
+//!
+//! let conversions = audit::end_frame();
+//! let roughness = conversions.iter().find(|c| c.path == "Material.roughness").unwrap();
+//! assert_eq!(roughness.value, f64::from(0.3f32));
+//! assert_eq!(roughness.stored, f64::from(77.0f32 / 255.0));
+//! assert!(roughness.max_error < 0.5 / 255.0);
+//! ```
+
+use crate::{Quantization, Std140Scalars};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use std::sync::{Mutex, MutexGuard};
+
+/// A kind of lossy conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Conversion {
+    /// A float field packed with `#[std140(quantize = ...)]`.
+    Quantized(Quantization),
+    /// An `f64` narrowed to `f32` by [RelativeEye](crate::RelativeEye), recorded when it moves by
+    /// more than [f32::EPSILON].
+    Narrowed,
+}
+
+/// The lossy conversions of one field recorded during a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossyConversion {
+    /// The name of the struct and field, such as `Material.roughness`.
+    pub path: &'static str,
+    /// How the field was converted.
+    pub conversion: Conversion,
+    /// How many times converting the field changed its value.
+    pub count: usize,
+    /// The largest absolute difference between a scalar and its converted value.
+    pub max_error: f64,
+    /// The scalar with the largest error.
+    pub value: f64,
+    /// What the shader reads for [LossyConversion::value].
+    pub stored: f64,
+}
+
+static COLLECTOR: Mutex<BTreeMap<&'static str, LossyConversion>> = Mutex::new(BTreeMap::new());
+
+fn collector() -> MutexGuard<'static, BTreeMap<&'static str, LossyConversion>> {
+    // The record stays meaningful if a recording thread panicked.
+    COLLECTOR.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Everything recorded so far in the current frame, sorted by path.
+pub fn frame() -> Vec<LossyConversion> {
+    collector().values().cloned().collect()
+}
+
+/// Take everything recorded in the current frame and start the next one.
+pub fn end_frame() -> Vec<LossyConversion> {
+    let mut collector = collector();
+    let conversions = collector.values().cloned().collect();
+    collector.clear();
+    conversions
+}
+
+/// Record that converting `value` for `path` stored `stored` instead.
+fn record(path: &'static str, conversion: Conversion, value: f64, stored: f64) {
+    let error = (value - stored).abs();
+    let mut collector = collector();
+    let entry = collector.entry(path).or_insert(LossyConversion {
+        path,
+        conversion,
+        count: 0,
+        max_error: error,
+        value,
+        stored,
+    });
+    entry.count += 1;
+    if error > entry.max_error {
+        (entry.max_error, entry.value, entry.stored) = (error, value, stored);
+    }
+}
+
+/// Record that `path` was narrowed from `f64` to `f32`, if that moved it by more than
+/// [f32::EPSILON].
+pub(crate) fn record_narrowed(path: &'static str, value: f64, stored: f32) {
+    if (value - f64::from(stored)).abs() > f64::from(f32::EPSILON) {
+        record(path, Conversion::Narrowed, value, f64::from(stored));
+    }
+}
+
+/// Record a field quantized by the derive, unpacking it only when auditing.
+#[doc(hidden)]
+#[inline]
+pub fn audit_quantized<T: Std140Scalars>(
+    path: &'static str,
+    quantization: Quantization,
+    value: &T,
+    stored: impl FnOnce() -> T,
+) {
+    let mut stored_scalars = Vec::new();
+    stored().find_scalar(&mut |scalar| {
+        stored_scalars.push(scalar);
+        false
+    });
+    let mut stored_scalars = stored_scalars.into_iter();
+    let mut worst: Option<(f64, f64)> = None;
+    value.find_scalar(&mut |scalar| {
+        let stored = stored_scalars.next().unwrap_or(0.0);
+        let error = (scalar - stored).abs();
+        if error > 0.0 && worst.is_none_or(|(value, stored)| error > (value - stored).abs()) {
+            worst = Some((scalar, stored));
+        }
+        false
+    });
+    if let Some((value, stored)) = worst {
+        record(path, Conversion::Quantized(quantization), value, stored);
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::Conversion;
+    use crate::{AsStd140, Quantization, RelativeEye, Std140Bytes};
+
+    #[derive(AsStd140)]
+    struct Audited {
+        #[std140(quantize = "half")]
+        uv: mint::Vector2<f32>,
+        #[std140(quantize = "unorm8")]
+        exact: f32,
+    }
+
+    #[test]
+    fn record() {
+        Audited { uv: [0.5, 0.1].into(), exact: 1.0 }.as_std140_bytes();
+        Audited { uv: [1.0 / 3.0, 0.5].into(), exact: 0.0 }.as_std140_bytes();
+
+        // Other tests pack concurrently, so only this test's fields are checked.
+        let conversions = super::frame();
+        assert!(conversions.iter().all(|conversion| conversion.path != "Audited.exact"));
+        let uv = conversions.iter().find(|conversion| conversion.path == "Audited.uv").unwrap();
+        assert_eq!(uv.conversion, Conversion::Quantized(Quantization::Half(2)));
+        assert_eq!(uv.count, 2);
+        assert_eq!(uv.value, f64::from(1.0f32 / 3.0));
+        assert!(uv.max_error > 0.0 && uv.max_error < 1e-3);
+
+        RelativeEye::new([0.0; 3].into()).position([1.0e9 + 0.5, 0.0, 0.0].into());
+        let conversions = super::frame();
+        let position = conversions.iter().find(|c| c.path == "RelativeEye.position").unwrap();
+        assert_eq!(position.conversion, Conversion::Narrowed);
+        assert!(position.max_error >= 0.5);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{Endian, Std140Bytes};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Packs several blocks of different types into one buffer and records where each of them
+/// lives, for binding them with `glBindBufferRange` or descriptor buffer infos afterwards.
+///
+/// Each block starts at a multiple of the alignment given at construction, which should be the
+/// device's minimum uniform buffer offset alignment.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140BlockPacker;
+///
+/// let mut packer = Std140BlockPacker::new(256);
+/// packer.push("camera", &mint::Vector4 { x: 0.0f32, y: 1.0, z: 5.0, w: 1.0 });
+/// packer.push("exposure", &1.5f32);
+///
+/// let exposure = packer.binding("exposure").unwrap();
+/// assert_eq!((exposure.offset, exposure.size), (256, 4));
+/// assert_eq!(packer.as_bytes().len(), 260);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Std140BlockPacker {
+    bytes: Vec<u8>,
+    bindings: Vec<BlockBinding>,
+    align: usize,
+    endian: Endian,
+}
+
+/// Where a block packed by a [Std140BlockPacker] lives in the buffer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlockBinding {
+    /// The name the block was pushed with.
+    pub name: String,
+    /// The offset of the block from the start of the buffer.
+    pub offset: usize,
+    /// The size of the block in bytes.
+    pub size: usize,
+}
+
+impl Std140BlockPacker {
+    /// Create an empty packer whose blocks start at multiples of `align` bytes and which packs in
+    /// the host byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16.
+    pub fn new(align: usize) -> Self {
+        Self::with_endian(align, Endian::NATIVE)
+    }
+
+    /// Create an empty packer that packs in the given byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16.
+    pub fn with_endian(align: usize, endian: Endian) -> Self {
+        assert!(align.is_power_of_two() && align >= 16, "invalid block alignment {}", align);
+        Std140BlockPacker { bytes: Vec::new(), bindings: Vec::new(), align, endian }
+    }
+
+    /// Append `value` as a block named `name` and return where it was packed.
+    pub fn push<T: Std140Bytes>(&mut self, name: impl Into<String>, value: &T) -> &BlockBinding {
+        let offset = (self.bytes.len() + self.align - 1) & !(self.align - 1);
+        self.bytes.resize(offset + T::SIZE, 0);
+        value.write_std140(&mut self.bytes[offset..], self.endian);
+        #[cfg(feature = "stats")]
+        crate::stats::record_block(core::any::type_name::<T>(), T::SIZE);
+        self.bindings.push(BlockBinding { name: name.into(), offset, size: T::SIZE });
+        self.bindings.last().unwrap()
+    }
+
+    /// The first block pushed with the given name.
+    pub fn binding(&self, name: &str) -> Option<&BlockBinding> {
+        self.bindings.iter().find(|binding| binding.name == name)
+    }
+
+    /// Every packed block, in the order they were pushed.
+    pub fn bindings(&self) -> &[BlockBinding] {
+        &self.bindings
+    }
+
+    /// The packed buffer, to upload to the GPU.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Remove every block, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.bindings.clear();
+    }
+
+    /// Split the packer into the packed buffer and its binding table.
+    pub fn into_parts(self) -> (Vec<u8>, Vec<BlockBinding>) {
+        (self.bytes, self.bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140BlockPacker;
+    use crate::Endian;
+
+    #[test]
+    fn bindings() {
+        let mut packer = Std140BlockPacker::with_endian(64, Endian::Big);
+        packer.push("a", &[1u32; 3]);
+        packer.push("b", &mint::Vector2 { x: 2u32, y: 3 });
+        packer.push("c", &4u32);
+
+        let offsets: Vec<_> =
+            packer.bindings().iter().map(|binding| (binding.offset, binding.size)).collect();
+        assert_eq!(offsets, [(0, 48), (64, 8), (128, 4)]);
+        assert!(packer.binding("d").is_none());
+
+        let (bytes, bindings) = packer.into_parts();
+        assert_eq!(&bytes[bindings[1].offset + 4..][..4], &3u32.to_be_bytes());
+        assert_eq!(&bytes[48..64], &[0; 16]);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::Std140Writer;
+use alloc::{vec, vec::Vec};
+
+/// An owned, zero-initialized byte buffer whose first byte is aligned to a configurable power of
+/// two, such as 16, 64 or 256.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AlignedStd140Buffer;
+///
+/// let mut buffer = AlignedStd140Buffer::new(32, 256);
+/// buffer.writer().write(&mint::Vector4 { x: 1.0f32, y: 2.0f32, z: 3.0f32, w: 4.0f32 });
+/// assert_eq!(buffer.as_slice().as_ptr() as usize % 256, 0);
+/// let bytes: Vec<u8> = buffer.into();
+/// assert_eq!(bytes.len(), 32);
+/// ```
+#[derive(Debug)]
+pub struct AlignedStd140Buffer {
+    storage: Vec<u8>,
+    start: usize,
+    len: usize,
+    align: usize,
+}
+
+impl AlignedStd140Buffer {
+    /// Allocate `len` zeroed bytes aligned to `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, or if `len` bytes with room for the alignment
+    /// overflow `usize`.
+    pub fn new(len: usize, align: usize) -> Self {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        let capacity = len.checked_add(align - 1).unwrap_or_else(|| {
+            panic!("a buffer of {} bytes aligned to {} overflows usize", len, align)
+        });
+        let storage = vec![0; capacity];
+        let misalignment = storage.as_ptr() as usize % align;
+        let start = (align - misalignment) % align;
+        AlignedStd140Buffer { storage, start, len, align }
+    }
+
+    /// The alignment of the first byte.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// The number of bytes in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the buffer has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bytes of the buffer.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.storage[self.start..self.start + self.len]
+    }
+
+    /// The bytes of the buffer, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.storage[self.start..self.start + self.len]
+    }
+
+    /// A writer that starts at the beginning of the buffer.
+    pub fn writer(&mut self) -> Std140Writer<'_> {
+        Std140Writer::new(self.as_mut_slice())
+    }
+
+    /// Convert the buffer to a `Vec<u8>`, which does not keep the alignment guarantee.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.storage.truncate(self.start + self.len);
+        self.storage.drain(..self.start);
+        self.storage
+    }
+}
+
+impl Clone for AlignedStd140Buffer {
+    fn clone(&self) -> Self {
+        // A cloned `Vec` can land at any address, so realign the copy.
+        let mut buffer = AlignedStd140Buffer::new(self.len, self.align);
+        buffer.as_mut_slice().copy_from_slice(self.as_slice());
+        buffer
+    }
+}
+
+impl From<AlignedStd140Buffer> for Vec<u8> {
+    fn from(buffer: AlignedStd140Buffer) -> Self {
+        buffer.into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlignedStd140Buffer;
+
+    #[test]
+    fn alignment() {
+        for &align in &[16, 64, 256] {
+            let mut buffer = AlignedStd140Buffer::new(20, align);
+            assert_eq!(buffer.as_slice().as_ptr() as usize % align, 0);
+            assert_eq!(buffer.len(), 20);
+
+            buffer.writer().write(&mint::Vector2 { x: 1u32, y: 2u32 });
+            let expected = buffer.as_slice().to_vec();
+            assert_eq!(buffer.clone().as_slice().as_ptr() as usize % align, 0);
+            assert_eq!(buffer.into_vec(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn overflow() {
+        AlignedStd140Buffer::new(usize::MAX, 16);
+    }
+}
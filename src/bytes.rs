@@ -0,0 +1,411 @@
+use crate::Std140Error;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::any::type_name;
+
+/// Byte order used when writing std140 data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Least significant byte first. This is what GPUs consume.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl Endian {
+    /// The byte order of the host.
+    pub const NATIVE: Endian =
+        if cfg!(target_endian = "big") { Endian::Big } else { Endian::Little };
+
+    pub(crate) fn write_u32(self, bytes: &mut [u8], value: u32) {
+        let value = match self {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+        bytes[..4].copy_from_slice(&value);
+    }
+
+    pub(crate) fn read_u32(self, bytes: &[u8]) -> u32 {
+        let mut value = [0; 4];
+        value.copy_from_slice(&bytes[..4]);
+        match self {
+            Endian::Little => u32::from_le_bytes(value),
+            Endian::Big => u32::from_be_bytes(value),
+        }
+    }
+
+    /// Write the low `size` bytes of `value`, for scalars narrower than 32 bits.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn write_bits(self, bytes: &mut [u8], value: u32, size: usize) {
+        match self {
+            Endian::Little => bytes[..size].copy_from_slice(&value.to_le_bytes()[..size]),
+            Endian::Big => bytes[..size].copy_from_slice(&value.to_be_bytes()[4 - size..]),
+        }
+    }
+
+    /// Read a `size` byte unsigned integer, for scalars narrower than 32 bits.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn read_bits(self, bytes: &[u8], size: usize) -> u32 {
+        let mut value = [0; 4];
+        match self {
+            Endian::Little => {
+                value[..size].copy_from_slice(&bytes[..size]);
+                u32::from_le_bytes(value)
+            }
+            Endian::Big => {
+                value[4 - size..].copy_from_slice(&bytes[..size]);
+                u32::from_be_bytes(value)
+            }
+        }
+    }
+}
+
+impl Default for Endian {
+    fn default() -> Self {
+        Endian::NATIVE
+    }
+}
+
+/// Round `size` up to the 16 byte stride of std140 array elements.
+pub(crate) const fn array_stride(size: usize) -> usize {
+    (size + 15) & !15
+}
+
+/// The std140 size of `T` in bytes, like [Std140Sized::SIZE] but callable as a function in const
+/// contexts, such as the length of a staging array.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use mint_std140::{
+///     std140_align_of, std140_array_size_of, std140_array_stride_of, std140_size_of, AsStd140,
+///     Std140Sized,
+/// };
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     radius: f32,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// const LIGHTS_RANGE: usize = 8 * std140_array_stride_of::<Light>();
+/// assert_eq!(LIGHTS_RANGE, 256);
+/// assert_eq!(std140_size_of::<mint::Vector3<f32>>(), 12);
+/// assert_eq!(std140_align_of::<mint::Vector3<f32>>(), 16);
+/// assert_eq!(std140_array_stride_of::<f32>(), 16);
+/// assert_eq!(mint::Vector2::<f32>::ARRAY_STRIDE, 16);
+/// assert_eq!(std140_array_size_of::<mint::Vector2<f32>>(3), 48);
+/// # }
+/// ```
+pub const fn std140_size_of<T: Std140Sized + ?Sized>() -> usize {
+    T::SIZE
+}
+
+/// The std140 base alignment of `T` in bytes, like [Std140Sized::ALIGN] but callable as a
+/// function in const contexts.
+pub const fn std140_align_of<T: Std140Sized + ?Sized>() -> usize {
+    T::ALIGN
+}
+
+/// The distance in bytes between consecutive elements of a std140 array of `T`, like
+/// [Std140Sized::ARRAY_STRIDE].
+pub const fn std140_array_stride_of<T: Std140Sized + ?Sized>() -> usize {
+    T::ARRAY_STRIDE
+}
+
+/// The size in bytes of a std140 array of `len` elements of `T`, such as a buffer holding the
+/// array alone. Every element occupies a whole stride, including the last.
+pub const fn std140_array_size_of<T: Std140Sized + ?Sized>(len: usize) -> usize {
+    T::ARRAY_STRIDE * len
+}
+
+/// In builds with debug assertions, panic if `bytes` is too short to hold a `T`, naming the type
+/// instead of leaving a bare slice index panic or, for unchecked writers, corrupted data.
+#[inline]
+pub fn debug_check_len<T: Std140Sized + ?Sized>(bytes: &[u8]) {
+    debug_assert!(
+        bytes.len() >= T::SIZE,
+        "writing {} needs {} bytes, but the destination is {} bytes",
+        type_name::<T>(),
+        T::SIZE,
+        bytes.len()
+    );
+}
+
+/// In builds with debug assertions, panic if `offset` bytes into a std140 block is not a valid
+/// place to write a `T`.
+#[inline]
+pub fn debug_check_offset<T: Std140Sized + ?Sized>(offset: usize) {
+    debug_assert!(
+        offset.is_multiple_of(T::ALIGN),
+        "{} at offset {} is not aligned to {} bytes",
+        type_name::<T>(),
+        offset,
+        T::ALIGN
+    );
+}
+
+/// A scalar component of a std140 value. This is public in a private module to seal
+/// [VectorComponents](crate::VectorComponents).
+pub trait Scalar: Copy {
+    /// The type of this component in std140 vector types.
+    #[cfg(feature = "std")]
+    type Component;
+
+    #[cfg(feature = "std")]
+    #[cfg(feature = "std")]
+    fn to_component(self) -> Self::Component;
+
+    fn to_bits(self) -> u32;
+
+    fn from_bits(bits: u32) -> Self;
+}
+
+impl Scalar for f32 {
+    #[cfg(feature = "std")]
+    type Component = f32;
+
+    #[cfg(feature = "std")]
+    fn to_component(self) -> f32 {
+        self
+    }
+
+    fn to_bits(self) -> u32 {
+        f32::to_bits(self)
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        f32::from_bits(bits)
+    }
+}
+
+impl Scalar for i32 {
+    #[cfg(feature = "std")]
+    type Component = i32;
+
+    #[cfg(feature = "std")]
+    fn to_component(self) -> i32 {
+        self
+    }
+
+    fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        bits as i32
+    }
+}
+
+impl Scalar for u32 {
+    #[cfg(feature = "std")]
+    type Component = u32;
+
+    #[cfg(feature = "std")]
+    fn to_component(self) -> u32 {
+        self
+    }
+
+    fn to_bits(self) -> u32 {
+        self
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        bits
+    }
+}
+
+impl Scalar for bool {
+    #[cfg(feature = "std")]
+    type Component = std140::boolean;
+
+    #[cfg(feature = "std")]
+    fn to_component(self) -> std140::boolean {
+        self.into()
+    }
+
+    fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        bits != 0
+    }
+}
+
+/// A type whose std140 representation has a statically known size and alignment.
+pub trait Std140Sized {
+    /// The size in bytes of the std140 representation. This excludes the padding that is only
+    /// added when the type is used as an array element.
+    const SIZE: usize;
+
+    /// The std140 base alignment in bytes.
+    const ALIGN: usize;
+
+    /// The distance in bytes between consecutive elements of a std140 array of this type, which
+    /// is [Std140Sized::SIZE] rounded up to 16, so 16 for a `vec2`.
+    const ARRAY_STRIDE: usize = array_stride(Self::SIZE);
+}
+
+/// A type that can be written as std140 bytes.
+pub trait Std140Bytes: Std140Sized {
+    /// Write the std140 representation to the first [`Std140Sized::SIZE`] bytes of `bytes`,
+    /// zeroing any padding in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than [`Std140Sized::SIZE`].
+    fn write_std140(&self, bytes: &mut [u8], endian: Endian);
+
+    /// Like [write_std140](Std140Bytes::write_std140), but return [Std140Error::Overflow] instead
+    /// of panicking if `bytes` is shorter than [`Std140Sized::SIZE`].
+    fn try_write_std140(&self, bytes: &mut [u8], endian: Endian) -> Result<(), Std140Error> {
+        if bytes.len() < Self::SIZE {
+            return Err(Std140Error::Overflow {
+                field: type_name::<Self>(),
+                offset: 0,
+                required: Self::SIZE,
+                available: bytes.len(),
+            });
+        }
+        self.write_std140(bytes, endian);
+        Ok(())
+    }
+
+    /// Convert this value to std140 bytes in a stack array, in the given byte order.
+    ///
+    /// `N` must be [`Std140Sized::SIZE`], which is checked at compile time. It can usually be
+    /// inferred from the destination or spelled as `{ <T as Std140Sized>::SIZE }`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mint_std140::{Endian, Std140Bytes};
+    ///
+    /// let color = mint::Vector3 { x: 1.0f32, y: 0.5, z: 0.25 };
+    /// let bytes: [u8; 12] = color.as_std140_array_bytes_with(Endian::Little);
+    /// assert_eq!(bytes[4..8], 0.5f32.to_le_bytes());
+    /// ```
+    fn as_std140_array_bytes_with<const N: usize>(&self, endian: Endian) -> [u8; N]
+    where
+        Self: Sized,
+    {
+        const { assert!(N == Self::SIZE, "the array length must be the std140 size") };
+        let mut bytes = [0; N];
+        self.write_std140(&mut bytes, endian);
+        bytes
+    }
+
+    /// Convert this value to std140 bytes in a stack array, in the host byte order.
+    fn as_std140_array_bytes<const N: usize>(&self) -> [u8; N]
+    where
+        Self: Sized,
+    {
+        self.as_std140_array_bytes_with(Endian::NATIVE)
+    }
+
+    /// Convert this value to std140 bytes in the given byte order.
+    #[cfg(feature = "alloc")]
+    fn as_std140_bytes_with(&self, endian: Endian) -> Vec<u8> {
+        let mut bytes = vec![0; Self::SIZE];
+        self.write_std140(&mut bytes, endian);
+        bytes
+    }
+
+    /// Convert this value to std140 bytes in the host byte order.
+    #[cfg(feature = "alloc")]
+    fn as_std140_bytes(&self) -> Vec<u8> {
+        self.as_std140_bytes_with(Endian::NATIVE)
+    }
+
+    /// Convert this value to little-endian std140 bytes, regardless of the host byte order.
+    #[cfg(feature = "alloc")]
+    fn as_std140_le_bytes(&self) -> Vec<u8> {
+        self.as_std140_bytes_with(Endian::Little)
+    }
+
+    /// Convert this value to big-endian std140 bytes, regardless of the host byte order.
+    #[cfg(feature = "alloc")]
+    fn as_std140_be_bytes(&self) -> Vec<u8> {
+        self.as_std140_bytes_with(Endian::Big)
+    }
+}
+
+/// A type that can be read back from std140 bytes, such as a buffer mapped after the GPU wrote
+/// it.
+pub trait FromStd140: Std140Sized + Sized {
+    /// Read a value from the first [`Std140Sized::SIZE`] bytes of `bytes`, ignoring padding.
+    ///
+    /// Derived implementations accept shorter input if the fields it does not cover have
+    /// `#[std140(default = ...)]` values, for reading data written before the struct gained
+    /// those fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is too short.
+    fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self;
+
+    /// Like [from_std140_bytes](FromStd140::from_std140_bytes), but return
+    /// [Std140Error::TooShort] instead of panicking if `bytes` is too short.
+    fn try_from_std140_bytes(bytes: &[u8], endian: Endian) -> Result<Self, Std140Error> {
+        if bytes.len() < Self::SIZE {
+            Err(Std140Error::TooShort { field: "", required: Self::SIZE, available: bytes.len() })
+        } else {
+            Ok(Self::from_std140_bytes(bytes, endian))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{Endian, Std140Bytes};
+
+    #[test]
+    fn vectors() {
+        let vector = mint::Vector3 { x: 1.0f32, y: 2.0f32, z: 3.0f32 };
+        let bytes = vector.as_std140_le_bytes();
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(&bytes[0..4], &1.0f32.to_le_bytes());
+        assert_eq!(&bytes[4..8], &2.0f32.to_le_bytes());
+        assert_eq!(&bytes[8..12], &3.0f32.to_le_bytes());
+
+        let vector = mint::Vector2 { x: -1i32, y: 2i32 };
+        let bytes = vector.as_std140_be_bytes();
+        assert_eq!(&bytes[0..4], &(-1i32).to_be_bytes());
+        assert_eq!(&bytes[4..8], &2i32.to_be_bytes());
+
+        let vector = mint::Vector4 { x: 1u32, y: 2u32, z: 3u32, w: 4u32 };
+        assert_eq!(vector.as_std140_bytes(), vector.as_std140_bytes_with(Endian::NATIVE));
+        assert_eq!(vector.as_std140_array_bytes::<16>()[..], vector.as_std140_bytes()[..]);
+    }
+
+    #[test]
+    fn matrices() {
+        let matrix = mint::ColumnMatrix3x2 {
+            x: mint::Vector3 { x: 1.0f32, y: 2.0f32, z: 3.0f32 },
+            y: mint::Vector3 { x: 4.0f32, y: 5.0f32, z: 6.0f32 },
+        };
+        let bytes = matrix.as_std140_le_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[8..12], &3.0f32.to_le_bytes());
+        assert_eq!(&bytes[12..16], &[0; 4]);
+        assert_eq!(&bytes[16..20], &4.0f32.to_le_bytes());
+        assert_eq!(&bytes[28..32], &[0; 4]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "needs 12 bytes, but the destination is 8 bytes")]
+    fn short_destination() {
+        mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 }.write_std140(&mut [0; 8], Endian::Little);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "at offset 4 is not aligned to 8 bytes")]
+    fn misaligned_offset() {
+        super::debug_check_offset::<mint::Vector2<f32>>(4);
+    }
+}
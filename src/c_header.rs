@@ -0,0 +1,219 @@
+use crate::bytes::array_stride;
+use crate::{Layout, LayoutKind, ScalarKind, Std140Layout};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Generate a C header declaring structs with the std140 layout of the struct `T` and the
+/// structs nested in it, for sharing uniform definitions with C and C++ code.
+///
+/// Members are annotated with `alignas` so the C compiler reproduces the std140 offsets, and
+/// every offset and size is checked with a static assertion. Vectors become arrays of their
+/// components, matrices become arrays of columns padded to four components and booleans become
+/// `uint32_t`, while 16-bit floats become `uint16_t` holding their bits. The elements of arrays of scalars and vectors are padded to four components, so
+/// `float h[2]` is declared as `float h[2][4]` and its second element is `h[1][0]`. Elements of
+/// 8-bit and 16-bit types are padded to 16 bytes the same way.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     enabled: bool,
+/// }
+///
+/// let header = mint_std140::c_header::<Light>();
+/// assert!(header.contains("    alignas(16) float position[3];\n    uint32_t enabled;\n"));
+/// assert!(header.contains("STD140_STATIC_ASSERT(offsetof(Light, enabled) == 12, "));
+/// ```
+pub fn c_header<T: Std140Layout>() -> String {
+    let layout = T::layout();
+    let name = match layout.kind {
+        LayoutKind::Struct { name, .. } => name,
+        _ => panic!("C headers can only be generated for structs, not {}", layout.glsl_type()),
+    };
+
+    let guard = upper_snake_case(name);
+    let mut header = String::new();
+    writeln!(header, "#ifndef {}_STD140_H\n#define {}_STD140_H\n", guard, guard).unwrap();
+    header.push_str(
+        "#include <stddef.h>\n\
+         #include <stdint.h>\n\
+         \n\
+         #ifndef STD140_STATIC_ASSERT\n\
+         #ifdef __cplusplus\n\
+         #define STD140_STATIC_ASSERT static_assert\n\
+         #else\n\
+         #include <stdalign.h>\n\
+         #define STD140_STATIC_ASSERT _Static_assert\n\
+         #endif\n\
+         #endif\n\n",
+    );
+
+    for (name, fields) in layout.struct_definitions() {
+        writeln!(header, "typedef struct {} {{", name).unwrap();
+        for (i, field) in fields.iter().enumerate() {
+            // Struct members must be 16 byte aligned, which is only implied by their first member
+            // if it has a larger alignment than its C type.
+            let align = if i == 0 { 16 } else { field.layout.align };
+            let align = if align > c_alignment(&field.layout) {
+                alloc::format!("alignas({}) ", align)
+            } else {
+                String::new()
+            };
+            writeln!(header, "    {}{};", align, c_declaration(&field.layout, field.name)).unwrap();
+        }
+        writeln!(header, "}} {};\n", name).unwrap();
+
+        for field in fields.iter() {
+            writeln!(
+                header,
+                "STD140_STATIC_ASSERT(offsetof({}, {}) == {}, \"std140 offset of {}.{}\");",
+                name, field.name, field.offset, name, field.name
+            )
+            .unwrap();
+        }
+        let end = fields.iter().map(|field| field.offset + field.layout.size).max().unwrap_or(0);
+        let size = array_stride(end);
+        writeln!(
+            header,
+            "STD140_STATIC_ASSERT(sizeof({}) == {}, \"std140 size of {}\");\n",
+            name, size, name
+        )
+        .unwrap();
+    }
+
+    writeln!(header, "#endif").unwrap();
+    header
+}
+
+/// The C declaration of a member `name` with the given std140 layout.
+fn c_declaration(layout: &Layout, name: &str) -> String {
+    let mut dimensions = String::new();
+    let mut layout = layout;
+    while let LayoutKind::Array { element, len } = &layout.kind {
+        write!(dimensions, "[{}]", len).unwrap();
+        layout = element;
+    }
+    let in_array = !dimensions.is_empty();
+
+    let (scalar, components) = match &layout.kind {
+        LayoutKind::Scalar(scalar) => (*scalar, 1),
+        LayoutKind::Vector(scalar, len) => (*scalar, *len),
+        LayoutKind::Matrix { columns, .. } => {
+            write!(dimensions, "[{}]", columns).unwrap();
+            (ScalarKind::Float, 4)
+        }
+        LayoutKind::Struct { name: struct_name, .. } => {
+            return alloc::format!("{} {}{}", struct_name, name, dimensions)
+        }
+        LayoutKind::Array { .. } => unreachable!(),
+    };
+    let components = if in_array { 16 / scalar.size() } else { components };
+    if components > 1 {
+        write!(dimensions, "[{}]", components).unwrap();
+    }
+
+    let scalar = match scalar {
+        ScalarKind::Float => "float",
+        ScalarKind::Int => "int32_t",
+        ScalarKind::Uint | ScalarKind::Bool => "uint32_t",
+        ScalarKind::Float16 | ScalarKind::Uint16 => "uint16_t",
+        ScalarKind::Int8 => "int8_t",
+        ScalarKind::Uint8 => "uint8_t",
+        ScalarKind::Int16 => "int16_t",
+    };
+    alloc::format!("{} {}{}", scalar, name, dimensions)
+}
+
+/// The alignment that the C declaration of a member with the given std140 layout has without
+/// `alignas`, or 4 bytes for structs, which are always annotated.
+fn c_alignment(layout: &Layout) -> usize {
+    match &layout.kind {
+        LayoutKind::Scalar(scalar) | LayoutKind::Vector(scalar, _) => scalar.size(),
+        LayoutKind::Array { element, .. } => c_alignment(element),
+        _ => 4,
+    }
+}
+
+fn upper_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_uppercase());
+    }
+    snake
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::c_header;
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Light {
+        intensity: f32,
+        color: mint::Vector3<f32>,
+    }
+
+    #[derive(AsStd140)]
+    struct SceneData {
+        view: mint::ColumnMatrix3x2<f32>,
+        weights: [f32; 2],
+        lights: [Light; 2],
+        count: u32,
+    }
+
+    #[test]
+    fn structs() {
+        assert_eq!(
+            c_header::<SceneData>(),
+            "#ifndef SCENE_DATA_STD140_H\n\
+             #define SCENE_DATA_STD140_H\n\
+             \n\
+             #include <stddef.h>\n\
+             #include <stdint.h>\n\
+             \n\
+             #ifndef STD140_STATIC_ASSERT\n\
+             #ifdef __cplusplus\n\
+             #define STD140_STATIC_ASSERT static_assert\n\
+             #else\n\
+             #include <stdalign.h>\n\
+             #define STD140_STATIC_ASSERT _Static_assert\n\
+             #endif\n\
+             #endif\n\
+             \n\
+             typedef struct Light {\n    \
+                 alignas(16) float intensity;\n    \
+                 alignas(16) float color[3];\n\
+             } Light;\n\
+             \n\
+             STD140_STATIC_ASSERT(offsetof(Light, intensity) == 0, \"std140 offset of Light.intensity\");\n\
+             STD140_STATIC_ASSERT(offsetof(Light, color) == 16, \"std140 offset of Light.color\");\n\
+             STD140_STATIC_ASSERT(sizeof(Light) == 32, \"std140 size of Light\");\n\
+             \n\
+             typedef struct SceneData {\n    \
+                 alignas(16) float view[2][4];\n    \
+                 alignas(16) float weights[2][4];\n    \
+                 alignas(16) Light lights[2];\n    \
+                 uint32_t count;\n\
+             } SceneData;\n\
+             \n\
+             STD140_STATIC_ASSERT(offsetof(SceneData, view) == 0, \"std140 offset of SceneData.view\");\n\
+             STD140_STATIC_ASSERT(offsetof(SceneData, weights) == 32, \"std140 offset of SceneData.weights\");\n\
+             STD140_STATIC_ASSERT(offsetof(SceneData, lights) == 64, \"std140 offset of SceneData.lights\");\n\
+             STD140_STATIC_ASSERT(offsetof(SceneData, count) == 128, \"std140 offset of SceneData.count\");\n\
+             STD140_STATIC_ASSERT(sizeof(SceneData) == 144, \"std140 size of SceneData\");\n\
+             \n\
+             #endif\n"
+        );
+    }
+}
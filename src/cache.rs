@@ -0,0 +1,144 @@
+use alloc::vec::Vec;
+
+/// Remembers a hash of the bytes last uploaded to each of a number of slots, so uploads of blocks
+/// whose contents did not change since the previous frame can be skipped.
+///
+/// Bytes are compared by a 64-bit hash rather than kept around, so the cache's memory use does
+/// not depend on block sizes. A hash collision would make a changed block look unchanged, which
+/// is vanishingly unlikely but not impossible; call [UploadCache::invalidate] to force an upload.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{Std140Bytes, UploadCache};
+///
+/// let mut cache = UploadCache::new();
+/// let material = cache.add_slot();
+///
+/// let color = mint::Vector4 { x: 1.0f32, y: 0.5, z: 0.5, w: 1.0 };
+/// assert!(cache.changed(material, &color.as_std140_bytes()));
+/// assert!(!cache.changed(material, &color.as_std140_bytes()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UploadCache {
+    slots: Vec<Option<Option<u64>>>,
+    free: Vec<usize>,
+}
+
+/// A slot of an [UploadCache], usually one per uniform block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheSlot(usize);
+
+impl CacheSlot {
+    /// The index of this slot, which is reused after the slot is removed.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl UploadCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of slots in use.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Whether no slots are in use.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add a slot. The first [UploadCache::changed] call on it always reports a change.
+    pub fn add_slot(&mut self) -> CacheSlot {
+        match self.free.pop() {
+            Some(index) => {
+                self.slots[index] = Some(None);
+                CacheSlot(index)
+            }
+            None => {
+                self.slots.push(Some(None));
+                CacheSlot(self.slots.len() - 1)
+            }
+        }
+    }
+
+    /// Remove a slot so its index can be reused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slot was already removed.
+    pub fn remove_slot(&mut self, slot: CacheSlot) {
+        assert!(self.slots[slot.0].take().is_some(), "cache slot {} was already removed", slot.0);
+        self.free.push(slot.0);
+    }
+
+    /// Record `bytes` as the contents of `slot` and return whether they differ from the bytes
+    /// recorded last time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slot was removed.
+    pub fn changed(&mut self, slot: CacheSlot, bytes: &[u8]) -> bool {
+        let hash = Some(fnv1a(bytes));
+        let recorded = self.slots[slot.0].as_mut().expect("cache slot was removed");
+        let changed = *recorded != hash;
+        *recorded = hash;
+        changed
+    }
+
+    /// Forget the contents of `slot`, so the next [UploadCache::changed] call reports a change.
+    pub fn invalidate(&mut self, slot: CacheSlot) {
+        if let Some(recorded) = &mut self.slots[slot.0] {
+            *recorded = None;
+        }
+    }
+
+    /// Forget the contents of every slot, for example after the GPU buffers were recreated.
+    pub fn invalidate_all(&mut self) {
+        for recorded in self.slots.iter_mut().flatten() {
+            *recorded = None;
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, mixed with their length.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64 ^ bytes.len() as u64;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UploadCache;
+
+    #[test]
+    fn slots() {
+        let mut cache = UploadCache::new();
+        let a = cache.add_slot();
+        let b = cache.add_slot();
+        assert!(cache.changed(a, &[1, 2, 3, 4]));
+        assert!(cache.changed(b, &[1, 2, 3, 4]));
+        assert!(!cache.changed(a, &[1, 2, 3, 4]));
+        assert!(cache.changed(a, &[1, 2, 3, 5]));
+        assert!(cache.changed(a, &[1, 2, 3, 5, 0]));
+
+        cache.invalidate(b);
+        assert!(cache.changed(b, &[1, 2, 3, 4]));
+        cache.invalidate_all();
+        assert!(cache.changed(a, &[1, 2, 3, 5, 0]));
+
+        cache.remove_slot(a);
+        assert_eq!(cache.len(), 1);
+        let c = cache.add_slot();
+        assert_eq!(c.index(), a.index());
+        assert!(cache.changed(c, &[1, 2, 3, 5, 0]));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::bytes::array_stride;
+use crate::{Endian, Std140Bytes};
+use alloc::{vec, vec::Vec};
+
+/// The three arrays of a clustered or tiled data set packed by [pack_clusters], each a valid
+/// std140 array that can be bound as its own block or combined into one.
+///
+/// The arrays match
+///
+/// ```glsl
+/// // Cell i has count cells[i / 2][i % 2 * 2 + 1] indices starting at cells[i / 2][i % 2 * 2].
+/// uvec4 cells[(CELL_COUNT + 1) / 2];
+/// // Index j is indices[j / 4][j % 4].
+/// uvec4 indices[(INDEX_COUNT + 3) / 4];
+/// Payload payload[PAYLOAD_COUNT];
+/// ```
+///
+/// where the counts are those passed to [pack_clusters]. Cells and indices are packed four
+/// `uint`s to a `uvec4`, since a std140 array of `uint` would pad every element to 16 bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClusterBlocks {
+    /// The `(offset, count)` pair of each cell, where `offset` is the position of its first index.
+    pub cells: Vec<u8>,
+    /// The payload indices of all cells, cell after cell.
+    pub indices: Vec<u8>,
+    /// The payload array.
+    pub payload: Vec<u8>,
+}
+
+/// The offsets of the arrays in a block made by [ClusterBlocks::combined].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClusterOffsets {
+    /// The offset of the cell array.
+    pub cells: usize,
+    /// The offset of the index array.
+    pub indices: usize,
+    /// The offset of the payload array.
+    pub payload: usize,
+}
+
+impl ClusterBlocks {
+    /// Concatenate the three arrays into one block, for a shader that declares them as members of
+    /// a single uniform block in order.
+    pub fn combined(&self) -> (Vec<u8>, ClusterOffsets) {
+        let offsets = ClusterOffsets {
+            cells: 0,
+            indices: self.cells.len(),
+            payload: self.cells.len() + self.indices.len(),
+        };
+        ([&self.cells[..], &self.indices[..], &self.payload[..]].concat(), offsets)
+    }
+}
+
+/// Pack a header grid of cells, each listing payload elements by index, in the host byte order.
+///
+/// # Panics
+///
+/// Panics if a cell lists an index past the end of `payload`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::pack_clusters;
+///
+/// let lights = [mint::Vector4 { x: 0.0f32, y: 0.0, z: 0.0, w: 5.0 }; 3];
+/// let cells = [vec![0, 2], vec![], vec![1, 2, 0]];
+///
+/// let blocks = pack_clusters(&cells, &lights);
+/// assert_eq!(blocks.cells.len(), 32);
+/// // The third cell starts at index 2 and holds 3 indices.
+/// assert_eq!(&blocks.cells[16..20], &2u32.to_ne_bytes());
+/// assert_eq!(&blocks.cells[20..24], &3u32.to_ne_bytes());
+/// assert_eq!(blocks.indices.len(), 32);
+///
+/// let (block, offsets) = blocks.combined();
+/// assert_eq!((offsets.indices, offsets.payload), (32, 64));
+/// assert_eq!(block.len(), 64 + 48);
+/// ```
+pub fn pack_clusters<I: AsRef<[u32]>, T: Std140Bytes>(cells: &[I], payload: &[T]) -> ClusterBlocks {
+    pack_clusters_with(cells, payload, Endian::NATIVE)
+}
+
+/// Pack cells and their payload like [pack_clusters], in the given byte order.
+pub fn pack_clusters_with<I: AsRef<[u32]>, T: Std140Bytes>(
+    cells: &[I],
+    payload: &[T],
+    endian: Endian,
+) -> ClusterBlocks {
+    let mut header = Vec::with_capacity(cells.len() * 2);
+    let mut indices = Vec::new();
+    for cell in cells {
+        let cell = cell.as_ref();
+        if let Some(&index) = cell.iter().find(|&&index| index as usize >= payload.len()) {
+            panic!(
+                "cluster index {} is out of bounds for {} payload elements",
+                index,
+                payload.len()
+            );
+        }
+        header.extend([indices.len() as u32, cell.len() as u32]);
+        indices.extend_from_slice(cell);
+    }
+
+    let stride = array_stride(T::SIZE);
+    let mut payload_bytes = vec![0; stride * payload.len()];
+    for (value, element) in payload.iter().zip(payload_bytes.chunks_exact_mut(stride)) {
+        value.write_std140(element, endian);
+    }
+    ClusterBlocks {
+        cells: pack_uints(&header, endian),
+        indices: pack_uints(&indices, endian),
+        payload: payload_bytes,
+    }
+}
+
+/// Pack `values` four to a `uvec4`, zero-filling the last vector.
+fn pack_uints(values: &[u32], endian: Endian) -> Vec<u8> {
+    let mut bytes = vec![0; values.len().div_ceil(4) * 16];
+    for (value, element) in values.iter().zip(bytes.chunks_exact_mut(4)) {
+        endian.write_u32(element, *value);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pack_clusters_with;
+    use crate::Endian;
+
+    #[test]
+    fn layout() {
+        let cells: [&[u32]; 3] = [&[1], &[0, 1, 1, 0, 1], &[]];
+        let blocks = pack_clusters_with(&cells, &[7u32, 8], Endian::Big);
+
+        let uints = |bytes: &[u8]| -> Vec<u32> {
+            bytes.chunks_exact(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).collect()
+        };
+        assert_eq!(uints(&blocks.cells), [0, 1, 1, 5, 6, 0, 0, 0]);
+        assert_eq!(uints(&blocks.indices), [1, 0, 1, 1, 0, 1, 0, 0]);
+        assert_eq!(uints(&blocks.payload), [7, 0, 0, 0, 8, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cluster index 2 is out of bounds for 2 payload elements")]
+    fn out_of_bounds() {
+        pack_clusters_with(&[[2u32]], &[0.0f32; 2], Endian::Little);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::bytes::Scalar;
+#[cfg(feature = "std")]
+use crate::AsStd140;
+use crate::{FromStd140, Std140Bytes};
+#[cfg(feature = "std")]
+use std140::*;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A scalar type that mint vectors can hold in std140: `f32`, `i32`, `u32` or `bool`.
+///
+/// The vector impls of this crate are written once over this trait, so a new scalar type only
+/// needs an impl here. The trait is sealed, and its std140 types need the `std` feature.
+pub trait VectorComponents: Scalar {
+    /// The std140 type of a two component vector, such as `vec2`.
+    #[cfg(feature = "std")]
+    type Vec2;
+    /// The std140 type of a three component vector, such as `vec3`.
+    #[cfg(feature = "std")]
+    type Vec3;
+    /// The std140 type of a four component vector, such as `vec4`.
+    #[cfg(feature = "std")]
+    type Vec4;
+
+    /// Build a two component std140 vector.
+    #[cfg(feature = "std")]
+    fn vec2(x: Self, y: Self) -> Self::Vec2;
+
+    /// Build a three component std140 vector.
+    #[cfg(feature = "std")]
+    fn vec3(x: Self, y: Self, z: Self) -> Self::Vec3;
+
+    /// Build a four component std140 vector.
+    #[cfg(feature = "std")]
+    fn vec4(x: Self, y: Self, z: Self, w: Self) -> Self::Vec4;
+}
+
+macro_rules! impl_vector_components {
+    ($rust_type:ty, $vec2:ident, $vec3:ident, $vec4:ident) => {
+        impl VectorComponents for $rust_type {
+            #[cfg(feature = "std")]
+            type Vec2 = $vec2;
+            #[cfg(feature = "std")]
+            type Vec3 = $vec3;
+            #[cfg(feature = "std")]
+            type Vec4 = $vec4;
+
+            #[cfg(feature = "std")]
+            fn vec2(x: Self, y: Self) -> $vec2 {
+                $vec2(x.to_component(), y.to_component())
+            }
+
+            #[cfg(feature = "std")]
+            fn vec3(x: Self, y: Self, z: Self) -> $vec3 {
+                $vec3(x.to_component(), y.to_component(), z.to_component())
+            }
+
+            #[cfg(feature = "std")]
+            fn vec4(x: Self, y: Self, z: Self, w: Self) -> $vec4 {
+                $vec4(x.to_component(), y.to_component(), z.to_component(), w.to_component())
+            }
+        }
+    };
+}
+
+impl_vector_components!(f32, vec2, vec3, vec4);
+impl_vector_components!(i32, ivec2, ivec3, ivec4);
+impl_vector_components!(u32, uvec2, uvec3, uvec4);
+impl_vector_components!(bool, bvec2, bvec3, bvec4);
+
+/// A mint vector type that can be a column of a std140 matrix: `Vector2<f32>`, `Vector3<f32>` or
+/// `Vector4<f32>`.
+///
+/// The matrix impls of this crate are written once over this trait. The trait is sealed, and its
+/// std140 types need the `std` feature.
+pub trait MatrixColumns: Std140Bytes + FromStd140 + Copy + sealed::Sealed {
+    /// The std140 type of a matrix with two of these columns, such as `mat2x3`.
+    #[cfg(feature = "std")]
+    type Mat2;
+    /// The std140 type of a matrix with three of these columns, such as `mat3`.
+    #[cfg(feature = "std")]
+    type Mat3;
+    /// The std140 type of a matrix with four of these columns, such as `mat4x3`.
+    #[cfg(feature = "std")]
+    type Mat4;
+
+    /// Build a std140 matrix with two columns.
+    #[cfg(feature = "std")]
+    fn mat2(x: Self, y: Self) -> Self::Mat2;
+
+    /// Build a std140 matrix with three columns.
+    #[cfg(feature = "std")]
+    fn mat3(x: Self, y: Self, z: Self) -> Self::Mat3;
+
+    /// Build a std140 matrix with four columns.
+    #[cfg(feature = "std")]
+    fn mat4(x: Self, y: Self, z: Self, w: Self) -> Self::Mat4;
+}
+
+macro_rules! impl_matrix_columns {
+    ($mint_type:ty, $mat2:ident, $mat3:ident, $mat4:ident) => {
+        impl sealed::Sealed for $mint_type {}
+
+        impl MatrixColumns for $mint_type {
+            #[cfg(feature = "std")]
+            type Mat2 = $mat2;
+            #[cfg(feature = "std")]
+            type Mat3 = $mat3;
+            #[cfg(feature = "std")]
+            type Mat4 = $mat4;
+
+            #[cfg(feature = "std")]
+            fn mat2(x: Self, y: Self) -> $mat2 {
+                $mat2(x.as_std140(), y.as_std140())
+            }
+
+            #[cfg(feature = "std")]
+            fn mat3(x: Self, y: Self, z: Self) -> $mat3 {
+                $mat3(x.as_std140(), y.as_std140(), z.as_std140())
+            }
+
+            #[cfg(feature = "std")]
+            fn mat4(x: Self, y: Self, z: Self, w: Self) -> $mat4 {
+                $mat4(x.as_std140(), y.as_std140(), z.as_std140(), w.as_std140())
+            }
+        }
+    };
+}
+
+impl_matrix_columns!(mint::Vector2<f32>, mat2x2, mat3x2, mat4x2);
+impl_matrix_columns!(mint::Vector3<f32>, mat2x3, mat3x3, mat4x3);
+impl_matrix_columns!(mint::Vector4<f32>, mat2x4, mat3x4, mat4x4);
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{AsStd140, Endian, FromStd140, Std140Bytes, Std140Sized, VectorComponents};
+
+    fn round_trip<T: VectorComponents + PartialEq + core::fmt::Debug>(x: T, y: T, z: T) {
+        let vector = mint::Vector3 { x, y, z };
+        let mut bytes = [0u8; 16];
+        vector.write_std140(&mut bytes, Endian::Little);
+        assert_eq!(mint::Vector3::<T>::from_std140_bytes(&bytes, Endian::Little), vector);
+        assert_eq!(mint::Vector3::<T>::ALIGN, 16);
+    }
+
+    #[test]
+    fn generic() {
+        round_trip(1.0f32, 2.0, 3.0);
+        round_trip(-1i32, 0, 1);
+        round_trip(1u32, 2, 3);
+        round_trip(true, false, true);
+
+        let columns = mint::ColumnMatrix2x3 {
+            x: mint::Vector2 { x: 1.0f32, y: 2.0 },
+            y: mint::Vector2 { x: 3.0, y: 4.0 },
+            z: mint::Vector2 { x: 5.0, y: 6.0 },
+        };
+        let _: std140::mat3x2 = columns.as_std140();
+        assert_eq!(mint::ColumnMatrix2x3::<f32>::SIZE, 48);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::bytes::debug_check_offset;
+use crate::{AlignedStd140Buffer, Endian, Handle, Std140Bytes, Std140Error};
+use core::any::type_name;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{fmt, slice};
+
+/// A fixed-capacity byte region that several threads pack std140 blocks into at once, without
+/// locking.
+///
+/// Each thread reserves an [ArenaChunk] of the region with a single atomic update and packs its
+/// blocks into it like into a [Std140Arena](crate::Std140Arena), returning [Handle]s whose offsets
+/// are relative to the whole region. Once every chunk is dropped, the region can be uploaded as a
+/// single frame buffer. Blocks and chunks start at multiples of the arena's alignment.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140ConcurrentArena;
+///
+/// let mut arena = Std140ConcurrentArena::new(4096, 256);
+/// let handles: Vec<_> = std::thread::scope(|scope| {
+///     let arena = &arena;
+///     let threads: Vec<_> = (0..4)
+///         .map(|thread| {
+///             scope.spawn(move || {
+///                 let mut chunk = arena.chunk().unwrap();
+///                 chunk.alloc(&mint::Vector2 { x: thread as f32, y: 1.0 }).unwrap()
+///             })
+///         })
+///         .collect();
+///     threads.into_iter().map(|thread| thread.join().unwrap()).collect()
+/// });
+///
+/// assert_eq!(arena.reserved(), 1024);
+/// let mut offsets: Vec<_> = handles.iter().map(|handle| handle.offset()).collect();
+/// offsets.sort();
+/// assert_eq!(offsets, [0, 256, 512, 768]);
+/// assert_eq!(arena.as_bytes()[handles[2].offset()..][..4], 2.0f32.to_ne_bytes());
+/// ```
+pub struct Std140ConcurrentArena {
+    /// Owns the region, which is only accessed through `base` after construction. Its bytes must
+    /// never be read through `&self`, since chunks may be writing to them.
+    storage: AlignedStd140Buffer,
+    base: *mut u8,
+    capacity: usize,
+    chunk_size: usize,
+    /// The offset of the next chunk, which stops at `capacity` once the arena is full.
+    next: AtomicUsize,
+    endian: Endian,
+}
+
+// SAFETY: The arena owns its region, and threads sharing it only ever get disjoint byte ranges of
+// it, each reserved once by an atomic update of `next`. Nothing reachable from `&self` reads the
+// bytes of the region.
+unsafe impl Send for Std140ConcurrentArena {}
+unsafe impl Sync for Std140ConcurrentArena {}
+
+// Written by hand so that formatting a shared arena never reads bytes that chunks are writing.
+impl fmt::Debug for Std140ConcurrentArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Std140ConcurrentArena")
+            .field("capacity", &self.capacity)
+            .field("chunk_size", &self.chunk_size)
+            .field("align", &self.align())
+            .field("reserved", &self.reserved())
+            .finish()
+    }
+}
+
+/// A part of a [Std140ConcurrentArena] reserved by one thread, which packs blocks into it.
+#[derive(Debug)]
+pub struct ArenaChunk<'a> {
+    bytes: &'a mut [u8],
+    offset: usize,
+    used: usize,
+    align: usize,
+    endian: Endian,
+}
+
+impl Std140ConcurrentArena {
+    /// Create an arena of `capacity` zeroed bytes that hands out chunks of `chunk_size` bytes and
+    /// packs in the host byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is not a multiple of 16, or is 0.
+    pub fn new(capacity: usize, chunk_size: usize) -> Self {
+        Self::with_alignment(capacity, chunk_size, 16, Endian::NATIVE)
+    }
+
+    /// Create an arena of `capacity` zeroed bytes whose chunks and blocks start at multiples of
+    /// `align` bytes, such as 64 to keep the chunks of different threads on separate cache lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16, or if `chunk_size` is not a
+    /// multiple of `align`, or is 0.
+    pub fn with_alignment(
+        capacity: usize,
+        chunk_size: usize,
+        align: usize,
+        endian: Endian,
+    ) -> Self {
+        assert!(align.is_power_of_two() && align >= 16, "invalid block alignment {}", align);
+        assert!(
+            chunk_size > 0 && chunk_size.is_multiple_of(align),
+            "chunk size {} is not a multiple of the alignment {}",
+            chunk_size,
+            align
+        );
+        let mut storage = AlignedStd140Buffer::new(capacity, align);
+        let base = storage.as_mut_slice().as_mut_ptr();
+        Std140ConcurrentArena {
+            storage,
+            base,
+            capacity,
+            chunk_size,
+            next: AtomicUsize::new(0),
+            endian,
+        }
+    }
+
+    /// The total number of bytes in the arena.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The alignment of the start of every chunk and block.
+    pub fn align(&self) -> usize {
+        self.storage.align()
+    }
+
+    /// The number of bytes handed out in chunks so far.
+    pub fn reserved(&self) -> usize {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    /// Reserve the next chunk, which is shorter than the chunk size if it is the last one, or
+    /// return `None` if the arena is full.
+    pub fn chunk(&self) -> Option<ArenaChunk<'_>> {
+        // Stop at the capacity, so that `next` cannot wrap around once the arena is full.
+        let offset = self
+            .next
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |offset| {
+                let end = offset
+                    .checked_add(self.chunk_size)
+                    .map_or(self.capacity, |end| end.min(self.capacity));
+                (offset < self.capacity).then_some(end)
+            })
+            .ok()?;
+        let len = self.chunk_size.min(self.capacity - offset);
+        #[cfg(feature = "stats")]
+        crate::stats::record_arena_usage(offset + len);
+        // SAFETY: The range is inside the region, and no other chunk covers it since `next` only
+        // grows up to the capacity. `as_bytes` and `reset` take `&mut self`, so they cannot run while it is borrowed.
+        let bytes = unsafe { slice::from_raw_parts_mut(self.base.add(offset), len) };
+        Some(ArenaChunk { bytes, offset, used: 0, align: self.align(), endian: self.endian })
+    }
+
+    /// The packed bytes of the whole arena, including unused capacity, once no chunk is left.
+    pub fn as_bytes(&mut self) -> &[u8] {
+        // SAFETY: `&mut self` guarantees that no chunk borrows the region any more.
+        unsafe { slice::from_raw_parts(self.base, self.capacity) }
+    }
+
+    /// Zero everything handed out and start reserving chunks from the beginning again.
+    pub fn reset(&mut self) {
+        let reserved = self.reserved();
+        // SAFETY: `&mut self` guarantees that no chunk borrows the region any more.
+        unsafe { slice::from_raw_parts_mut(self.base, reserved) }.fill(0);
+        *self.next.get_mut() = 0;
+    }
+}
+
+impl ArenaChunk<'_> {
+    /// The offset of the chunk from the start of the arena.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The number of bytes in the chunk.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the chunk has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The number of bytes up to the end of the last block in the chunk.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Pack `value` into a new block, or return [Std140Error::Overflow] with offsets relative to
+    /// the chunk if it is full, in which case the caller should reserve another chunk.
+    pub fn alloc<T: Std140Bytes>(&mut self, value: &T) -> Result<Handle<T>, Std140Error> {
+        let offset = self.used.next_multiple_of(self.align);
+        if offset + T::SIZE > self.bytes.len() {
+            return Err(Std140Error::Overflow {
+                field: type_name::<T>(),
+                offset,
+                required: offset + T::SIZE,
+                available: self.bytes.len(),
+            });
+        }
+        debug_check_offset::<T>(offset);
+        value.write_std140(&mut self.bytes[offset..], self.endian);
+        // Padding after a previous block is still zero, since chunk bytes are only written once.
+        self.used = offset + T::SIZE;
+        #[cfg(feature = "stats")]
+        crate::stats::record_block(type_name::<T>(), T::SIZE);
+        Ok(Handle::at(self.offset + offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140ConcurrentArena;
+    use crate::{Endian, Std140Error};
+
+    #[test]
+    fn debug() {
+        let arena = Std140ConcurrentArena::with_alignment(320, 128, 64, Endian::Little);
+        let mut chunk = arena.chunk().unwrap();
+        assert_eq!(
+            format!("{:?}", arena),
+            "Std140ConcurrentArena { capacity: 320, chunk_size: 128, align: 64, reserved: 128 }"
+        );
+        assert!(chunk.alloc(&1u32).is_ok());
+    }
+
+    #[test]
+    fn chunks() {
+        let mut arena = Std140ConcurrentArena::with_alignment(320, 128, 64, Endian::Little);
+        let mut a = arena.chunk().unwrap();
+        let mut b = arena.chunk().unwrap();
+        let c = arena.chunk().unwrap();
+        assert!(arena.chunk().is_none());
+        assert!(arena.chunk().is_none());
+        assert_eq!(arena.reserved(), 320);
+        assert_eq!((b.offset(), c.offset(), c.len()), (128, 256, 64));
+
+        let first = a.alloc(&1u32).unwrap();
+        let second = a.alloc(&mint::Vector4 { x: 2u32, y: 3, z: 4, w: 5 }).unwrap();
+        assert_eq!((first.offset(), second.offset()), (0, 64));
+        assert!(matches!(
+            a.alloc(&4u32),
+            Err(Std140Error::Overflow { offset: 128, required: 132, available: 128, .. })
+        ));
+        assert_eq!(b.alloc(&6u32).unwrap().offset(), 128);
+
+        assert_eq!(arena.reserved(), 320);
+        assert_eq!(arena.as_bytes()[76..80], 5u32.to_le_bytes());
+        assert_eq!(arena.as_bytes()[128..132], 6u32.to_le_bytes());
+        arena.reset();
+        assert!(arena.as_bytes().iter().all(|&byte| byte == 0));
+        assert_eq!(arena.chunk().unwrap().offset(), 0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::bytes::{array_stride, debug_check_len};
+use crate::{Endian, Std140Bytes, Std140Sized};
+
+/// A bounded dynamic array in a uniform block: an element count followed by a fixed-length array
+/// of `MAX` elements, matching
+///
+/// ```glsl
+/// layout(std140) uniform Lights {
+///     uint count;
+///     Light lights[MAX];
+/// };
+/// ```
+///
+/// Writing it stores the count, packs the first `MAX` elements with the std140 array stride and
+/// zero-fills the unused elements. Elements past `MAX` are dropped, and the count is that of the
+/// packed elements.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{CountedArray, Endian, Std140Bytes, Std140Sized};
+///
+/// let lights = [mint::Vector4 { x: 1.0f32, y: 1.0, z: 1.0, w: 8.0 }; 3];
+/// let block = CountedArray::<_, 16>::new(&lights);
+/// assert_eq!(CountedArray::<mint::Vector4<f32>, 16>::SIZE, 16 + 16 * 16);
+///
+/// let mut bytes = [0xff; 16 + 16 * 16];
+/// block.write_std140(&mut bytes, Endian::Little);
+/// assert_eq!(&bytes[0..4], &3u32.to_le_bytes());
+/// assert_eq!(&bytes[16 + 2 * 16 + 12..16 + 3 * 16], &8.0f32.to_le_bytes());
+/// assert!(bytes[16 + 3 * 16..].iter().all(|&byte| byte == 0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountedArray<'a, T, const MAX: usize> {
+    elements: &'a [T],
+}
+
+impl<'a, T: Std140Sized, const MAX: usize> CountedArray<'a, T, MAX> {
+    /// The offset of the element array, after the `uint` count.
+    pub const ELEMENTS_OFFSET: usize = 16;
+
+    /// The distance in bytes between consecutive elements.
+    pub const STRIDE: usize = array_stride(T::SIZE);
+
+    /// Wrap `elements` for writing as a counted array.
+    pub fn new(elements: &'a [T]) -> Self {
+        CountedArray { elements }
+    }
+
+    /// The elements that are packed, which are at most the first `MAX`.
+    pub fn elements(&self) -> &'a [T] {
+        &self.elements[..self.elements.len().min(MAX)]
+    }
+
+    /// Whether some elements are dropped because there are more than `MAX`.
+    pub fn is_truncated(&self) -> bool {
+        self.elements.len() > MAX
+    }
+}
+
+impl<T: Std140Sized, const MAX: usize> Std140Sized for CountedArray<'_, T, MAX> {
+    const SIZE: usize = Self::ELEMENTS_OFFSET + Self::STRIDE * MAX;
+    const ALIGN: usize = 16;
+}
+
+impl<T: Std140Bytes, const MAX: usize> Std140Bytes for CountedArray<'_, T, MAX> {
+    fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+        debug_check_len::<Self>(bytes);
+        let bytes = &mut bytes[..Self::SIZE];
+        bytes.fill(0);
+        let elements = self.elements();
+        (elements.len() as u32).write_std140(bytes, endian);
+        for (i, element) in elements.iter().enumerate() {
+            element.write_std140(&mut bytes[Self::ELEMENTS_OFFSET + i * Self::STRIDE..], endian);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CountedArray;
+    use crate::{Endian, Std140Bytes, Std140Sized};
+
+    #[test]
+    fn truncated() {
+        let values = [1.0f32, 2.0, 3.0];
+        let array = CountedArray::<_, 2>::new(&values);
+        assert!(array.is_truncated());
+        assert_eq!(CountedArray::<f32, 2>::SIZE, 48);
+
+        let mut bytes = [0xff; 48];
+        array.write_std140(&mut bytes, Endian::Big);
+        assert_eq!(&bytes[0..4], &2u32.to_be_bytes());
+        assert_eq!(&bytes[4..16], &[0; 12]);
+        assert_eq!(&bytes[32..36], &2.0f32.to_be_bytes());
+        assert_eq!(&bytes[36..48], &[0; 12]);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{Endian, Std140Bytes, Std140Layout, Std140Value};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A scalar that differs between two std140 buffers of the same type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDiff {
+    /// The path to the scalar, such as `lights[1].color.y`.
+    pub path: String,
+    /// The offset of the scalar from the start of the buffer.
+    pub offset: usize,
+    /// The value in the first buffer.
+    pub old: Std140Value,
+    /// The value in the second buffer.
+    pub new: Std140Value,
+}
+
+impl FieldDiff {
+    /// How much the value changed from the first buffer to the second.
+    pub fn delta(&self) -> f64 {
+        self.new.to_f64() - self.old.to_f64()
+    }
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (offset {}): {} -> {} ({:+})",
+            self.path,
+            self.offset,
+            self.old,
+            self.new,
+            self.delta()
+        )
+    }
+}
+
+/// Compare two buffers holding the std140 representation of `T` and report every scalar that
+/// differs. Floats are considered equal if they differ by at most `tolerance`.
+///
+/// # Panics
+///
+/// Panics if either buffer is shorter than [`Std140Sized::SIZE`](crate::Std140Sized::SIZE).
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{diff_fields, Endian, Std140Bytes};
+///
+/// let old = mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 };
+/// let new = mint::Vector3 { x: 1.0f32, y: 2.5, z: 3.0 };
+/// let diffs = diff_fields::<mint::Vector3<f32>>(
+///     &old.as_std140_le_bytes(),
+///     &new.as_std140_le_bytes(),
+///     Endian::Little,
+///     1e-6,
+/// );
+/// assert_eq!(diffs.len(), 1);
+/// assert_eq!(diffs[0].path, "y");
+/// assert_eq!(diffs[0].delta(), 0.5);
+/// ```
+pub fn diff_fields<T: Std140Layout>(
+    old: &[u8],
+    new: &[u8],
+    endian: Endian,
+    tolerance: f32,
+) -> Vec<FieldDiff> {
+    diff_fields_by::<T>(old, new, endian, |old, new| (old - new).abs() <= tolerance)
+}
+
+/// Compare two buffers holding the std140 representation of `T` like [diff_fields], but consider
+/// floats equal if they differ by at most `epsilon` or by at most `max_relative` times the larger
+/// of the two, like `approx::relative_eq!`.
+///
+/// [assert_std140_relative_eq!](crate::assert_std140_relative_eq) wraps this for tests.
+///
+/// # Panics
+///
+/// Panics if either buffer is shorter than [`Std140Sized::SIZE`](crate::Std140Sized::SIZE).
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{relative_diff_fields, Endian, Std140Bytes};
+///
+/// let old = mint::Vector2 { x: 1000.0f32, y: 0.001 };
+/// let new = mint::Vector2 { x: 1000.01f32, y: 0.002 };
+/// let diffs = relative_diff_fields::<mint::Vector2<f32>>(
+///     &old.as_std140_le_bytes(),
+///     &new.as_std140_le_bytes(),
+///     Endian::Little,
+///     1e-6,
+///     1e-4,
+/// );
+/// assert_eq!(diffs.len(), 1);
+/// assert_eq!(diffs[0].path, "y");
+/// ```
+pub fn relative_diff_fields<T: Std140Layout>(
+    old: &[u8],
+    new: &[u8],
+    endian: Endian,
+    epsilon: f32,
+    max_relative: f32,
+) -> Vec<FieldDiff> {
+    diff_fields_by::<T>(old, new, endian, |old, new| {
+        let difference = (old - new).abs();
+        old == new || difference <= epsilon || difference <= old.abs().max(new.abs()) * max_relative
+    })
+}
+
+/// Assert that a value or a std140 buffer relatively equals another std140 buffer, scalar by
+/// scalar, with floats compared like [relative_diff_fields] and every other scalar exactly.
+///
+/// `assert_std140_relative_eq!(value, bytes)` packs `value` and compares it with `bytes`, and
+/// `assert_std140_relative_eq!(<T> expected, actual)` compares two buffers holding a `T`. Buffers
+/// are in the host byte order. The tolerances default to `f32::EPSILON` and can be set with
+/// trailing `epsilon = ...` and `max_relative = ...` arguments, in that order. On failure, the
+/// panic message lists the path and both values of every scalar that differs.
+///
+/// # Panics
+///
+/// Panics if the buffers differ, or if a buffer is shorter than `T`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{assert_std140_relative_eq, AsStd140, Std140Bytes};
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     enabled: bool,
+/// }
+///
+/// let light = Light { position: [0.1, 0.2, 0.3].into(), enabled: true };
+/// let bytes = light.as_std140_bytes();
+/// let roundtrip = Light { position: [0.1, 0.2, 0.1 + 0.2].into(), enabled: true };
+///
+/// assert_std140_relative_eq!(roundtrip, bytes, epsilon = 1e-6);
+/// assert_std140_relative_eq!(<Light> bytes, roundtrip.as_std140_bytes(), max_relative = 1e-5);
+/// ```
+#[macro_export]
+macro_rules! assert_std140_relative_eq {
+    (@or $value:expr) => {
+        $value
+    };
+    (@or) => {
+        f32::EPSILON
+    };
+    (<$ty:ty> $expected:expr, $actual:expr $(, epsilon = $epsilon:expr)? $(, max_relative = $max_relative:expr)? $(,)?) => {
+        $crate::__private::assert_relative_eq::<$ty>(
+            &$expected[..],
+            &$actual[..],
+            $crate::assert_std140_relative_eq!(@or $($epsilon)?),
+            $crate::assert_std140_relative_eq!(@or $($max_relative)?),
+        )
+    };
+    ($expected:expr, $actual:expr $(, epsilon = $epsilon:expr)? $(, max_relative = $max_relative:expr)? $(,)?) => {
+        $crate::__private::assert_value_relative_eq(
+            &$expected,
+            &$actual[..],
+            $crate::assert_std140_relative_eq!(@or $($epsilon)?),
+            $crate::assert_std140_relative_eq!(@or $($max_relative)?),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_relative_eq<T: Std140Layout>(
+    expected: &[u8],
+    actual: &[u8],
+    epsilon: f32,
+    max_relative: f32,
+) {
+    let diffs = relative_diff_fields::<T>(expected, actual, Endian::NATIVE, epsilon, max_relative);
+    if !diffs.is_empty() {
+        let mut message = String::new();
+        for diff in &diffs {
+            message.push_str(&format!("\n    {}", diff));
+        }
+        panic!(
+            "std140 values are not relatively equal (epsilon = {:?}, max_relative = {:?}):{}",
+            epsilon, max_relative, message
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_value_relative_eq<T: Std140Bytes + Std140Layout>(
+    expected: &T,
+    actual: &[u8],
+    epsilon: f32,
+    max_relative: f32,
+) {
+    assert_relative_eq::<T>(&expected.as_std140_bytes(), actual, epsilon, max_relative);
+}
+
+fn diff_fields_by<T: Std140Layout>(
+    old: &[u8],
+    new: &[u8],
+    endian: Endian,
+    floats_equal: impl Fn(f32, f32) -> bool,
+) -> Vec<FieldDiff> {
+    let old = &old[..T::SIZE];
+    let new = &new[..T::SIZE];
+
+    T::layout()
+        .components()
+        .into_iter()
+        .filter_map(|component| {
+            let range = component.offset..component.offset + component.scalar.size();
+            if old[range.clone()] == new[range.clone()] {
+                return None;
+            }
+
+            let old = Std140Value::read(component.scalar, &old[range.clone()], endian);
+            let new = Std140Value::read(component.scalar, &new[range], endian);
+            let equal = match (old, new) {
+                (Std140Value::Float(old), Std140Value::Float(new)) => floats_equal(old, new),
+                _ => old == new,
+            };
+            if equal {
+                None
+            } else {
+                Some(FieldDiff { path: component.path, offset: component.offset, old, new })
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::diff_fields;
+    use crate::{AsStd140, Endian, Std140Bytes, Std140Value};
+
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        enabled: bool,
+    }
+
+    #[derive(AsStd140)]
+    struct Lights {
+        count: u32,
+        lights: [Light; 2],
+    }
+
+    #[test]
+    fn fields() {
+        let light = |x, enabled| Light { position: mint::Vector3 { x, y: 0.0, z: 0.0 }, enabled };
+        let old = Lights { count: 2, lights: [light(1.0, true), light(2.0, true)] };
+        let new = Lights { count: 1, lights: [light(1.0000001, true), light(2.5, false)] };
+
+        let diffs = diff_fields::<Lights>(
+            &old.as_std140_be_bytes(),
+            &new.as_std140_be_bytes(),
+            Endian::Big,
+            1e-5,
+        );
+        let paths: Vec<_> = diffs.iter().map(|diff| diff.path.as_str()).collect();
+        assert_eq!(paths, ["count", "lights[1].position.x", "lights[1].enabled"]);
+        assert_eq!(diffs[1].offset, 32);
+        assert_eq!(diffs[1].new, Std140Value::Float(2.5));
+        assert_eq!(diffs[2].offset, 44);
+        assert_eq!(diffs[2].delta(), -1.0);
+        assert_eq!(diffs[0].to_string(), "count (offset 0): 2u -> 1u (-1)");
+    }
+
+    #[test]
+    #[should_panic(expected = "\n    lights[0].position.x (offset 16): 1.0 -> 1.001 (+")]
+    fn relative() {
+        let light = |x| Light { position: mint::Vector3 { x, y: 0.0, z: 0.0 }, enabled: true };
+        let lights = |x| Lights { count: 1, lights: [light(x), light(-x)] };
+
+        crate::assert_std140_relative_eq!(
+            lights(1.0),
+            lights(1.0001).as_std140_bytes(),
+            max_relative = 1e-3
+        );
+        crate::assert_std140_relative_eq!(
+            lights(1.0),
+            lights(1.001).as_std140_bytes(),
+            max_relative = 1e-4
+        );
+    }
+}
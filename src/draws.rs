@@ -0,0 +1,109 @@
+use crate::split::split_by_stride;
+use crate::storage::{pack_std140, pack_std430};
+use crate::{ArrayChunk, BufferBinding, Endian, Std140Bytes, Std140Layout};
+use alloc::vec::Vec;
+
+/// Per-draw parameters packed by [pack_draw_parameters] for multi-draw indirect calls.
+///
+/// Each batch is one multi-draw call. Its bytes are bound as the parameter block, in which the
+/// shader indexes the array with `gl_DrawID` or `draw_index`, since those restart from 0 in every
+/// call:
+///
+/// ```glsl
+/// layout(std140) uniform Draws {
+///     Draw draws[CAPACITY];
+/// };
+/// ```
+///
+/// where `CAPACITY` is the number of draws in the first batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DrawParameters {
+    /// How the renderer should bind the bytes.
+    pub binding: BufferBinding,
+    /// The distance in bytes between the parameters of consecutive draws.
+    pub stride: usize,
+    /// The parameters of every draw.
+    pub bytes: Vec<u8>,
+    /// The draws of each multi-draw call and the bytes to bind for it.
+    pub batches: Vec<ArrayChunk>,
+}
+
+/// Pack the parameters of `draws` in the host byte order as a std140 array for a uniform binding
+/// or a std430 array for a storage binding, split into batches that each fit in a block of
+/// `max_block_size` bytes and start at a multiple of `offset_alignment` bytes.
+///
+/// # Panics
+///
+/// Panics if `offset_alignment` is not a power of two, or if no batch of whole draws both fits in
+/// a block and ends at a multiple of `offset_alignment`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{pack_draw_parameters, AsStd140, BufferBinding};
+///
+/// #[derive(AsStd140)]
+/// struct Draw {
+///     material: u32,
+///     tint: mint::Vector3<f32>,
+/// }
+///
+/// let draws: Vec<_> = (0..1500).map(|material| Draw { material, tint: [1.0; 3].into() }).collect();
+///
+/// let parameters = pack_draw_parameters(&draws, BufferBinding::Uniform, 16384, 256);
+/// assert_eq!(parameters.stride, 32);
+/// assert_eq!(parameters.batches.len(), 3);
+/// assert_eq!(parameters.batches[2].elements, 1024..1500);
+///
+/// let parameters = pack_draw_parameters(&draws, BufferBinding::Storage, 1 << 27, 16);
+/// assert_eq!(parameters.stride, 32);
+/// assert_eq!(parameters.batches.len(), 1);
+/// ```
+pub fn pack_draw_parameters<T: Std140Bytes + Std140Layout>(
+    draws: &[T],
+    binding: BufferBinding,
+    max_block_size: usize,
+    offset_alignment: usize,
+) -> DrawParameters {
+    pack_draw_parameters_with(draws, binding, max_block_size, offset_alignment, Endian::NATIVE)
+}
+
+/// Pack the parameters of `draws` like [pack_draw_parameters], in the given byte order.
+pub fn pack_draw_parameters_with<T: Std140Bytes + Std140Layout>(
+    draws: &[T],
+    binding: BufferBinding,
+    max_block_size: usize,
+    offset_alignment: usize,
+    endian: Endian,
+) -> DrawParameters {
+    let packed = match binding {
+        BufferBinding::Uniform => pack_std140(draws, endian),
+        BufferBinding::Storage => pack_std430(draws, endian),
+    };
+    let batches = split_by_stride(draws.len(), packed.stride, max_block_size, offset_alignment);
+    DrawParameters { binding, stride: packed.stride, bytes: packed.bytes, batches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pack_draw_parameters_with;
+    use crate::{BufferBinding, Endian};
+
+    #[test]
+    fn batches() {
+        let draws: Vec<_> = (0..10).map(|i| mint::Vector2 { x: i as f32, y: 1.0f32 }).collect();
+
+        let parameters =
+            pack_draw_parameters_with(&draws, BufferBinding::Uniform, 64, 32, Endian::Little);
+        assert_eq!(parameters.stride, 16);
+        assert_eq!(parameters.bytes.len(), 160);
+        let bytes: Vec<_> = parameters.batches.iter().map(|batch| batch.bytes.clone()).collect();
+        assert_eq!(bytes, [0..64, 64..128, 128..160]);
+
+        let parameters =
+            pack_draw_parameters_with(&draws, BufferBinding::Storage, 64, 32, Endian::Little);
+        assert_eq!(parameters.stride, 8);
+        assert_eq!(&parameters.bytes[72..76], &9.0f32.to_le_bytes());
+        assert_eq!(parameters.batches[1].elements, 8..10);
+    }
+}
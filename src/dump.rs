@@ -0,0 +1,87 @@
+use crate::{Endian, Std140Layout, Std140Value};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Render the std140 representation of `T` in `bytes` as a hexdump, with every scalar annotated
+/// by its path and decoded value and every run of padding marked as such. Values are decoded in
+/// the host byte order.
+///
+/// # Panics
+///
+/// Panics if `bytes` is shorter than [`Std140Sized::SIZE`](crate::Std140Sized::SIZE).
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{dump_annotated, Std140Bytes};
+///
+/// let matrix = mint::ColumnMatrix3x2 {
+///     x: mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 },
+///     y: mint::Vector3 { x: 4.0f32, y: 5.0, z: 6.0 },
+/// };
+/// let dump = dump_annotated::<mint::ColumnMatrix3x2<f32>>(&matrix.as_std140_bytes());
+/// assert!(dump.lines().any(|line| line.ends_with("[1][2] = 6.0")));
+/// assert!(dump.lines().any(|line| line.starts_with("000c") && line.ends_with("(padding)")));
+/// ```
+pub fn dump_annotated<T: Std140Layout>(bytes: &[u8]) -> String {
+    dump_annotated_with::<T>(bytes, Endian::NATIVE)
+}
+
+/// Like [dump_annotated], but decodes values in the given byte order.
+pub fn dump_annotated_with<T: Std140Layout>(bytes: &[u8], endian: Endian) -> String {
+    let bytes = &bytes[..T::SIZE];
+    let mut dump = String::new();
+    let mut offset = 0;
+
+    for component in T::layout().components() {
+        dump_padding(&mut dump, bytes, offset, component.offset);
+        let end = component.offset + component.scalar.size();
+        let scalar = &bytes[component.offset..end];
+        let value = Std140Value::read(component.scalar, scalar, endian);
+        writeln!(dump, "{:04x}  {}  {} = {}", component.offset, hex(scalar), component.path, value)
+            .unwrap();
+        offset = end;
+    }
+    dump_padding(&mut dump, bytes, offset, T::SIZE);
+
+    dump
+}
+
+fn dump_padding(dump: &mut String, bytes: &[u8], start: usize, end: usize) {
+    let mut offset = start;
+    while offset < end {
+        let line_end = end.min((offset + 16) & !15);
+        writeln!(dump, "{:04x}  {}  (padding)", offset, hex(&bytes[offset..line_end])).unwrap();
+        offset = line_end;
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dump_annotated_with;
+    use crate::{Endian, Std140Bytes};
+
+    #[test]
+    fn padding() {
+        let array = [mint::Vector2 { x: 1u32, y: 2u32 }, mint::Vector2 { x: 3u32, y: 4u32 }];
+        let dump = dump_annotated_with::<[mint::Vector2<u32>; 2]>(
+            &array.as_std140_le_bytes(),
+            Endian::Little,
+        );
+        assert_eq!(
+            dump,
+            "0000  01 00 00 00  [0].x = 1u\n\
+             0004  02 00 00 00  [0].y = 2u\n\
+             0008  00 00 00 00 00 00 00 00  (padding)\n\
+             0010  03 00 00 00  [1].x = 3u\n\
+             0014  04 00 00 00  [1].y = 4u\n\
+             0018  00 00 00 00 00 00 00 00  (padding)\n"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Std140Sized;
+use core::convert::TryFrom;
+
+/// The offsets of instances of a block in a buffer bound with dynamic offsets, such as a
+/// `UNIFORM_BUFFER_DYNAMIC` descriptor or a ring of per-draw uniforms bound with
+/// `glBindBufferRange`.
+///
+/// Each instance starts at a multiple of the device's minimum offset alignment, such as
+/// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT` or Vulkan's `minUniformBufferOffsetAlignment`, and every
+/// binding covers [DynamicOffsets::size] bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use mint_std140::{AsStd140, DynamicOffsets};
+///
+/// #[derive(AsStd140)]
+/// struct Draw {
+///     model: mint::ColumnMatrix4<f32>,
+///     tint: mint::Vector4<f32>,
+/// }
+///
+/// let offsets = DynamicOffsets::new::<Draw>(256);
+/// assert_eq!((offsets.size(), offsets.stride()), (80, 256));
+/// assert_eq!(offsets.offset(3), 768);
+/// assert_eq!(offsets.dynamic_offset(3), 768u32);
+/// assert_eq!(offsets.buffer_size(4), 768 + 80);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DynamicOffsets {
+    size: usize,
+    stride: usize,
+}
+
+impl DynamicOffsets {
+    /// Compute the offsets of instances of `T` for a device with the given minimum offset
+    /// alignment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_offset_alignment` is not a power of two.
+    pub fn new<T: Std140Sized>(min_offset_alignment: usize) -> Self {
+        Self::for_size(T::SIZE, min_offset_alignment)
+    }
+
+    /// Compute the offsets of instances of a block of `size` bytes, such as one described by a
+    /// runtime [Layout](crate::Layout), like [DynamicOffsets::new].
+    pub fn for_size(size: usize, min_offset_alignment: usize) -> Self {
+        assert!(
+            min_offset_alignment.is_power_of_two(),
+            "invalid offset alignment {}",
+            min_offset_alignment
+        );
+        // Instances of an empty block still get distinct offsets.
+        let stride = size.max(1).next_multiple_of(min_offset_alignment);
+        DynamicOffsets { size, stride }
+    }
+
+    /// The size of each instance, which is the range to bind.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The distance in bytes between consecutive instances.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The offset of instance `index`.
+    pub fn offset(&self, index: usize) -> usize {
+        index * self.stride
+    }
+
+    /// The offset of instance `index` as the `u32` passed to `vkCmdBindDescriptorSets`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset does not fit in a `u32`.
+    pub fn dynamic_offset(&self, index: usize) -> u32 {
+        let offset = self.offset(index);
+        u32::try_from(offset)
+            .unwrap_or_else(|_| panic!("dynamic offset {} does not fit in a u32", offset))
+    }
+
+    /// The number of bytes a buffer needs to hold `count` instances, which is the end of the
+    /// last one rather than `count` whole strides.
+    pub fn buffer_size(&self, count: usize) -> usize {
+        match count {
+            0 => 0,
+            count => self.offset(count - 1) + self.size,
+        }
+    }
+
+    /// The number of instances that fit in a buffer of `buffer_size` bytes.
+    pub fn capacity(&self, buffer_size: usize) -> usize {
+        match buffer_size.checked_sub(self.size) {
+            Some(rest) => rest / self.stride + 1,
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynamicOffsets;
+
+    #[test]
+    fn offsets() {
+        let offsets = DynamicOffsets::new::<mint::Vector3<f32>>(64);
+        assert_eq!((offsets.size(), offsets.stride()), (12, 64));
+        assert_eq!(offsets.buffer_size(0), 0);
+        assert_eq!(offsets.buffer_size(3), 140);
+        assert_eq!(offsets.capacity(140), 3);
+        assert_eq!(offsets.capacity(139), 2);
+        assert_eq!(offsets.capacity(11), 0);
+
+        // Blocks larger than the alignment take whole multiples of it.
+        let offsets = DynamicOffsets::for_size(300, 256);
+        assert_eq!(offsets.offset(2), 1024);
+        assert_eq!(DynamicOffsets::for_size(0, 4).stride(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid offset alignment 48")]
+    fn invalid_alignment() {
+        DynamicOffsets::new::<f32>(48);
+    }
+}
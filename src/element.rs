@@ -0,0 +1,89 @@
+use crate::bytes::array_stride;
+use crate::{Endian, Std140Bytes};
+use core::any::type_name;
+
+/// Write `value` in the host byte order as element `index` of a std140 array of `T` that starts
+/// `array_offset` bytes into `bytes`, without repacking the rest of the array, and return the
+/// offset of the element.
+///
+/// # Panics
+///
+/// Panics if `array_offset` is not a multiple of 16, the base alignment of std140 arrays, or if
+/// the element ends past the end of `bytes`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{write_element_at, Endian, Std140Bytes};
+///
+/// let mut bones = [mint::Vector3 { x: 0.0f32, y: 0.0, z: 0.0 }; 64];
+/// let mut bytes = [0; 64 * 16];
+/// bones.write_std140(&mut bytes, Endian::NATIVE);
+///
+/// bones[5].y = 1.0;
+/// let offset = write_element_at(&mut bytes, 0, 5, &bones[5]);
+/// assert_eq!(offset, 80);
+/// assert_eq!(bytes[84..88], 1.0f32.to_ne_bytes());
+/// ```
+pub fn write_element_at<T: Std140Bytes>(
+    bytes: &mut [u8],
+    array_offset: usize,
+    index: usize,
+    value: &T,
+) -> usize {
+    write_element_at_with(bytes, array_offset, index, value, Endian::NATIVE)
+}
+
+/// Write one element of a std140 array like [write_element_at], in the given byte order.
+pub fn write_element_at_with<T: Std140Bytes>(
+    bytes: &mut [u8],
+    array_offset: usize,
+    index: usize,
+    value: &T,
+    endian: Endian,
+) -> usize {
+    assert!(
+        array_offset.is_multiple_of(16),
+        "array of {} at offset {} is not aligned to 16 bytes",
+        type_name::<T>(),
+        array_offset
+    );
+    let offset = element_offset::<T>(array_offset, index);
+    match offset.checked_add(T::SIZE) {
+        Some(end) if end <= bytes.len() => {}
+        _ => panic!(
+            "element {} of {} at offset {} ends past the {} bytes of the buffer",
+            index,
+            type_name::<T>(),
+            offset,
+            bytes.len()
+        ),
+    }
+    value.write_std140(&mut bytes[offset..], endian);
+    offset
+}
+
+/// The offset of element `index` of an array of `T` at `array_offset`, saturating on overflow.
+pub(crate) fn element_offset<T: Std140Bytes>(array_offset: usize, index: usize) -> usize {
+    index.saturating_mul(array_stride(T::SIZE)).saturating_add(array_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_element_at_with;
+    use crate::Endian;
+
+    #[test]
+    fn element() {
+        let mut bytes = [0u8; 64];
+        assert_eq!(write_element_at_with(&mut bytes, 16, 2, &7u32, Endian::Big), 48);
+        assert_eq!(&bytes[48..52], &7u32.to_be_bytes());
+        assert!(bytes[..48].iter().chain(&bytes[52..]).all(|&byte| byte == 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "element 3 of u32 at offset 64 ends past the 64 bytes of the buffer")]
+    fn out_of_bounds() {
+        write_element_at_with(&mut [0u8; 64], 16, 3, &7u32, Endian::Little);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::vulkan::VulkanError;
+#[cfg(feature = "alloc")]
+use crate::{ScalarKind, Std140Value};
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use core::any::TypeId;
+use core::error::Error;
+use core::fmt;
+
+/// An error from one of the fallible operations of this crate.
+///
+/// Variants that concern a single scalar or field carry its path, such as `lights[3].direction`
+/// or `lights[3].direction.x`, so callers can report or handle the failure without parsing the
+/// message.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Std140Error {
+    /// A field holds a value rejected by its `#[std140(range = ...)]` or
+    /// `#[std140(assert_finite)]` attribute.
+    #[cfg(feature = "alloc")]
+    OutOfRange {
+        /// The path of the field from the struct being validated, such as `direction` or
+        /// `lights[3].direction` for a field of a nested struct.
+        field: String,
+        /// The first offending scalar of the field.
+        value: f64,
+        /// What the field must hold, as written in the attribute, such as `0.0..=1.0`.
+        expected: &'static str,
+    },
+    /// Bytes being read end before a field that has no default.
+    TooShort {
+        /// The name of the field, or an empty string for the whole value.
+        field: &'static str,
+        /// The number of bytes needed to read the field.
+        required: usize,
+        /// The number of bytes available.
+        available: usize,
+    },
+    /// A destination is too small for the value being written.
+    Overflow {
+        /// The name of the field or type being written.
+        field: &'static str,
+        /// The offset at which it would be written.
+        offset: usize,
+        /// The number of bytes needed from the start of the destination.
+        required: usize,
+        /// The size of the destination.
+        available: usize,
+    },
+    /// A value would be written at an offset that does not satisfy its std140 alignment.
+    Misaligned {
+        /// The name of the field or type being written, or an empty string for the start of a
+        /// block.
+        field: &'static str,
+        /// The offending offset.
+        offset: usize,
+        /// The required alignment.
+        align: usize,
+    },
+    /// An alignment is not a power of two or is too small.
+    InvalidAlignment {
+        /// The requested alignment.
+        align: usize,
+        /// The smallest alignment accepted.
+        minimum: usize,
+    },
+    /// A value does not fit in the std140 scalar type it is converted to.
+    Narrowing {
+        /// The value.
+        value: u64,
+        /// The GLSL name of the scalar type, such as `uint`.
+        target: &'static str,
+    },
+    /// An index is past the end of a container.
+    IndexOutOfBounds {
+        /// The index.
+        index: usize,
+        /// The length of the container.
+        len: usize,
+    },
+    /// A layout has a different size than expected.
+    #[cfg(feature = "alloc")]
+    SizeMismatch {
+        /// The actual size in bytes.
+        size: usize,
+        /// The expected size in bytes.
+        expected: usize,
+    },
+    /// A layout has a different scalar than expected at some position.
+    #[cfg(feature = "alloc")]
+    ComponentMismatch {
+        /// The path of the actual scalar.
+        path: String,
+        /// The offset and type of the actual scalar.
+        actual: (usize, ScalarKind),
+        /// The path of the expected scalar.
+        expected_path: String,
+        /// The offset and type of the expected scalar.
+        expected: (usize, ScalarKind),
+    },
+    /// A layout has a different number of scalars than expected.
+    #[cfg(feature = "alloc")]
+    ComponentCount {
+        /// The actual number of scalars.
+        count: usize,
+        /// The expected number of scalars.
+        expected: usize,
+    },
+    /// Bytes hold a different scalar value than expected.
+    #[cfg(feature = "alloc")]
+    ValueMismatch {
+        /// The path of the scalar.
+        path: String,
+        /// The offset of the scalar in bytes.
+        offset: usize,
+        /// The value found.
+        value: Std140Value,
+        /// The value expected.
+        expected: Std140Value,
+    },
+    /// A value passed to a [Std140Registry](crate::Std140Registry) has a type that was not
+    /// registered.
+    #[cfg(feature = "alloc")]
+    UnregisteredType {
+        /// The type of the value.
+        type_id: TypeId,
+    },
+    /// A [LayoutManifest](crate::LayoutManifest) text form could not be parsed.
+    #[cfg(feature = "alloc")]
+    InvalidManifest {
+        /// The 1-based number of the malformed line.
+        line: usize,
+    },
+    /// A Vulkan valid usage rule is violated.
+    Vulkan(VulkanError),
+}
+
+impl fmt::Display for Std140Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "alloc")]
+            Std140Error::OutOfRange { field, value, expected } => {
+                write!(f, "{} holds {}, expected {}", field, value, expected)
+            }
+            Std140Error::TooShort { field: "", required, available } => {
+                write!(f, "std140 data is {} bytes, expected {}", available, required)
+            }
+            Std140Error::TooShort { field, required, available } => write!(
+                f,
+                "std140 data is {} bytes and ends before field {}, which needs {} and has no \
+                 default",
+                available, field, required
+            ),
+            Std140Error::Overflow { field, offset, required, available } => write!(
+                f,
+                "writing {} at offset {} needs {} bytes, but the destination is {} bytes",
+                field, offset, required, available
+            ),
+            Std140Error::Misaligned { field: "", offset, align } => {
+                write!(f, "block offset {} is not a multiple of {}", offset, align)
+            }
+            Std140Error::Misaligned { field, offset, align } => {
+                write!(f, "{} at offset {} is not aligned to {} bytes", field, offset, align)
+            }
+            Std140Error::InvalidAlignment { align, minimum } => {
+                write!(f, "alignment {} is not a power of two of at least {}", align, minimum)
+            }
+            Std140Error::Narrowing { value, target } => {
+                write!(f, "{} does not fit in a {}", value, target)
+            }
+            Std140Error::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds for length {}", index, len)
+            }
+            #[cfg(feature = "alloc")]
+            Std140Error::SizeMismatch { size, expected } => {
+                write!(f, "size is {}, expected {}", size, expected)
+            }
+            #[cfg(feature = "alloc")]
+            Std140Error::ComponentMismatch { path, actual, expected_path, expected } => write!(
+                f,
+                "{} is a {} at offset {}, expected {} to be a {} at offset {}",
+                path,
+                actual.1.glsl_name(),
+                actual.0,
+                expected_path,
+                expected.1.glsl_name(),
+                expected.0
+            ),
+            #[cfg(feature = "alloc")]
+            Std140Error::ComponentCount { count, expected } => {
+                write!(f, "has {} scalars, expected {}", count, expected)
+            }
+            #[cfg(feature = "alloc")]
+            Std140Error::ValueMismatch { path, offset, value, expected } => {
+                write!(f, "{} at offset {} is {}, expected {}", path, offset, value, expected)
+            }
+            #[cfg(feature = "alloc")]
+            Std140Error::UnregisteredType { type_id } => {
+                write!(f, "{:?} is not registered", type_id)
+            }
+            #[cfg(feature = "alloc")]
+            Std140Error::InvalidManifest { line } => {
+                write!(f, "layout manifest line {} is malformed", line)
+            }
+            Std140Error::Vulkan(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for Std140Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Std140Error::Vulkan(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Std140Error {
+    /// Prefix the path of a field with `prefix`, the name of the field or the `[index]` of the
+    /// array element that holds it.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn in_field(self, prefix: &str) -> Self {
+        match self {
+            Std140Error::OutOfRange { field, value, expected } => {
+                let field = if field.starts_with('[') {
+                    format!("{}{}", prefix, field)
+                } else {
+                    format!("{}.{}", prefix, field)
+                };
+                Std140Error::OutOfRange { field, value, expected }
+            }
+            error => error,
+        }
+    }
+}
+
+impl From<VulkanError> for Std140Error {
+    fn from(error: VulkanError) -> Self {
+        Std140Error::Vulkan(error)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::Std140Error;
+    use crate::vulkan::{check_update_buffer, VulkanError};
+    use alloc::string::ToString;
+    use core::error::Error;
+
+    #[test]
+    fn display() {
+        let error = Std140Error::TooShort { field: "", required: 16, available: 12 };
+        assert_eq!(error.to_string(), "std140 data is 12 bytes, expected 16");
+
+        let error: Std140Error = check_update_buffer(2, &[0; 4]).unwrap_err().into();
+        assert_eq!(error, Std140Error::Vulkan(VulkanError::UpdateOffsetNotMultipleOfFour(2)));
+        assert_eq!(error.to_string(), "vkCmdUpdateBuffer offset 2 is not a multiple of 4");
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::quantize::{f16_to_f32, f32_to_f16};
+use crate::{bytes, Endian, FromStd140, Std140Bytes, Std140Sized};
+
+/// A 16-bit float, stored as its bits, for `float16_t` members of blocks declared with
+/// `GL_EXT_shader_explicit_arithmetic_types` and 16-bit storage.
+///
+/// Together with [i8], [u8], [i16] and [u16], it implements [Std140Bytes] with the extended
+/// std140 rules of those extensions: an 8-bit or 16-bit scalar is aligned to its own size, a
+/// two-component vector of them to twice that and a three- or four-component vector to four
+/// times that, while array elements are still padded to 16 bytes. These types can be packed with
+/// a [Std140Writer](crate::Std140Writer) and, with the `alloc` feature, implement
+/// [Std140Layout](crate::Std140Layout) with the 8-bit and 16-bit
+/// [ScalarKind](crate::ScalarKind)s, so they can be fields of derived non-generic structs. They
+/// have no [AsStd140](crate::AsStd140) type, since the `std140` crate only has 32-bit scalars.
+///
+/// | Rust                  | GLSL                              | Size | Alignment |
+/// |-----------------------|-----------------------------------|------|-----------|
+/// | `i8`, `u8`            | `int8_t`, `uint8_t`               | 1    | 1         |
+/// | `mint::Vector3<i8>`   | `i8vec3`                          | 3    | 4         |
+/// | `i16`, `u16`, `F16`   | `int16_t`, `uint16_t`, `float16_t` | 2    | 2         |
+/// | `mint::Vector2<F16>`  | `f16vec2`                         | 4    | 4         |
+/// | `mint::Vector3<F16>`  | `f16vec3`                         | 6    | 8         |
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{Std140Writer, F16};
+///
+/// let mut bytes = [0u8; 32];
+/// let mut writer = Std140Writer::new(&mut bytes);
+/// writer.write(&F16::from_f32(0.5));
+/// let color = mint::Vector3 { x: F16::from_f32(1.0), y: F16::ZERO, z: F16::ZERO };
+/// assert_eq!(writer.write(&color), 8);
+/// assert_eq!(writer.write(&-3i8), 14);
+/// assert_eq!(writer.write(&mint::Vector2 { x: 7u16, y: 9 }), 16);
+/// assert_eq!(bytes[..2], F16::from_f32(0.5).to_bits().to_ne_bytes());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct F16(u16);
+
+impl F16 {
+    /// Positive zero.
+    pub const ZERO: F16 = F16(0);
+
+    /// Round a float to the nearest 16-bit float, like GLSL's `packHalf2x16`.
+    pub fn from_f32(value: f32) -> Self {
+        F16(f32_to_f16(value))
+    }
+
+    /// Convert to a float, which is always exact.
+    pub fn to_f32(self) -> f32 {
+        f16_to_f32(self.0)
+    }
+
+    /// Reinterpret IEEE 754 binary16 bits as a 16-bit float.
+    pub const fn from_bits(bits: u16) -> Self {
+        F16(bits)
+    }
+
+    /// The IEEE 754 binary16 bits of this float.
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+}
+
+impl From<F16> for f32 {
+    fn from(value: F16) -> Self {
+        value.to_f32()
+    }
+}
+
+/// An 8-bit or 16-bit scalar of the explicit arithmetic types.
+trait SmallScalar: Copy {
+    const BYTES: usize;
+
+    fn write(self, bytes: &mut [u8], endian: Endian);
+
+    fn read(bytes: &[u8], endian: Endian) -> Self;
+}
+
+macro_rules! impl_small_scalar {
+    ($rust_type:ty, $bits:ty, $to_bits:expr, $from_bits:expr) => {
+        impl SmallScalar for $rust_type {
+            const BYTES: usize = core::mem::size_of::<$bits>();
+
+            fn write(self, bytes: &mut [u8], endian: Endian) {
+                let bits: $bits = $to_bits(self);
+                let bits = match endian {
+                    Endian::Little => bits.to_le_bytes(),
+                    Endian::Big => bits.to_be_bytes(),
+                };
+                bytes[..Self::BYTES].copy_from_slice(&bits);
+            }
+
+            fn read(bytes: &[u8], endian: Endian) -> Self {
+                let mut bits = [0; core::mem::size_of::<$bits>()];
+                bits.copy_from_slice(&bytes[..Self::BYTES]);
+                $from_bits(match endian {
+                    Endian::Little => <$bits>::from_le_bytes(bits),
+                    Endian::Big => <$bits>::from_be_bytes(bits),
+                })
+            }
+        }
+
+        impl Std140Sized for $rust_type {
+            const SIZE: usize = <$rust_type as SmallScalar>::BYTES;
+            const ALIGN: usize = <$rust_type as SmallScalar>::BYTES;
+        }
+
+        impl Std140Bytes for $rust_type {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                bytes::debug_check_len::<Self>(bytes);
+                SmallScalar::write(*self, bytes, endian);
+            }
+        }
+
+        impl FromStd140 for $rust_type {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                SmallScalar::read(bytes, endian)
+            }
+        }
+
+        impl_small_vector!($rust_type, Vector2, 2, [x, y]);
+        impl_small_vector!($rust_type, Vector3, 4, [x, y, z]);
+        impl_small_vector!($rust_type, Vector4, 4, [x, y, z, w]);
+    };
+}
+
+macro_rules! impl_small_vector {
+    ($rust_type:ty, $mint_type:ident, $align:expr, [$($component:ident),+]) => {
+        impl Std140Sized for mint::$mint_type<$rust_type> {
+            const SIZE: usize = [$(stringify!($component)),+].len() * <$rust_type as SmallScalar>::BYTES;
+            const ALIGN: usize = $align * <$rust_type as SmallScalar>::BYTES;
+        }
+
+        impl Std140Bytes for mint::$mint_type<$rust_type> {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                bytes::debug_check_len::<Self>(bytes);
+                let size = <$rust_type as SmallScalar>::BYTES;
+                let bytes = &mut bytes[..Self::SIZE];
+                for (component, bytes) in [$(self.$component),+].iter().zip(bytes.chunks_exact_mut(size)) {
+                    component.write(bytes, endian);
+                }
+            }
+        }
+
+        impl FromStd140 for mint::$mint_type<$rust_type> {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                let size = <$rust_type as SmallScalar>::BYTES;
+                let mut chunks = bytes[..Self::SIZE].chunks_exact(size);
+                mint::$mint_type {
+                    $($component: SmallScalar::read(chunks.next().unwrap(), endian)),+
+                }
+            }
+        }
+    };
+}
+
+impl_small_scalar!(i8, i8, i8::from, i8::from);
+impl_small_scalar!(u8, u8, u8::from, u8::from);
+impl_small_scalar!(i16, i16, i16::from, i16::from);
+impl_small_scalar!(u16, u16, u16::from, u16::from);
+impl_small_scalar!(F16, u16, F16::to_bits, F16::from_bits);
+
+#[cfg(test)]
+mod tests {
+    use super::F16;
+    use crate::{Endian, FromStd140, Std140Sized, Std140Writer};
+
+    #[test]
+    fn layout() {
+        assert_eq!((mint::Vector3::<u8>::SIZE, mint::Vector3::<u8>::ALIGN), (3, 4));
+        assert_eq!((mint::Vector2::<i16>::SIZE, mint::Vector2::<i16>::ALIGN), (4, 4));
+        assert_eq!((mint::Vector4::<F16>::SIZE, mint::Vector4::<F16>::ALIGN), (8, 8));
+        assert_eq!(<[F16; 3]>::SIZE, 48);
+
+        let mut bytes = [0xffu8; 16];
+        let mut writer = Std140Writer::with_endian(&mut bytes, Endian::Big);
+        assert_eq!(writer.write(&1u8), 0);
+        assert_eq!(writer.write(&-2i16), 2);
+        let vector = mint::Vector3 { x: F16::from_f32(-1.5), y: F16::ZERO, z: F16::from_f32(1e5) };
+        assert_eq!(writer.write(&vector), 8);
+        assert_eq!(&bytes[..4], &[1, 0, 0xff, 0xfe]);
+        assert_eq!(&bytes[8..10], &0xbe00u16.to_be_bytes());
+
+        let read = mint::Vector3::<F16>::from_std140_bytes(&bytes[8..], Endian::Big);
+        assert_eq!(read.x.to_f32(), -1.5);
+        assert_eq!(read.z.to_f32(), f32::INFINITY);
+        assert_eq!(i16::from_std140_bytes(&bytes[2..], Endian::Big), -2);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive() {
+        use crate::{AsStd140, Std140Bytes, Std140Layout};
+
+        #[derive(AsStd140, Debug, PartialEq)]
+        struct Vertex {
+            weight: F16,
+            offset: mint::Vector2<i16>,
+            id: u8,
+            normal: mint::Vector3<F16>,
+        }
+
+        let layout = Vertex::layout();
+        assert_eq!((Vertex::SIZE, Vertex::ALIGN), (32, 16));
+        assert_eq!(layout.offset_of("offset.y"), Some(6));
+        assert_eq!(layout.offset_of("id"), Some(8));
+        assert_eq!(layout.offset_of("normal.z"), Some(20));
+        assert_eq!(mint::Vector3::<F16>::layout().glsl_type(), "f16vec3");
+
+        let vertex = Vertex {
+            weight: F16::from_f32(0.25),
+            offset: mint::Vector2 { x: -3, y: 300 },
+            id: 7,
+            normal: mint::Vector3 { x: F16::ZERO, y: F16::from_f32(-1.0), z: F16::ZERO },
+        };
+        let bytes = vertex.as_std140_bytes();
+        assert_eq!(bytes[4..8], [(-3i16).to_ne_bytes(), 300i16.to_ne_bytes()].concat()[..]);
+        assert_eq!(Vertex::from_std140_bytes(&bytes, Endian::NATIVE), vertex);
+    }
+}
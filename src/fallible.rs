@@ -0,0 +1,339 @@
+//! `Result`-returning variants of the writes, reads, packers and containers that panic on bad
+//! input, for plugin sandboxes and long-running servers where a panic is not acceptable.
+//!
+//! Each function checks what its panicking counterpart would assert and returns a
+//! [Std140Error] instead. Indexing a [Std140Vec] and the debugging helpers such as
+//! [dump_annotated](crate::dump_annotated) and [diff_fields](crate::diff_fields) have no fallible
+//! form; look elements up through [Std140Vec::as_slice] and check buffer lengths against
+//! [Std140Sized::SIZE] before calling the helpers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mint_std140::{fallible, Endian, Std140Error};
+//!
+//! let mut bytes = [0u8; 16];
+//! let direction = mint::Vector3 { x: 0.0f32, y: 0.0, z: 1.0 };
+//! assert!(fallible::write_at(&direction, &mut bytes, 0, Endian::Little).is_ok());
+//! assert!(matches!(
+//!     fallible::write_at(&direction, &mut bytes, 8, Endian::Little),
+//!     Err(Std140Error::Misaligned { offset: 8, align: 16, .. })
+//! ));
+//! ```
+
+#[cfg(feature = "alloc")]
+use crate::bytes::array_stride;
+use crate::element::element_offset;
+#[cfg(feature = "alloc")]
+use crate::{
+    ArrayChunk, Handle, InstancePacker, Std140Arena, Std140BlockPacker, Std140Interner, Std140Vec,
+};
+use crate::{
+    DynamicOffsets, Endian, FromStd140, Std140Bytes, Std140Error, Std140Sized, Std140Writer,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::convert::TryFrom;
+
+/// Write `value` to the start of `bytes`, like [Std140Bytes::write_std140].
+pub fn write<T: Std140Bytes>(
+    value: &T,
+    bytes: &mut [u8],
+    endian: Endian,
+) -> Result<(), Std140Error> {
+    value.try_write_std140(bytes, endian)
+}
+
+/// Write `value` at `offset` bytes into a std140 block held in `bytes`, checking that the offset
+/// satisfies the std140 alignment of `T` and that the value fits.
+pub fn write_at<T: Std140Bytes>(
+    value: &T,
+    bytes: &mut [u8],
+    offset: usize,
+    endian: Endian,
+) -> Result<(), Std140Error> {
+    check_offset::<T>(offset)?;
+    let required = offset.checked_add(T::SIZE).filter(|&required| required <= bytes.len());
+    match required {
+        Some(_) => value.try_write_std140(&mut bytes[offset..], endian),
+        None => Err(Std140Error::Overflow {
+            field: type_name::<T>(),
+            offset,
+            required: offset.saturating_add(T::SIZE),
+            available: bytes.len(),
+        }),
+    }
+}
+
+/// Write one element of a std140 array, like [write_element_at](crate::write_element_at_with),
+/// returning the offset of the element.
+pub fn write_element_at<T: Std140Bytes>(
+    bytes: &mut [u8],
+    array_offset: usize,
+    index: usize,
+    value: &T,
+    endian: Endian,
+) -> Result<usize, Std140Error> {
+    if !array_offset.is_multiple_of(16) {
+        return Err(Std140Error::Misaligned {
+            field: type_name::<T>(),
+            offset: array_offset,
+            align: 16,
+        });
+    }
+    let offset = element_offset::<T>(array_offset, index);
+    write_at(value, bytes, offset, endian)?;
+    Ok(offset)
+}
+
+/// Compute the dynamic offsets of instances of `T`, like [DynamicOffsets::new].
+pub fn dynamic_offsets<T: Std140Sized>(
+    min_offset_alignment: usize,
+) -> Result<DynamicOffsets, Std140Error> {
+    check_alignment(min_offset_alignment, 1)?;
+    Ok(DynamicOffsets::new::<T>(min_offset_alignment))
+}
+
+/// Read a value from the start of `bytes`, like [FromStd140::from_std140_bytes].
+pub fn read<T: FromStd140>(bytes: &[u8], endian: Endian) -> Result<T, Std140Error> {
+    T::try_from_std140_bytes(bytes, endian)
+}
+
+/// Read a value from `offset` bytes into a std140 block held in `bytes`, checking the offset
+/// like [write_at].
+pub fn read_at<T: FromStd140>(
+    bytes: &[u8],
+    offset: usize,
+    endian: Endian,
+) -> Result<T, Std140Error> {
+    check_offset::<T>(offset)?;
+    match bytes.get(offset..) {
+        Some(bytes) => T::try_from_std140_bytes(bytes, endian),
+        None => Err(Std140Error::TooShort {
+            field: "",
+            required: offset.saturating_add(T::SIZE),
+            available: bytes.len(),
+        }),
+    }
+}
+
+/// Convert a length or index to the `uint` a shader reads it as.
+pub fn to_u32(value: usize) -> Result<u32, Std140Error> {
+    u32::try_from(value).map_err(|_| Std140Error::Narrowing { value: value as u64, target: "uint" })
+}
+
+/// Convert a count or offset to the `int` a shader reads it as.
+pub fn to_i32(value: usize) -> Result<i32, Std140Error> {
+    i32::try_from(value).map_err(|_| Std140Error::Narrowing { value: value as u64, target: "int" })
+}
+
+/// Pad `writer` to a multiple of `align`, like [Std140Writer::align_to].
+pub fn align_to(writer: &mut Std140Writer<'_>, align: usize) -> Result<usize, Std140Error> {
+    check_alignment(align, 1)?;
+    writer.try_align_to(align)
+}
+
+/// Split an array like [split_array](crate::split_array).
+#[cfg(feature = "alloc")]
+pub fn split_array<T: Std140Sized>(
+    len: usize,
+    max_uniform_block_size: usize,
+    offset_alignment: usize,
+) -> Result<Vec<ArrayChunk>, Std140Error> {
+    check_alignment(offset_alignment, 1)?;
+    // The smallest chunk that ends at a multiple of the offset alignment.
+    let stride = array_stride(T::SIZE);
+    let required = stride / gcd(stride, offset_alignment) * offset_alignment;
+    if required > max_uniform_block_size {
+        return Err(Std140Error::Overflow {
+            field: type_name::<T>(),
+            offset: 0,
+            required,
+            available: max_uniform_block_size,
+        });
+    }
+    Ok(crate::split_array::<T>(len, max_uniform_block_size, offset_alignment))
+}
+
+/// Create an [InstancePacker], checking that at least one instance fits in a block.
+#[cfg(feature = "alloc")]
+pub fn instance_packer<T: Std140Bytes>(
+    max_uniform_block_size: usize,
+    endian: Endian,
+) -> Result<InstancePacker<T>, Std140Error> {
+    let required = InstancePacker::<T>::ARRAY_OFFSET + InstancePacker::<T>::STRIDE;
+    if required > max_uniform_block_size {
+        return Err(Std140Error::Overflow {
+            field: type_name::<T>(),
+            offset: InstancePacker::<T>::ARRAY_OFFSET,
+            required,
+            available: max_uniform_block_size,
+        });
+    }
+    Ok(InstancePacker::with_endian(max_uniform_block_size, endian))
+}
+
+/// Create a [Std140BlockPacker], checking that `align` is a power of two of at least 16.
+#[cfg(feature = "alloc")]
+pub fn block_packer(align: usize, endian: Endian) -> Result<Std140BlockPacker, Std140Error> {
+    check_alignment(align, 16)?;
+    Ok(Std140BlockPacker::with_endian(align, endian))
+}
+
+/// Create a [Std140Interner], checking that `align` is a power of two of at least 16.
+#[cfg(feature = "alloc")]
+pub fn interner(align: usize, endian: Endian) -> Result<Std140Interner, Std140Error> {
+    check_alignment(align, 16)?;
+    Ok(Std140Interner::with_endian(align, endian))
+}
+
+/// Write a field into an arena block, like [Std140Arena::write_field].
+#[cfg(feature = "alloc")]
+pub fn write_field<T: Std140Sized, F: Std140Bytes>(
+    arena: &mut Std140Arena,
+    handle: Handle<T>,
+    offset: usize,
+    value: &F,
+) -> Result<(), Std140Error> {
+    check_offset::<F>(offset)?;
+    let required = offset.saturating_add(F::SIZE);
+    if required > T::SIZE || handle.offset() + T::SIZE > arena.capacity() {
+        return Err(Std140Error::Overflow {
+            field: type_name::<F>(),
+            offset,
+            required,
+            available: T::SIZE,
+        });
+    }
+    arena.write_field(handle, offset, value);
+    Ok(())
+}
+
+/// Repack the whole block behind `handle`, like [Std140Arena::set].
+#[cfg(feature = "alloc")]
+pub fn set_block<T: Std140Bytes>(
+    arena: &mut Std140Arena,
+    handle: Handle<T>,
+    value: &T,
+) -> Result<(), Std140Error> {
+    let required = handle.offset().saturating_add(T::SIZE);
+    if required > arena.capacity() {
+        return Err(Std140Error::Overflow {
+            field: type_name::<T>(),
+            offset: handle.offset(),
+            required,
+            available: arena.capacity(),
+        });
+    }
+    arena.set(handle, value);
+    Ok(())
+}
+
+/// Replace an element of a [Std140Vec], like [Std140Vec::set].
+#[cfg(feature = "alloc")]
+pub fn set<T: Std140Bytes>(
+    vec: &mut Std140Vec<T>,
+    index: usize,
+    value: T,
+) -> Result<(), Std140Error> {
+    if index >= vec.len() {
+        return Err(Std140Error::IndexOutOfBounds { index, len: vec.len() });
+    }
+    vec.set(index, value);
+    Ok(())
+}
+
+/// Modify an element of a [Std140Vec] in place, like [Std140Vec::update].
+#[cfg(feature = "alloc")]
+pub fn update<T: Std140Bytes, R>(
+    vec: &mut Std140Vec<T>,
+    index: usize,
+    f: impl FnOnce(&mut T) -> R,
+) -> Result<R, Std140Error> {
+    if index >= vec.len() {
+        return Err(Std140Error::IndexOutOfBounds { index, len: vec.len() });
+    }
+    Ok(vec.update(index, f))
+}
+
+fn check_offset<T: Std140Sized>(offset: usize) -> Result<(), Std140Error> {
+    if offset.is_multiple_of(T::ALIGN) {
+        Ok(())
+    } else {
+        Err(Std140Error::Misaligned { field: type_name::<T>(), offset, align: T::ALIGN })
+    }
+}
+
+fn check_alignment(align: usize, minimum: usize) -> Result<(), Std140Error> {
+    if align.is_power_of_two() && align >= minimum {
+        Ok(())
+    } else {
+        Err(Std140Error::InvalidAlignment { align, minimum })
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{fallible, Endian, Std140Arena, Std140Error, Std140Vec, Std140Writer};
+
+    #[test]
+    fn errors() {
+        assert_eq!(fallible::to_u32(7), Ok(7));
+        assert_eq!(
+            fallible::to_i32(1 << 31),
+            Err(Std140Error::Narrowing { value: 1 << 31, target: "int" })
+        );
+
+        let mut bytes = [0u8; 8];
+        let mut writer = Std140Writer::new(&mut bytes);
+        assert_eq!(
+            fallible::align_to(&mut writer, 12),
+            Err(Std140Error::InvalidAlignment { align: 12, minimum: 1 })
+        );
+        assert!(fallible::read_at::<f32>(&bytes, 12, Endian::Little).is_err());
+        assert_eq!(fallible::read_at::<u32>(&bytes, 4, Endian::Little), Ok(0));
+        assert!(matches!(
+            fallible::write_element_at(&mut bytes, 0, 1, &1.0f32, Endian::Little),
+            Err(Std140Error::Overflow { offset: 16, .. })
+        ));
+
+        assert!(fallible::interner(8, Endian::Little).is_err());
+        assert!(fallible::dynamic_offsets::<f32>(0).is_err());
+        assert!(matches!(
+            fallible::split_array::<mint::Vector3<f32>>(10, 128, 256),
+            Err(Std140Error::Overflow { required: 256, available: 128, .. })
+        ));
+        assert!(fallible::instance_packer::<f32>(64, Endian::Little).is_err());
+
+        let mut arena = Std140Arena::new(64);
+        let handle = arena.alloc(&mint::Vector4 { x: 0.0f32, y: 0.0, z: 0.0, w: 0.0 }).unwrap();
+        assert!(fallible::write_field(&mut arena, handle, 12, &1.0f32).is_ok());
+        assert!(fallible::write_field(&mut arena, handle, 16, &1.0f32).is_err());
+        let value = mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 0.0 };
+        let second = arena.alloc(&value).unwrap();
+        assert!(fallible::set_block(&mut arena, second, &value).is_ok());
+        assert!(matches!(
+            fallible::set_block(&mut Std140Arena::new(16), second, &value),
+            Err(Std140Error::Overflow { required: 32, available: 16, .. })
+        ));
+
+        let mut vec = Std140Vec::new();
+        vec.push(1u32);
+        assert_eq!(
+            fallible::set(&mut vec, 1, 2),
+            Err(Std140Error::IndexOutOfBounds { index: 1, len: 1 })
+        );
+        assert_eq!(fallible::update(&mut vec, 0, |value| *value += 1), Ok(()));
+        assert_eq!(vec[0], 2);
+        assert!(fallible::update(&mut vec, 1, |value| *value += 1).is_err());
+    }
+}
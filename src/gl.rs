@@ -0,0 +1,502 @@
+//! Helpers for checking std140 layouts against what an OpenGL implementation reports and the
+//! uniform limits it guarantees.
+
+use crate::{Layout, LayoutKind, Std140Layout, Std140Sized};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A member of a uniform block, as queried with `glGetActiveUniformsiv`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ActiveUniform {
+    /// `GL_UNIFORM_NAME`, such as `lights[1].color` or `weights[0]`.
+    pub name: String,
+    /// `GL_UNIFORM_OFFSET`.
+    pub offset: i32,
+    /// `GL_UNIFORM_ARRAY_STRIDE`, or 0 if the member is not an array.
+    pub array_stride: i32,
+    /// `GL_UNIFORM_MATRIX_STRIDE`, or 0 if the member is not a matrix.
+    pub matrix_stride: i32,
+    /// `GL_UNIFORM_IS_ROW_MAJOR`.
+    pub is_row_major: bool,
+}
+
+/// A way in which a queried uniform block disagrees with a Rust type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutMismatch {
+    /// The program has a member that the Rust type does not.
+    Unexpected(String),
+    /// The Rust type has a member that the program does not report.
+    Missing(String),
+    /// A member is at a different offset.
+    Offset {
+        /// The member name.
+        name: String,
+        /// The offset in the Rust type.
+        expected: i32,
+        /// The offset reported by the program.
+        actual: i32,
+    },
+    /// An array member has a different stride.
+    ArrayStride {
+        /// The member name.
+        name: String,
+        /// The stride in the Rust type.
+        expected: i32,
+        /// The stride reported by the program.
+        actual: i32,
+    },
+    /// A matrix member has a different stride.
+    MatrixStride {
+        /// The member name.
+        name: String,
+        /// The stride in the Rust type.
+        expected: i32,
+        /// The stride reported by the program.
+        actual: i32,
+    },
+    /// A matrix member is row-major, while Rust types are always column-major.
+    RowMajor(String),
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutMismatch::Unexpected(name) => write!(f, "{}: not in the Rust type", name),
+            LayoutMismatch::Missing(name) => write!(f, "{}: not in the program", name),
+            LayoutMismatch::Offset { name, expected, actual } => {
+                write!(f, "{}: offset is {}, expected {}", name, actual, expected)
+            }
+            LayoutMismatch::ArrayStride { name, expected, actual } => {
+                write!(f, "{}: array stride is {}, expected {}", name, actual, expected)
+            }
+            LayoutMismatch::MatrixStride { name, expected, actual } => {
+                write!(f, "{}: matrix stride is {}, expected {}", name, actual, expected)
+            }
+            LayoutMismatch::RowMajor(name) => write!(f, "{}: is row-major", name),
+        }
+    }
+}
+
+/// The uniforms that a program using `T` as a std140 uniform block should report, named the way
+/// OpenGL names them.
+pub fn expected_uniforms<T: Std140Layout>() -> Vec<ActiveUniform> {
+    let mut uniforms = Vec::new();
+    match T::layout().kind {
+        LayoutKind::Struct { fields, .. } => {
+            for field in fields {
+                collect_uniforms(&field.layout, field.name.to_string(), field.offset, &mut uniforms)
+            }
+        }
+        _ => collect_uniforms(&T::layout(), "value".to_string(), 0, &mut uniforms),
+    }
+    uniforms
+}
+
+fn collect_uniforms(
+    layout: &Layout,
+    name: String,
+    offset: usize,
+    uniforms: &mut Vec<ActiveUniform>,
+) {
+    let uniform = |name, array_stride, matrix_stride| ActiveUniform {
+        name,
+        offset: offset as i32,
+        array_stride,
+        matrix_stride,
+        is_row_major: false,
+    };
+
+    match &layout.kind {
+        LayoutKind::Scalar(_) | LayoutKind::Vector(..) => uniforms.push(uniform(name, 0, 0)),
+        LayoutKind::Matrix { .. } => uniforms.push(uniform(name, 0, 16)),
+        LayoutKind::Array { element, len } => match &element.kind {
+            LayoutKind::Struct { .. } | LayoutKind::Array { .. } => {
+                for i in 0..*len {
+                    let name = format!("{}[{}]", name, i);
+                    collect_uniforms(element, name, offset + i * element.array_stride(), uniforms);
+                }
+            }
+            LayoutKind::Matrix { .. } => {
+                uniforms.push(uniform(format!("{}[0]", name), element.array_stride() as i32, 16))
+            }
+            _ => uniforms.push(uniform(format!("{}[0]", name), element.array_stride() as i32, 0)),
+        },
+        LayoutKind::Struct { fields, .. } => {
+            for field in fields {
+                let name = format!("{}.{}", name, field.name);
+                collect_uniforms(&field.layout, name, offset + field.offset, uniforms);
+            }
+        }
+    }
+}
+
+/// Compare the members of a uniform block, as queried from a linked program, against the std140
+/// layout of `T` and report every difference.
+///
+/// `block_prefix` is stripped from the start of every queried name; pass `"Block."` if the
+/// implementation prefixes member names with the block name, or `""` otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::gl::{validate_uniform_block, ActiveUniform, LayoutMismatch};
+///
+/// let queried = [ActiveUniform {
+///     name: "Block.value[0]".to_string(),
+///     offset: 0,
+///     array_stride: 8,
+///     matrix_stride: 0,
+///     is_row_major: false,
+/// }];
+/// let mismatches = validate_uniform_block::<[mint::Vector2<f32>; 4]>("Block.", &queried);
+/// assert_eq!(
+///     mismatches,
+///     [LayoutMismatch::ArrayStride { name: "value[0]".to_string(), expected: 16, actual: 8 }]
+/// );
+/// ```
+pub fn validate_uniform_block<T: Std140Layout>(
+    block_prefix: &str,
+    uniforms: &[ActiveUniform],
+) -> Vec<LayoutMismatch> {
+    let expected = expected_uniforms::<T>();
+    let mut mismatches = Vec::new();
+
+    for uniform in uniforms {
+        let name = uniform.name.strip_prefix(block_prefix).unwrap_or(&uniform.name);
+        let expected = match expected.iter().find(|expected| expected.name == name) {
+            Some(expected) => expected,
+            None => {
+                mismatches.push(LayoutMismatch::Unexpected(name.to_string()));
+                continue;
+            }
+        };
+
+        let name = || name.to_string();
+        if uniform.offset != expected.offset {
+            mismatches.push(LayoutMismatch::Offset {
+                name: name(),
+                expected: expected.offset,
+                actual: uniform.offset,
+            });
+        }
+        if uniform.array_stride != expected.array_stride {
+            mismatches.push(LayoutMismatch::ArrayStride {
+                name: name(),
+                expected: expected.array_stride,
+                actual: uniform.array_stride,
+            });
+        }
+        if uniform.matrix_stride != expected.matrix_stride {
+            mismatches.push(LayoutMismatch::MatrixStride {
+                name: name(),
+                expected: expected.matrix_stride,
+                actual: uniform.matrix_stride,
+            });
+        }
+        if uniform.is_row_major {
+            mismatches.push(LayoutMismatch::RowMajor(name()));
+        }
+    }
+
+    for expected in expected {
+        let reported = uniforms.iter().any(|uniform| {
+            uniform.name.strip_prefix(block_prefix).unwrap_or(&uniform.name) == expected.name
+        });
+        if !reported {
+            mismatches.push(LayoutMismatch::Missing(expected.name));
+        }
+    }
+
+    mismatches
+}
+
+/// The limits of an OpenGL ES 3.0 or WebGL 2 implementation on the uniforms of a program, as
+/// queried with `glGetIntegerv`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UniformLimits {
+    /// `GL_MAX_UNIFORM_BLOCK_SIZE`, in bytes.
+    pub max_uniform_block_size: usize,
+    /// `GL_MAX_VERTEX_UNIFORM_BLOCKS`.
+    pub max_vertex_uniform_blocks: usize,
+    /// `GL_MAX_FRAGMENT_UNIFORM_BLOCKS`.
+    pub max_fragment_uniform_blocks: usize,
+    /// `GL_MAX_COMBINED_UNIFORM_BLOCKS`.
+    pub max_combined_uniform_blocks: usize,
+    /// `GL_MAX_VERTEX_UNIFORM_COMPONENTS`, for uniforms outside of blocks.
+    pub max_vertex_uniform_components: usize,
+    /// `GL_MAX_FRAGMENT_UNIFORM_COMPONENTS`, for uniforms outside of blocks.
+    pub max_fragment_uniform_components: usize,
+}
+
+impl UniformLimits {
+    /// The minimums that every OpenGL ES 3.0 and WebGL 2 implementation guarantees, which is the
+    /// portable budget.
+    pub const WEBGL2: UniformLimits = UniformLimits {
+        max_uniform_block_size: 16384,
+        max_vertex_uniform_blocks: 12,
+        max_fragment_uniform_blocks: 12,
+        max_combined_uniform_blocks: 24,
+        max_vertex_uniform_components: 1024,
+        max_fragment_uniform_components: 896,
+    };
+
+    /// `GL_MAX_COMBINED_VERTEX_UNIFORM_COMPONENTS`, with the value the specification requires.
+    pub fn max_combined_vertex_uniform_components(&self) -> usize {
+        self.max_vertex_uniform_blocks * self.max_uniform_block_size / 4
+            + self.max_vertex_uniform_components
+    }
+
+    /// `GL_MAX_COMBINED_FRAGMENT_UNIFORM_COMPONENTS`, with the value the specification requires.
+    pub fn max_combined_fragment_uniform_components(&self) -> usize {
+        self.max_fragment_uniform_blocks * self.max_uniform_block_size / 4
+            + self.max_fragment_uniform_components
+    }
+}
+
+/// The shader stages that use a [ProgramUniforms] entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderStages {
+    /// The vertex shader only.
+    Vertex,
+    /// The fragment shader only.
+    Fragment,
+    /// Both shaders.
+    Both,
+}
+
+impl ShaderStages {
+    fn vertex(self) -> bool {
+        self != ShaderStages::Fragment
+    }
+
+    fn fragment(self) -> bool {
+        self != ShaderStages::Vertex
+    }
+}
+
+/// A struct used by a program, either as a uniform block or as plain uniforms outside of blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProgramUniforms {
+    /// The name of the block or struct, for reports.
+    pub name: &'static str,
+    /// The std140 size of the struct in bytes.
+    pub size: usize,
+    /// The stages that use it.
+    pub stages: ShaderStages,
+    /// Whether its members are plain uniforms rather than a uniform block.
+    pub default_block: bool,
+}
+
+impl ProgramUniforms {
+    /// A uniform block holding `T`.
+    pub fn block<T: Std140Sized>(name: &'static str, stages: ShaderStages) -> Self {
+        ProgramUniforms { name, size: T::SIZE, stages, default_block: false }
+    }
+
+    /// The members of `T` declared as plain uniforms, conservatively counted as one component per
+    /// 4 bytes of its std140 size.
+    pub fn default_block<T: Std140Sized>(name: &'static str, stages: ShaderStages) -> Self {
+        ProgramUniforms { name, size: T::SIZE, stages, default_block: true }
+    }
+}
+
+/// A limit of [UniformLimits] that a program exceeds.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LimitViolation {
+    /// The name of the limit, such as `GL_MAX_UNIFORM_BLOCK_SIZE`.
+    pub limit: &'static str,
+    /// The block that exceeds it, for limits on a single block.
+    pub block: Option<&'static str>,
+    /// What the program needs.
+    pub required: usize,
+    /// The value of the limit.
+    pub max: usize,
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(block) = self.block {
+            write!(f, "{}: ", block)?;
+        }
+        write!(f, "{} exceeds {} of {}", self.required, self.limit, self.max)
+    }
+}
+
+/// Check the uniforms of one program against `limits`, such as [UniformLimits::WEBGL2] to audit
+/// the portable budget at startup, and return every limit it exceeds.
+///
+/// Blocks used by both stages count toward the limits of each, as they do in OpenGL.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::gl::{check_uniform_limits, ProgramUniforms, ShaderStages, UniformLimits};
+///
+/// let program = [
+///     ProgramUniforms::block::<[mint::ColumnMatrix4<f32>; 320]>("Bones", ShaderStages::Vertex),
+///     ProgramUniforms::block::<[mint::Vector4<f32>; 64]>("Lights", ShaderStages::Fragment),
+/// ];
+/// let violations = check_uniform_limits(&program, &UniformLimits::WEBGL2);
+/// assert_eq!(violations.len(), 1);
+/// assert_eq!(
+///     violations[0].to_string(),
+///     "Bones: 20480 exceeds GL_MAX_UNIFORM_BLOCK_SIZE of 16384"
+/// );
+/// ```
+pub fn check_uniform_limits(
+    program: &[ProgramUniforms],
+    limits: &UniformLimits,
+) -> Vec<LimitViolation> {
+    let mut violations = Vec::new();
+    let mut check = |limit, block, required, max| {
+        if required > max {
+            violations.push(LimitViolation { limit, block, required, max });
+        }
+    };
+
+    let blocks = || program.iter().filter(|uniforms| !uniforms.default_block);
+    for block in blocks() {
+        check(
+            "GL_MAX_UNIFORM_BLOCK_SIZE",
+            Some(block.name),
+            block.size,
+            limits.max_uniform_block_size,
+        );
+    }
+    let vertex_blocks = blocks().filter(|block| block.stages.vertex()).count();
+    let fragment_blocks = blocks().filter(|block| block.stages.fragment()).count();
+    check("GL_MAX_VERTEX_UNIFORM_BLOCKS", None, vertex_blocks, limits.max_vertex_uniform_blocks);
+    check(
+        "GL_MAX_FRAGMENT_UNIFORM_BLOCKS",
+        None,
+        fragment_blocks,
+        limits.max_fragment_uniform_blocks,
+    );
+    check(
+        "GL_MAX_COMBINED_UNIFORM_BLOCKS",
+        None,
+        vertex_blocks + fragment_blocks,
+        limits.max_combined_uniform_blocks,
+    );
+
+    let components = |stage: fn(ShaderStages) -> bool, default_block_only: bool| -> usize {
+        program
+            .iter()
+            .filter(|uniforms| {
+                stage(uniforms.stages) && (uniforms.default_block || !default_block_only)
+            })
+            .map(|uniforms| uniforms.size / 4)
+            .sum()
+    };
+    check(
+        "GL_MAX_VERTEX_UNIFORM_COMPONENTS",
+        None,
+        components(ShaderStages::vertex, true),
+        limits.max_vertex_uniform_components,
+    );
+    check(
+        "GL_MAX_FRAGMENT_UNIFORM_COMPONENTS",
+        None,
+        components(ShaderStages::fragment, true),
+        limits.max_fragment_uniform_components,
+    );
+    check(
+        "GL_MAX_COMBINED_VERTEX_UNIFORM_COMPONENTS",
+        None,
+        components(ShaderStages::vertex, false),
+        limits.max_combined_vertex_uniform_components(),
+    );
+    check(
+        "GL_MAX_COMBINED_FRAGMENT_UNIFORM_COMPONENTS",
+        None,
+        components(ShaderStages::fragment, false),
+        limits.max_combined_fragment_uniform_components(),
+    );
+    violations
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{
+        check_uniform_limits, expected_uniforms, validate_uniform_block, LayoutMismatch,
+        ProgramUniforms, ShaderStages, UniformLimits,
+    };
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        intensity: f32,
+    }
+
+    #[derive(AsStd140)]
+    struct Uniforms {
+        transform: mint::ColumnMatrix4<f32>,
+        weights: [f32; 3],
+        lights: [Light; 2],
+    }
+
+    #[test]
+    fn validate() {
+        let mut queried = expected_uniforms::<Uniforms>();
+        let names: Vec<_> = queried.iter().map(|uniform| uniform.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "transform",
+                "weights[0]",
+                "lights[0].position",
+                "lights[0].intensity",
+                "lights[1].position",
+                "lights[1].intensity"
+            ]
+        );
+        assert_eq!(queried[5].offset, 140);
+        assert!(validate_uniform_block::<Uniforms>("", &queried).is_empty());
+
+        queried[0].is_row_major = true;
+        queried[3].offset = 116;
+        queried[4].name = "lights[1].color".to_string();
+        assert_eq!(
+            validate_uniform_block::<Uniforms>("", &queried),
+            [
+                LayoutMismatch::RowMajor("transform".to_string()),
+                LayoutMismatch::Offset {
+                    name: "lights[0].intensity".to_string(),
+                    expected: 124,
+                    actual: 116
+                },
+                LayoutMismatch::Unexpected("lights[1].color".to_string()),
+                LayoutMismatch::Missing("lights[1].position".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let light = ProgramUniforms::block::<Light>("Light", ShaderStages::Both);
+        let program = [light; 12];
+        assert!(check_uniform_limits(&program, &UniformLimits::WEBGL2).is_empty());
+
+        let mut program = [light; 13].to_vec();
+        program.push(ProgramUniforms::default_block::<[[f32; 4]; 240]>(
+            "Params",
+            ShaderStages::Fragment,
+        ));
+        let limits: Vec<_> = check_uniform_limits(&program, &UniformLimits::WEBGL2)
+            .into_iter()
+            .map(|violation| (violation.limit, violation.required))
+            .collect();
+        assert_eq!(
+            limits,
+            [
+                ("GL_MAX_VERTEX_UNIFORM_BLOCKS", 13),
+                ("GL_MAX_FRAGMENT_UNIFORM_BLOCKS", 13),
+                ("GL_MAX_COMBINED_UNIFORM_BLOCKS", 26),
+                ("GL_MAX_FRAGMENT_UNIFORM_COMPONENTS", 3840),
+            ]
+        );
+    }
+}
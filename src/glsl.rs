@@ -0,0 +1,124 @@
+use crate::{Endian, Layout, LayoutKind, ScalarKind, Std140Bytes, Std140Layout, Std140Value};
+use core::fmt;
+
+/// Formats a value as the GLSL literal of its std140 type, such as `vec3(1.0, 2.0, 3.0)`, with
+/// both [Display](fmt::Display) and [Debug](fmt::Debug).
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::GlslLiteral;
+///
+/// let vector = mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 };
+/// assert_eq!(GlslLiteral(&vector).to_string(), "vec3(1.0, 2.0, 3.0)");
+///
+/// let array = [mint::Vector2 { x: 1u32, y: 2 }, mint::Vector2 { x: 3u32, y: 4 }];
+/// assert_eq!(format!("{:?}", GlslLiteral(&array)), "uvec2[2](uvec2(1u, 2u), uvec2(3u, 4u))");
+/// ```
+pub struct GlslLiteral<'a, T>(pub &'a T);
+
+impl<T: Std140Bytes + Std140Layout> fmt::Display for GlslLiteral<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.0.as_std140_bytes();
+        write_literal(f, &T::layout(), &bytes, 0)
+    }
+}
+
+impl<T: Std140Bytes + Std140Layout> fmt::Debug for GlslLiteral<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+fn write_literal(
+    f: &mut fmt::Formatter<'_>,
+    layout: &Layout,
+    bytes: &[u8],
+    offset: usize,
+) -> fmt::Result {
+    let scalar =
+        |scalar, offset: usize| Std140Value::read(scalar, &bytes[offset..], Endian::NATIVE);
+
+    match &layout.kind {
+        // Literals of 8-bit and 16-bit types need a constructor, since they only convert
+        // implicitly to wider types.
+        LayoutKind::Scalar(kind) if kind.size() < 4 => {
+            write!(f, "{}({})", kind.glsl_name(), scalar(*kind, offset))
+        }
+        LayoutKind::Scalar(kind) => write!(f, "{}", scalar(*kind, offset)),
+        LayoutKind::Vector(kind, len) => {
+            write!(f, "{}(", layout.glsl_type())?;
+            for i in 0..*len {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", scalar(*kind, offset + kind.size() * i))?;
+            }
+            f.write_str(")")
+        }
+        LayoutKind::Matrix { columns, rows } => {
+            write!(f, "{}(", layout.glsl_type())?;
+            for column in 0..*columns {
+                for row in 0..*rows {
+                    if column > 0 || row > 0 {
+                        f.write_str(", ")?;
+                    }
+                    let value = scalar(ScalarKind::Float, offset + 16 * column + 4 * row);
+                    write!(f, "{}", value)?;
+                }
+            }
+            f.write_str(")")
+        }
+        LayoutKind::Array { element, len } => {
+            write!(f, "{}(", layout.glsl_type())?;
+            for i in 0..*len {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_literal(f, element, bytes, offset + i * element.array_stride())?;
+            }
+            f.write_str(")")
+        }
+        LayoutKind::Struct { name, fields } => {
+            write!(f, "{}(", name)?;
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_literal(f, &field.layout, bytes, offset + field.offset)?;
+            }
+            f.write_str(")")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlslLiteral;
+
+    #[test]
+    fn literals() {
+        assert_eq!(GlslLiteral(&-1i32).to_string(), "-1");
+        assert_eq!(GlslLiteral(&true).to_string(), "true");
+        assert_eq!(GlslLiteral(&mint::Vector2 { x: 1i32, y: 2 }).to_string(), "ivec2(1, 2)");
+
+        let matrix = mint::ColumnMatrix3x2 {
+            x: mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 },
+            y: mint::Vector3 { x: 4.0f32, y: 5.0, z: 6.5 },
+        };
+        assert_eq!(GlslLiteral(&matrix).to_string(), "mat2x3(1.0, 2.0, 3.0, 4.0, 5.0, 6.5)");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn structs() {
+        #[derive(crate::AsStd140)]
+        struct Light {
+            color: mint::Vector3<f32>,
+            intensity: f32,
+        }
+
+        let light = Light { color: mint::Vector3 { x: 1.0, y: 0.5, z: 0.0 }, intensity: 2.0 };
+        assert_eq!(GlslLiteral(&light).to_string(), "Light(vec3(1.0, 0.5, 0.0), 2.0)");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::__private::align_up;
+use crate::bytes::{array_stride, debug_check_offset};
+use crate::{Endian, Std140Bytes};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::Range;
+
+/// Packs per-instance transforms and data into uniform blocks for instanced draws that look up
+/// their parameters with `gl_InstanceID` instead of reading vertex attributes.
+///
+/// Each block holds an instance count followed by a fixed-length array, matching
+///
+/// ```glsl
+/// struct Instance {
+///     mat4 transform;
+///     Data data; // Your per-instance type.
+/// };
+///
+/// layout(std140) uniform Instances {
+///     uint count;
+///     Instance instances[CAPACITY];
+/// };
+/// ```
+///
+/// where `CAPACITY` is [InstancePacker::capacity]. Instances that do not fit in one block are
+/// split into several batches, one draw call each.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::InstancePacker;
+///
+/// let identity: mint::ColumnMatrix4<f32> = [
+///     [1.0, 0.0, 0.0, 0.0],
+///     [0.0, 1.0, 0.0, 0.0],
+///     [0.0, 0.0, 1.0, 0.0],
+///     [0.0, 0.0, 0.0, 1.0],
+/// ]
+/// .into();
+/// let instances = vec![(identity, mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 1.0 }); 300];
+///
+/// let packer = InstancePacker::new(16384);
+/// assert_eq!(packer.capacity(), 204);
+///
+/// let batches = packer.pack(&instances);
+/// assert_eq!(batches.len(), 2);
+/// assert_eq!(batches[1].instances, 204..300);
+/// assert_eq!(batches[1].bytes.len(), packer.block_size());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct InstancePacker<T> {
+    capacity: usize,
+    endian: Endian,
+    marker: PhantomData<fn(&T)>,
+}
+
+/// A block of instances packed by an [InstancePacker].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstanceBatch {
+    /// The indices of the packed instances, whose instance IDs in the draw call start from 0.
+    pub instances: Range<usize>,
+    /// The bytes of the uniform block, padded to [InstancePacker::block_size].
+    pub bytes: Vec<u8>,
+}
+
+impl<T: Std140Bytes> InstancePacker<T> {
+    /// The offset of the instance array in each block, after the `uint` count.
+    pub const ARRAY_OFFSET: usize = 16;
+
+    /// The offset of the per-instance data in each array element, after the `mat4` transform.
+    pub const DATA_OFFSET: usize = align_up(64, T::ALIGN);
+
+    /// The distance in bytes between consecutive instances.
+    pub const STRIDE: usize = array_stride(Self::DATA_OFFSET + T::SIZE);
+
+    /// Create a packer for blocks of at most `max_uniform_block_size` bytes that packs in the host
+    /// byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not even one instance fits in a block.
+    pub fn new(max_uniform_block_size: usize) -> Self {
+        Self::with_endian(max_uniform_block_size, Endian::NATIVE)
+    }
+
+    /// Create a packer that packs in the given byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not even one instance fits in a block.
+    pub fn with_endian(max_uniform_block_size: usize, endian: Endian) -> Self {
+        let capacity = max_uniform_block_size.saturating_sub(Self::ARRAY_OFFSET) / Self::STRIDE;
+        assert!(
+            capacity > 0,
+            "a {}-byte block cannot hold a {}-byte instance",
+            max_uniform_block_size,
+            Self::STRIDE
+        );
+        InstancePacker { capacity, endian, marker: PhantomData }
+    }
+
+    /// The number of instances per block, which is the length the shader should declare the
+    /// instance array with.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The size in bytes of each block.
+    pub fn block_size(&self) -> usize {
+        Self::ARRAY_OFFSET + self.capacity * Self::STRIDE
+    }
+
+    /// Pack `instances` into as many blocks as needed.
+    pub fn pack(&self, instances: &[(mint::ColumnMatrix4<f32>, T)]) -> Vec<InstanceBatch> {
+        instances
+            .chunks(self.capacity)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut bytes = vec![0; self.block_size()];
+                (chunk.len() as u32).write_std140(&mut bytes, self.endian);
+                for (j, (transform, data)) in chunk.iter().enumerate() {
+                    let offset = Self::ARRAY_OFFSET + j * Self::STRIDE;
+                    debug_check_offset::<T>(offset + Self::DATA_OFFSET);
+                    transform.write_std140(&mut bytes[offset..], self.endian);
+                    data.write_std140(&mut bytes[offset + Self::DATA_OFFSET..], self.endian);
+                }
+                let start = i * self.capacity;
+                InstanceBatch { instances: start..start + chunk.len(), bytes }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstancePacker;
+    use crate::Endian;
+
+    #[test]
+    fn batches() {
+        let transform: mint::ColumnMatrix4<f32> = [[2.0; 4]; 4].into();
+        let instances: Vec<_> = (0..4).map(|i| (transform, i as u32)).collect();
+
+        let packer = InstancePacker::<u32>::with_endian(256, Endian::Little);
+        assert_eq!(InstancePacker::<u32>::STRIDE, 80);
+        assert_eq!((packer.capacity(), packer.block_size()), (3, 256));
+
+        let batches = packer.pack(&instances);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(&batches[0].bytes[0..4], &3u32.to_le_bytes());
+        assert_eq!(&batches[0].bytes[16..20], &2.0f32.to_le_bytes());
+        assert_eq!(&batches[0].bytes[160..164], &1u32.to_le_bytes());
+        assert_eq!(&batches[1].bytes[0..4], &1u32.to_le_bytes());
+        assert_eq!(&batches[1].bytes[80..84], &3u32.to_le_bytes());
+        assert_eq!(&batches[1].bytes[96..], &[0; 160][..]);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::cache::fnv1a;
+use crate::{Endian, Std140Bytes};
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+/// A growable buffer that stores every distinct std140 block once, for sharing parameter blocks
+/// between the many objects that use identical values.
+///
+/// Interning returns the location of the block in the shared buffer. Locations stay valid until
+/// [Std140Interner::clear], since the buffer only ever grows.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140Interner;
+///
+/// let mut interner = Std140Interner::new(256);
+/// let red = interner.intern_value(&mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 1.0 });
+/// let blue = interner.intern_value(&mint::Vector4 { x: 0.0f32, y: 0.0, z: 1.0, w: 1.0 });
+/// let red_again = interner.intern_value(&mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 1.0 });
+///
+/// assert_eq!(red, red_again);
+/// assert_eq!(blue.offset, 256);
+/// assert_eq!(interner.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Std140Interner {
+    bytes: Vec<u8>,
+    blocks: BTreeMap<u64, Vec<InternedBlock>>,
+    len: usize,
+    align: usize,
+    endian: Endian,
+}
+
+/// The location of a block in the buffer of a [Std140Interner].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InternedBlock {
+    /// The offset of the block from the start of the buffer.
+    pub offset: usize,
+    /// The size of the block in bytes.
+    pub size: usize,
+}
+
+impl Std140Interner {
+    /// Create an empty interner whose blocks start at multiples of `align` bytes, such as the
+    /// device's minimum uniform buffer offset alignment, and which packs values in the host byte
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16.
+    pub fn new(align: usize) -> Self {
+        Self::with_endian(align, Endian::NATIVE)
+    }
+
+    /// Create an empty interner that packs values in the given byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two of at least 16.
+    pub fn with_endian(align: usize, endian: Endian) -> Self {
+        assert!(align.is_power_of_two() && align >= 16, "invalid block alignment {}", align);
+        Std140Interner { bytes: Vec::new(), blocks: BTreeMap::new(), len: 0, align, endian }
+    }
+
+    /// The number of distinct blocks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no blocks have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Store `bytes` unless an identical block is already stored, and return its location.
+    pub fn intern(&mut self, bytes: &[u8]) -> InternedBlock {
+        let candidates = self.blocks.entry(fnv1a(bytes)).or_default();
+        let stored = &self.bytes;
+        let existing = candidates
+            .iter()
+            .find(|block| &stored[block.offset..block.offset + block.size] == bytes);
+        if let Some(block) = existing {
+            return *block;
+        }
+
+        let offset = (self.bytes.len() + self.align - 1) & !(self.align - 1);
+        self.bytes.resize(offset, 0);
+        self.bytes.extend_from_slice(bytes);
+        let block = InternedBlock { offset, size: bytes.len() };
+        candidates.push(block);
+        self.len += 1;
+        block
+    }
+
+    /// Pack `value` and intern its bytes.
+    pub fn intern_value<T: Std140Bytes>(&mut self, value: &T) -> InternedBlock {
+        let mut bytes = vec![0; T::SIZE];
+        value.write_std140(&mut bytes, self.endian);
+        self.intern(&bytes)
+    }
+
+    /// The bytes of an interned block.
+    pub fn bytes_of(&self, block: InternedBlock) -> &[u8] {
+        &self.bytes[block.offset..block.offset + block.size]
+    }
+
+    /// The shared buffer holding every interned block, to upload to the GPU.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Remove every block, invalidating all returned locations.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.blocks.clear();
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140Interner;
+
+    #[test]
+    fn dedup() {
+        let mut interner = Std140Interner::new(64);
+        let a = interner.intern(&[1; 20]);
+        let b = interner.intern(&[2; 20]);
+        let c = interner.intern(&[1; 24]);
+        assert_eq!((a.offset, b.offset, c.offset), (0, 64, 128));
+        assert_eq!(interner.intern(&[2; 20]), b);
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.as_bytes().len(), 152);
+        assert_eq!(interner.bytes_of(b), &[2; 20]);
+
+        interner.clear();
+        assert!(interner.is_empty());
+        assert_eq!(interner.intern(&[2; 20]).offset, 0);
+    }
+}
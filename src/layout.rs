@@ -0,0 +1,744 @@
+use crate::bytes::array_stride;
+use crate::quantize::{f16_to_f32, f32_to_f16};
+use crate::{Endian, Quantization, Std140Error, Std140Sized};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The scalar type of a std140 component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScalarKind {
+    /// A 32-bit float.
+    Float,
+    /// A 32-bit signed integer.
+    Int,
+    /// A 32-bit unsigned integer.
+    Uint,
+    /// A 32-bit boolean.
+    Bool,
+    /// A 16-bit float, `float16_t` of `GL_EXT_shader_explicit_arithmetic_types`.
+    Float16,
+    /// An 8-bit signed integer, `int8_t`.
+    Int8,
+    /// An 8-bit unsigned integer, `uint8_t`.
+    Uint8,
+    /// A 16-bit signed integer, `int16_t`.
+    Int16,
+    /// A 16-bit unsigned integer, `uint16_t`.
+    Uint16,
+}
+
+impl ScalarKind {
+    /// The GLSL name of this scalar type.
+    pub fn glsl_name(self) -> &'static str {
+        match self {
+            ScalarKind::Float => "float",
+            ScalarKind::Int => "int",
+            ScalarKind::Uint => "uint",
+            ScalarKind::Bool => "bool",
+            ScalarKind::Float16 => "float16_t",
+            ScalarKind::Int8 => "int8_t",
+            ScalarKind::Uint8 => "uint8_t",
+            ScalarKind::Int16 => "int16_t",
+            ScalarKind::Uint16 => "uint16_t",
+        }
+    }
+
+    /// The prefix of GLSL vector types with this component type, such as `i` in `ivec3`.
+    pub fn glsl_vector_prefix(self) -> &'static str {
+        match self {
+            ScalarKind::Float => "",
+            ScalarKind::Int => "i",
+            ScalarKind::Uint => "u",
+            ScalarKind::Bool => "b",
+            ScalarKind::Float16 => "f16",
+            ScalarKind::Int8 => "i8",
+            ScalarKind::Uint8 => "u8",
+            ScalarKind::Int16 => "i16",
+            ScalarKind::Uint16 => "u16",
+        }
+    }
+
+    /// The size of this scalar type in bytes, which is also its alignment.
+    pub fn size(self) -> usize {
+        match self {
+            ScalarKind::Float | ScalarKind::Int | ScalarKind::Uint | ScalarKind::Bool => 4,
+            ScalarKind::Float16 | ScalarKind::Int16 | ScalarKind::Uint16 => 2,
+            ScalarKind::Int8 | ScalarKind::Uint8 => 1,
+        }
+    }
+
+    /// The 32-bit scalar type that holds every value of this one.
+    pub(crate) fn widened(self) -> Self {
+        match self {
+            ScalarKind::Float16 => ScalarKind::Float,
+            ScalarKind::Int8 | ScalarKind::Int16 => ScalarKind::Int,
+            ScalarKind::Uint8 | ScalarKind::Uint16 => ScalarKind::Uint,
+            scalar => scalar,
+        }
+    }
+}
+
+/// The shape of a std140 type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutKind {
+    /// A single scalar.
+    Scalar(ScalarKind),
+    /// A column vector of 2 to 4 scalars.
+    Vector(ScalarKind, usize),
+    /// A column-major float matrix, stored as an array of column vectors.
+    Matrix {
+        /// The number of columns.
+        columns: usize,
+        /// The number of rows, which is the length of each column.
+        rows: usize,
+    },
+    /// A fixed-length array.
+    Array {
+        /// The layout of each element.
+        element: Box<Layout>,
+        /// The number of elements.
+        len: usize,
+    },
+    /// A struct with named fields.
+    Struct {
+        /// The name of the struct type.
+        name: &'static str,
+        /// The fields, in declaration order.
+        fields: Vec<Field>,
+    },
+}
+
+/// The std140 layout of a type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    /// The size in bytes, same as [`Std140Sized::SIZE`].
+    pub size: usize,
+    /// The base alignment in bytes, same as [`Std140Sized::ALIGN`].
+    pub align: usize,
+    /// The shape of the type.
+    pub kind: LayoutKind,
+}
+
+/// A field of a struct [Layout].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The offset of the field from the start of the struct.
+    pub offset: usize,
+    /// The layout of the field.
+    pub layout: Layout,
+    /// How the field was quantized with `#[std140(quantize = ...)]`, in which case [Field::layout]
+    /// is the layout of the packed value.
+    pub quantization: Option<Quantization>,
+}
+
+/// A single scalar inside a [Layout].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Component {
+    /// The GLSL-style path to the scalar, such as `lights[1].color.y`.
+    pub path: String,
+    /// The offset of the scalar from the start of the layout.
+    pub offset: usize,
+    /// The type of the scalar.
+    pub scalar: ScalarKind,
+}
+
+/// A scalar value decoded from std140 bytes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Std140Value {
+    /// A float.
+    Float(f32),
+    /// A signed integer.
+    Int(i32),
+    /// An unsigned integer.
+    Uint(u32),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl Std140Value {
+    /// Decode a value of type `scalar` from the first [ScalarKind::size] bytes of `bytes`.
+    ///
+    /// 8-bit and 16-bit scalars are widened to the 32-bit value of the same kind.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than the scalar.
+    pub fn read(scalar: ScalarKind, bytes: &[u8], endian: Endian) -> Self {
+        let bits = endian.read_bits(bytes, scalar.size());
+        match scalar {
+            ScalarKind::Float => Std140Value::Float(f32::from_bits(bits)),
+            ScalarKind::Int => Std140Value::Int(bits as i32),
+            ScalarKind::Uint | ScalarKind::Uint8 | ScalarKind::Uint16 => Std140Value::Uint(bits),
+            ScalarKind::Bool => Std140Value::Bool(bits != 0),
+            ScalarKind::Float16 => Std140Value::Float(f16_to_f32(bits as u16)),
+            ScalarKind::Int8 => Std140Value::Int(bits as u8 as i8 as i32),
+            ScalarKind::Int16 => Std140Value::Int(bits as u16 as i16 as i32),
+        }
+    }
+
+    /// Encode this value as a scalar of type `scalar` into the first [ScalarKind::size] bytes of
+    /// `bytes`, converting it like a GLSL constructor such as `int(value)` would. Integers that
+    /// do not fit an 8-bit or 16-bit scalar keep their low bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than the scalar.
+    pub fn write(self, scalar: ScalarKind, bytes: &mut [u8], endian: Endian) {
+        let value = self.to_f64();
+        let bits = match scalar {
+            ScalarKind::Float => (value as f32).to_bits(),
+            ScalarKind::Float16 => f32_to_f16(value as f32) as u32,
+            ScalarKind::Int | ScalarKind::Int8 | ScalarKind::Int16 => value as i32 as u32,
+            ScalarKind::Uint | ScalarKind::Uint8 | ScalarKind::Uint16 => value as u32,
+            ScalarKind::Bool => (value != 0.0) as u32,
+        };
+        endian.write_bits(bytes, bits, scalar.size());
+    }
+
+    /// The value as a double, with booleans as 0 or 1.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Std140Value::Float(value) => value as f64,
+            Std140Value::Int(value) => value as f64,
+            Std140Value::Uint(value) => value as f64,
+            Std140Value::Bool(value) => value as u32 as f64,
+        }
+    }
+}
+
+impl fmt::Display for Std140Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Std140Value::Float(value) => write!(f, "{:?}", value),
+            Std140Value::Int(value) => write!(f, "{}", value),
+            Std140Value::Uint(value) => write!(f, "{}u", value),
+            Std140Value::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Layout {
+    /// The layout of a scalar.
+    pub fn scalar(scalar: ScalarKind) -> Self {
+        Layout { size: scalar.size(), align: scalar.size(), kind: LayoutKind::Scalar(scalar) }
+    }
+
+    /// The layout of a vector of `len` scalars.
+    pub fn vector(scalar: ScalarKind, len: usize) -> Self {
+        Layout {
+            size: scalar.size() * len,
+            align: scalar.size() * if len == 3 { 4 } else { len },
+            kind: LayoutKind::Vector(scalar, len),
+        }
+    }
+
+    /// The layout of a float matrix.
+    pub fn matrix(columns: usize, rows: usize) -> Self {
+        Layout { size: 16 * columns, align: 16, kind: LayoutKind::Matrix { columns, rows } }
+    }
+
+    /// The layout of an array of `len` elements.
+    pub fn array(element: Layout, len: usize) -> Self {
+        Layout {
+            size: element.array_stride() * len,
+            align: 16,
+            kind: LayoutKind::Array { element: Box::new(element), len },
+        }
+    }
+
+    /// The layout of a struct, given fields with their offsets.
+    pub fn structure(name: &'static str, fields: Vec<Field>) -> Self {
+        let end = fields.iter().map(|field| field.offset + field.layout.size).max().unwrap_or(0);
+        Layout { size: array_stride(end), align: 16, kind: LayoutKind::Struct { name, fields } }
+    }
+
+    /// The layout of a GLSL scalar, vector or matrix type, such as `uint`, `vec3` or `mat4x2`.
+    pub fn from_glsl_type(name: &str) -> Option<Self> {
+        let scalar = match name {
+            "float" => Some(ScalarKind::Float),
+            "int" => Some(ScalarKind::Int),
+            "uint" => Some(ScalarKind::Uint),
+            "bool" => Some(ScalarKind::Bool),
+            "float16_t" => Some(ScalarKind::Float16),
+            "int8_t" => Some(ScalarKind::Int8),
+            "uint8_t" => Some(ScalarKind::Uint8),
+            "int16_t" => Some(ScalarKind::Int16),
+            "uint16_t" => Some(ScalarKind::Uint16),
+            _ => None,
+        };
+        if let Some(scalar) = scalar {
+            return Some(Layout::scalar(scalar));
+        }
+
+        let dimension = |digit: &str| match digit {
+            "2" => Some(2),
+            "3" => Some(3),
+            "4" => Some(4),
+            _ => None,
+        };
+        if let Some(dimensions) = name.strip_prefix("mat") {
+            return match dimensions.len() {
+                1 => dimension(dimensions).map(|n| Layout::matrix(n, n)),
+                3 if &dimensions[1..2] == "x" => {
+                    Some(Layout::matrix(dimension(&dimensions[..1])?, dimension(&dimensions[2..])?))
+                }
+                _ => None,
+            };
+        }
+
+        let (scalar, len) = match name.split_once("vec")? {
+            ("", len) => (ScalarKind::Float, len),
+            ("i", len) => (ScalarKind::Int, len),
+            ("u", len) => (ScalarKind::Uint, len),
+            ("b", len) => (ScalarKind::Bool, len),
+            ("f16", len) => (ScalarKind::Float16, len),
+            ("i8", len) => (ScalarKind::Int8, len),
+            ("u8", len) => (ScalarKind::Uint8, len),
+            ("i16", len) => (ScalarKind::Int16, len),
+            ("u16", len) => (ScalarKind::Uint16, len),
+            _ => return None,
+        };
+        Some(Layout::vector(scalar, dimension(len)?))
+    }
+
+    /// The distance between consecutive elements of an array of this type.
+    pub fn array_stride(&self) -> usize {
+        array_stride(self.size)
+    }
+
+    /// The GLSL name of this type, such as `vec3`, `mat4x3` or `Light[4]`.
+    pub fn glsl_type(&self) -> String {
+        match &self.kind {
+            LayoutKind::Scalar(scalar) => scalar.glsl_name().to_string(),
+            LayoutKind::Vector(scalar, len) => format!("{}vec{}", scalar.glsl_vector_prefix(), len),
+            LayoutKind::Matrix { columns, rows } if columns == rows => format!("mat{}", columns),
+            LayoutKind::Matrix { columns, rows } => format!("mat{}x{}", columns, rows),
+            LayoutKind::Array { element, len } => format!("{}[{}]", element.glsl_type(), len),
+            LayoutKind::Struct { name, .. } => name.to_string(),
+        }
+    }
+
+    /// Every scalar in this layout, in offset order.
+    pub fn components(&self) -> Vec<Component> {
+        let mut components = Vec::new();
+        self.collect_components("", 0, &mut components);
+        components
+    }
+
+    /// The number of bytes in this layout that hold a scalar, which is its size minus padding.
+    pub(crate) fn scalar_bytes(&self) -> usize {
+        self.components().iter().map(|component| component.scalar.size()).sum()
+    }
+
+    /// The offset of the member at a GLSL-style `path` from the start of the layout, such as
+    /// `lights[2].color`, `color.y` or `transform[3][1]`, or `None` if there is no such member.
+    ///
+    /// This is the runtime counterpart of [std140_offset_of!](crate::std140_offset_of), for tools
+    /// that only know field names as strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mint_std140::{AsStd140, Std140Layout};
+    ///
+    /// #[derive(AsStd140)]
+    /// struct Light {
+    ///     position: mint::Vector3<f32>,
+    ///     color: mint::Vector3<f32>,
+    /// }
+    ///
+    /// #[derive(AsStd140)]
+    /// struct Scene {
+    ///     ambient: f32,
+    ///     lights: [Light; 4],
+    /// }
+    ///
+    /// let layout = Scene::layout();
+    /// assert_eq!(layout.offset_of("lights[2].color"), Some(96));
+    /// assert_eq!(layout.offset_of("lights[2].color.y"), Some(100));
+    /// assert_eq!(layout.offset_of("lights[4]"), None);
+    /// ```
+    pub fn offset_of(&self, path: &str) -> Option<usize> {
+        let mut layout = self;
+        let mut offset = 0;
+        let mut segments = path_segments(path)?.into_iter().peekable();
+        while let Some(segment) = segments.next() {
+            match (&layout.kind, segment) {
+                (LayoutKind::Struct { fields, .. }, PathSegment::Field(name)) => {
+                    let field = fields.iter().find(|field| field.name == name)?;
+                    offset += field.offset;
+                    layout = &field.layout;
+                }
+                (LayoutKind::Array { element, len }, PathSegment::Index(index)) if index < *len => {
+                    offset += index * element.array_stride();
+                    layout = element;
+                }
+                (LayoutKind::Vector(scalar, len), segment) => {
+                    let component = segment.component(*len)?;
+                    return segments.next().is_none().then_some(offset + scalar.size() * component);
+                }
+                (LayoutKind::Matrix { columns, rows }, PathSegment::Index(column))
+                    if column < *columns =>
+                {
+                    offset += 16 * column;
+                    return match segments.next() {
+                        None => Some(offset),
+                        Some(segment) => {
+                            let row = segment.component(*rows)?;
+                            segments.next().is_none().then_some(offset + 4 * row)
+                        }
+                    };
+                }
+                _ => return None,
+            }
+        }
+        Some(offset)
+    }
+
+    /// The struct types used by this layout, including itself, with nested structs ordered before
+    /// the structs that use them and each struct listed once.
+    pub(crate) fn struct_definitions(&self) -> Vec<(&'static str, &[Field])> {
+        let mut definitions = Vec::new();
+        self.collect_struct_definitions(&mut definitions);
+        definitions
+    }
+
+    fn collect_struct_definitions<'a>(
+        &'a self,
+        definitions: &mut Vec<(&'static str, &'a [Field])>,
+    ) {
+        match &self.kind {
+            LayoutKind::Array { element, .. } => element.collect_struct_definitions(definitions),
+            LayoutKind::Struct { name, fields } => {
+                for field in fields {
+                    field.layout.collect_struct_definitions(definitions);
+                }
+                if !definitions.iter().any(|(existing, _)| existing == name) {
+                    definitions.push((name, fields));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The GLSL declaration of a member of this type, such as `vec3 color` or `Light lights[4]`.
+    pub(crate) fn glsl_declaration(&self, name: &str) -> String {
+        let mut dimensions = String::new();
+        let mut layout = self;
+        while let LayoutKind::Array { element, len } = &layout.kind {
+            dimensions.push_str(&format!("[{}]", len));
+            layout = element;
+        }
+        format!("{} {}{}", layout.glsl_type(), name, dimensions)
+    }
+
+    fn collect_components(&self, path: &str, offset: usize, components: &mut Vec<Component>) {
+        const SWIZZLE: [&str; 4] = ["x", "y", "z", "w"];
+
+        match &self.kind {
+            LayoutKind::Scalar(scalar) => {
+                components.push(Component { path: path.to_string(), offset, scalar: *scalar })
+            }
+            LayoutKind::Vector(scalar, len) => {
+                for (i, name) in SWIZZLE.iter().enumerate().take(*len) {
+                    let path = if path.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{}.{}", path, name)
+                    };
+                    let offset = offset + scalar.size() * i;
+                    Layout::scalar(*scalar).collect_components(&path, offset, components);
+                }
+            }
+            LayoutKind::Matrix { columns, rows } => {
+                for column in 0..*columns {
+                    for row in 0..*rows {
+                        components.push(Component {
+                            path: format!("{}[{}][{}]", path, column, row),
+                            offset: offset + 16 * column + 4 * row,
+                            scalar: ScalarKind::Float,
+                        });
+                    }
+                }
+            }
+            LayoutKind::Array { element, len } => {
+                for i in 0..*len {
+                    let path = format!("{}[{}]", path, i);
+                    element.collect_components(
+                        &path,
+                        offset + i * element.array_stride(),
+                        components,
+                    );
+                }
+            }
+            LayoutKind::Struct { fields, .. } => {
+                for field in fields {
+                    let path = if path.is_empty() {
+                        field.name.to_string()
+                    } else {
+                        format!("{}.{}", path, field.name)
+                    };
+                    field.layout.collect_components(&path, offset + field.offset, components);
+                }
+            }
+        }
+    }
+}
+
+/// A step of a member path given to [Layout::offset_of].
+enum PathSegment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+impl PathSegment<'_> {
+    /// The index of the vector component this segment selects, either by name or by index.
+    fn component(&self, len: usize) -> Option<usize> {
+        let index = match *self {
+            PathSegment::Field(name) => ["x", "y", "z", "w"].iter().position(|&x| x == name)?,
+            PathSegment::Index(index) => index,
+        };
+        (index < len).then_some(index)
+    }
+}
+
+fn path_segments(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+    let mut first = true;
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let (index, after) = index.split_once(']')?;
+            segments.push(PathSegment::Index(index.trim().parse().ok()?));
+            rest = after;
+        } else {
+            let name = if first { rest } else { rest.strip_prefix('.')? };
+            let end = name.find(['.', '[']).unwrap_or(name.len());
+            if end == 0 {
+                return None;
+            }
+            segments.push(PathSegment::Field(&name[..end]));
+            rest = &name[end..];
+        }
+        first = false;
+    }
+    Some(segments)
+}
+
+/// A type whose std140 layout can be inspected at runtime.
+pub trait Std140Layout: Std140Sized {
+    /// The std140 layout of this type.
+    fn layout() -> Layout;
+
+    /// A text table of every member of this type, with its GLSL type, offset, size and the
+    /// padding that follows it, for pasting into code reviews when a block changes.
+    ///
+    /// Members of nested structs follow the member that holds them, with absolute offsets. For
+    /// arrays of structs, the members of the first element are listed. The last line sums up the
+    /// size and all bytes that do not hold a scalar, including array element padding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mint_std140::{AsStd140, Std140Layout};
+    ///
+    /// #[derive(AsStd140)]
+    /// struct Material {
+    ///     roughness: f32,
+    ///     tint: mint::Vector3<f32>,
+    ///     weights: [f32; 2],
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Material::layout_report(),
+    ///     "field      type      offset  size  padding\n\
+    ///      roughness  float          0     4       12\n\
+    ///      tint       vec3          16    12        4\n\
+    ///      weights    float[2]      32    32        0\n\
+    ///      size 64, 40 bytes of padding\n"
+    /// );
+    /// ```
+    fn layout_report() -> String {
+        crate::report::layout_report(&Self::layout())
+    }
+
+    /// The rows of [Std140Layout::layout_report] as CSV with a header line, for tools.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mint_std140::Std140Layout;
+    ///
+    /// let csv = <[mint::Vector2<f32>; 3]>::layout_report_csv();
+    /// assert_eq!(csv, "field,type,offset,size,padding\n,vec2[3],0,48,0\n");
+    /// ```
+    fn layout_report_csv() -> String {
+        crate::report::layout_report_csv(&Self::layout())
+    }
+
+    /// Check the fields of derived structs inside this value against their
+    /// `#[std140(range = ...)]` and `#[std140(assert_finite)]` attributes, which is what
+    /// derived [Std140Validate](crate::Std140Validate) implementations call for each of their
+    /// fields.
+    ///
+    /// Derived structs forward to [Std140Validate::validate](crate::Std140Validate::validate) and
+    /// arrays check each element. Other types have no attributes and accept every value, so
+    /// hand-written implementations of this trait do not need to override it.
+    fn validate_fields(&self) -> Result<(), Std140Error> {
+        Ok(())
+    }
+}
+
+/// Validate the field `name` of a derived struct, prefixing the paths in errors with it.
+#[doc(hidden)]
+pub fn validate_field<T: Std140Layout>(value: &T, name: &str) -> Result<(), Std140Error> {
+    value.validate_fields().map_err(|error| error.in_field(name))
+}
+
+macro_rules! impl_std140_layout_for_scalar {
+    ($rust_type:ty, $scalar:ident) => {
+        impl Std140Layout for $rust_type {
+            fn layout() -> Layout {
+                Layout::scalar(ScalarKind::$scalar)
+            }
+        }
+    };
+}
+
+impl_std140_layout_for_scalar!(f32, Float);
+impl_std140_layout_for_scalar!(i32, Int);
+impl_std140_layout_for_scalar!(u32, Uint);
+impl_std140_layout_for_scalar!(bool, Bool);
+impl_std140_layout_for_scalar!(crate::F16, Float16);
+impl_std140_layout_for_scalar!(i8, Int8);
+impl_std140_layout_for_scalar!(u8, Uint8);
+impl_std140_layout_for_scalar!(i16, Int16);
+impl_std140_layout_for_scalar!(u16, Uint16);
+
+macro_rules! impl_std140_layout_for_vector {
+    ($mint_type:ident, $len:expr) => {
+        impl<T> Std140Layout for mint::$mint_type<T>
+        where
+            T: Std140Layout,
+            Self: Std140Sized,
+        {
+            fn layout() -> Layout {
+                match T::layout().kind {
+                    LayoutKind::Scalar(scalar) => Layout::vector(scalar, $len),
+                    _ => unreachable!("vector components are scalars"),
+                }
+            }
+        }
+    };
+}
+
+impl_std140_layout_for_vector!(Vector2, 2);
+impl_std140_layout_for_vector!(Vector3, 3);
+impl_std140_layout_for_vector!(Vector4, 4);
+
+impl Std140Layout for mint::Quaternion<f32> {
+    fn layout() -> Layout {
+        Layout::vector(ScalarKind::Float, 4)
+    }
+}
+
+macro_rules! impl_std140_layout_for_column_matrix {
+    ($mint_type:ty, $columns:expr, $rows:expr) => {
+        impl Std140Layout for $mint_type {
+            fn layout() -> Layout {
+                Layout::matrix($columns, $rows)
+            }
+        }
+    };
+}
+
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix2<f32>, 2, 2);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix3x2<f32>, 2, 3);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix4x2<f32>, 2, 4);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix2x3<f32>, 3, 2);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix3<f32>, 3, 3);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix4x3<f32>, 3, 4);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix2x4<f32>, 4, 2);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix3x4<f32>, 4, 3);
+impl_std140_layout_for_column_matrix!(mint::ColumnMatrix4<f32>, 4, 4);
+
+impl<T: Std140Layout, const N: usize> Std140Layout for [T; N] {
+    fn layout() -> Layout {
+        Layout::array(T::layout(), N)
+    }
+
+    fn validate_fields(&self) -> Result<(), Std140Error> {
+        self.iter().enumerate().try_for_each(|(index, element)| {
+            element.validate_fields().map_err(|error| error.in_field(&format!("[{}]", index)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, ScalarKind, Std140Layout};
+
+    fn check<T: Std140Layout>(glsl_type: &str) {
+        let layout = T::layout();
+        assert_eq!(layout.size, T::SIZE, "{}", glsl_type);
+        assert_eq!(layout.align, T::ALIGN, "{}", glsl_type);
+        assert_eq!(layout.glsl_type(), glsl_type);
+    }
+
+    #[test]
+    fn primitives() {
+        check::<f32>("float");
+        check::<bool>("bool");
+        check::<mint::Vector3<f32>>("vec3");
+        check::<mint::Vector2<i32>>("ivec2");
+        check::<mint::Vector4<u32>>("uvec4");
+        check::<mint::ColumnMatrix2<f32>>("mat2");
+        check::<mint::ColumnMatrix3x2<f32>>("mat2x3");
+        check::<mint::ColumnMatrix2x4<f32>>("mat4x2");
+        check::<mint::ColumnMatrix4<f32>>("mat4");
+        check::<[mint::Vector2<f32>; 3]>("vec2[3]");
+    }
+
+    #[test]
+    fn components() {
+        let components = <[mint::ColumnMatrix3x2<f32>; 2]>::layout().components();
+        assert_eq!(components.len(), 12);
+        assert_eq!(components[4].path, "[0][1][1]");
+        assert_eq!(components[4].offset, 20);
+        assert_eq!(components[6].path, "[1][0][0]");
+        assert_eq!(components[6].offset, 32);
+        assert_eq!(Layout::vector(ScalarKind::Int, 3).components()[2].path, "z");
+    }
+
+    #[test]
+    fn offset_of() {
+        let layout = <[mint::ColumnMatrix3x2<f32>; 2]>::layout();
+        assert_eq!(layout.offset_of(""), Some(0));
+        assert_eq!(layout.offset_of("[1]"), Some(32));
+        assert_eq!(layout.offset_of("[1][1]"), Some(48));
+        assert_eq!(layout.offset_of("[1][1].y"), Some(52));
+        assert_eq!(layout.offset_of("[1][1][1]"), Some(52));
+        assert_eq!(layout.offset_of("[1][2]"), None);
+        assert_eq!(layout.offset_of("[0][0][3]"), None);
+        assert_eq!(layout.offset_of("[1]x"), None);
+        assert_eq!(Layout::vector(ScalarKind::Float, 3).offset_of("z"), Some(8));
+        assert_eq!(Layout::vector(ScalarKind::Float, 3).offset_of("w"), None);
+    }
+
+    #[test]
+    fn from_glsl_type() {
+        for name in
+            &["float", "bool", "vec2", "ivec3", "uvec4", "bvec2", "mat3", "mat2x4", "mat4x3"]
+        {
+            assert_eq!(Layout::from_glsl_type(name).unwrap().glsl_type(), *name);
+        }
+        for name in &["double", "vec5", "mat1", "mat2x", "mat2y3", "dvec2", "Light"] {
+            assert_eq!(Layout::from_glsl_type(name), None);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{math, Endian, Std140Bytes};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A value that can be interpolated between two states.
+pub trait Lerp {
+    /// The value `alpha` of the way from `self` to `other`, where `alpha` is usually between 0
+    /// and 1.
+    fn lerp(&self, other: &Self, alpha: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, alpha: f32) -> Self {
+        self + (other - self) * alpha
+    }
+}
+
+macro_rules! impl_lerp_for_fields {
+    ($mint_type:ty, [$($field:ident),+]) => {
+        impl Lerp for $mint_type {
+            fn lerp(&self, other: &Self, alpha: f32) -> Self {
+                Self { $($field: self.$field.lerp(&other.$field, alpha)),+ }
+            }
+        }
+    };
+}
+
+impl_lerp_for_fields!(mint::Vector2<f32>, [x, y]);
+impl_lerp_for_fields!(mint::Vector3<f32>, [x, y, z]);
+impl_lerp_for_fields!(mint::Vector4<f32>, [x, y, z, w]);
+
+// Matrices are interpolated per element, which is exact for translations and scales but shrinks
+// rotations part way through. Interpolate rotations as quaternions and compose the matrix
+// afterwards when that matters.
+impl_lerp_for_fields!(mint::ColumnMatrix2<f32>, [x, y]);
+impl_lerp_for_fields!(mint::ColumnMatrix3x2<f32>, [x, y]);
+impl_lerp_for_fields!(mint::ColumnMatrix4x2<f32>, [x, y]);
+impl_lerp_for_fields!(mint::ColumnMatrix2x3<f32>, [x, y, z]);
+impl_lerp_for_fields!(mint::ColumnMatrix3<f32>, [x, y, z]);
+impl_lerp_for_fields!(mint::ColumnMatrix4x3<f32>, [x, y, z]);
+impl_lerp_for_fields!(mint::ColumnMatrix2x4<f32>, [x, y, z, w]);
+impl_lerp_for_fields!(mint::ColumnMatrix3x4<f32>, [x, y, z, w]);
+impl_lerp_for_fields!(mint::ColumnMatrix4<f32>, [x, y, z, w]);
+
+/// Spherical linear interpolation along the shorter arc between unit quaternions.
+impl Lerp for mint::Quaternion<f32> {
+    fn lerp(&self, other: &Self, alpha: f32) -> Self {
+        let (a, mut b) =
+            ([self.v.x, self.v.y, self.v.z, self.s], [other.v.x, other.v.y, other.v.z, other.s]);
+        let mut cos = a.iter().zip(&b).map(|(a, b)| a * b).sum::<f32>();
+        if cos < 0.0 {
+            b = b.map(|b| -b);
+            cos = -cos;
+        }
+
+        // Nearly identical rotations would divide by a vanishing sine, and linear interpolation is
+        // indistinguishable there.
+        let (wa, wb) = if cos > 0.9995 {
+            (1.0 - alpha, alpha)
+        } else {
+            let angle = math::acos(cos);
+            let sin = |angle| math::sin_cos(angle).0;
+            (sin((1.0 - alpha) * angle) / sin(angle), sin(alpha * angle) / sin(angle))
+        };
+        let mut q: [f32; 4] = core::array::from_fn(|i| wa * a[i] + wb * b[i]);
+        let len = math::sqrt(q.iter().map(|c| c * c).sum::<f32>());
+        q = q.map(|c| c / len);
+        mint::Quaternion { v: mint::Vector3 { x: q[0], y: q[1], z: q[2] }, s: q[3] }
+    }
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    fn lerp(&self, other: &Self, alpha: f32) -> Self {
+        core::array::from_fn(|i| self[i].lerp(&other[i], alpha))
+    }
+}
+
+/// The previous and current states of a value updated at a fixed timestep, for rendering it at
+/// points in between.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{Endian, Interpolated};
+///
+/// let mut position = Interpolated::new(mint::Vector2 { x: 0.0f32, y: 0.0 });
+/// position.push(mint::Vector2 { x: 4.0, y: 2.0 });
+///
+/// // A quarter of the way into the current step.
+/// assert_eq!(position.lerp(0.25), mint::Vector2 { x: 1.0, y: 0.5 });
+/// let mut bytes = [0; 8];
+/// position.write_lerp(0.25, &mut bytes, Endian::Little);
+/// assert_eq!(bytes[4..8], 0.5f32.to_le_bytes());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interpolated<T> {
+    previous: T,
+    current: T,
+}
+
+impl<T: Lerp + Std140Bytes + Clone> Interpolated<T> {
+    /// Start with both states at `value`.
+    pub fn new(value: T) -> Self {
+        Interpolated { previous: value.clone(), current: value }
+    }
+
+    /// Make the current state the previous one and `value` the current one, at the end of a
+    /// simulation step.
+    pub fn push(&mut self, value: T) {
+        self.previous = core::mem::replace(&mut self.current, value);
+    }
+
+    /// Set both states to `value`, for teleports and other discontinuities.
+    pub fn reset(&mut self, value: T) {
+        self.previous = value.clone();
+        self.current = value;
+    }
+
+    /// The state before the last [Interpolated::push].
+    pub fn previous(&self) -> &T {
+        &self.previous
+    }
+
+    /// The state after the last [Interpolated::push].
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// The state `alpha` of the way from the previous to the current state.
+    pub fn lerp(&self, alpha: f32) -> T {
+        self.previous.lerp(&self.current, alpha)
+    }
+
+    /// Write the std140 bytes of [Interpolated::lerp] to the start of `bytes`.
+    pub fn write_lerp(&self, alpha: f32, bytes: &mut [u8], endian: Endian) {
+        self.lerp(alpha).write_std140(bytes, endian);
+    }
+
+    /// The std140 bytes of [Interpolated::lerp] in the given byte order.
+    #[cfg(feature = "alloc")]
+    pub fn lerp_bytes_with(&self, alpha: f32, endian: Endian) -> Vec<u8> {
+        self.lerp(alpha).as_std140_bytes_with(endian)
+    }
+
+    /// The std140 bytes of [Interpolated::lerp] in the host byte order.
+    #[cfg(feature = "alloc")]
+    pub fn lerp_bytes(&self, alpha: f32) -> Vec<u8> {
+        self.lerp_bytes_with(alpha, Endian::NATIVE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interpolated, Lerp};
+
+    #[test]
+    fn matrices() {
+        let mut scale = Interpolated::new(mint::ColumnMatrix2::from([[1.0f32, 0.0], [0.0, 1.0]]));
+        scale.push([[3.0, 0.0], [0.0, 5.0]].into());
+        assert_eq!(scale.lerp(0.5), [[2.0, 0.0], [0.0, 3.0]].into());
+
+        let weights = [0.0f32, 1.0].lerp(&[1.0, 0.0], 0.75);
+        assert_eq!(weights, [0.75, 0.25]);
+    }
+
+    #[test]
+    fn slerp() {
+        // Rotations by 0 and 90 degrees about z, the latter negated to take the shorter arc.
+        let identity = mint::Quaternion { v: [0.0, 0.0, 0.0].into(), s: 1.0f32 };
+        let half = core::f32::consts::FRAC_1_SQRT_2;
+        let quarter_turn = mint::Quaternion { v: [0.0, 0.0, -half].into(), s: -half };
+
+        let eighth_turn = identity.lerp(&quarter_turn, 0.5);
+        let expected = (core::f32::consts::PI / 8.0).sin_cos();
+        assert!((eighth_turn.v.z - expected.0).abs() < 1e-6);
+        assert!((eighth_turn.s - expected.1).abs() < 1e-6);
+    }
+}
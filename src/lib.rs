@@ -1,11 +1,14 @@
+#![cfg_attr(not(test), no_std)]
 #![deny(warnings)]
 #![deny(missing_docs)]
+#![cfg_attr(feature = "safe", deny(unsafe_code))]
 
-//! This library contains a single trait [AsStd140] which is implemented for [mint] types that can be converted to [std140] types.
+//! This library contains the trait [AsStd140] which is implemented for [mint] types that can be converted to [std140] types.
 //!
 //! # Examples
 //!
 //! ```rust
+//! # #[cfg(feature = "std")] {
 //! use mint_std140::AsStd140;
 //!
 //! let mint_vector = mint::Vector2 { x: 0.0f32, y: 0.0f32 };
@@ -18,11 +21,315 @@
 //!     y: mint::Vector2 { x: 2.0f32, y: 3.0f32 },
 //! };
 //! let std140_matrix = mint_matrix.as_std140();
+//! # }
 //! ```
+//!
+//! The same types implement [Std140Bytes], which writes their std140 representation as bytes in an
+//! explicit byte order:
+//!
+//! ```rust
+//! use mint_std140::{Endian, Std140Bytes};
+//!
+//! let mint_vector = mint::Vector2 { x: 1.0f32, y: 2.0f32 };
+//! let mut bytes = [0; 8];
+//! mint_vector.write_std140(&mut bytes, Endian::Little);
+//! assert_eq!(&bytes[4..8], &2.0f32.to_le_bytes());
+//! ```
+//!
+//! With the `derive` feature, which is enabled by default, [AsStd140] can be derived for structs
+//! whose fields implement it. The derived std140 layout can be inspected at runtime through
+//! [Std140Layout]:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")] {
+//! use mint_std140::{AsStd140, Std140Layout};
+//!
+//! #[derive(AsStd140)]
+//! struct PointLight {
+//!     position: mint::Vector3<f32>,
+//!     intensity: f32,
+//! }
+//!
+//! let layout = PointLight::layout();
+//! assert_eq!(layout.size, 16);
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! - `alloc` (default): APIs that allocate, such as byte vectors, containers and layout
+//!   reflection. Without `std` the crate is `#![no_std]`, and without `alloc` as well it only
+//!   needs `core`.
+//! - `derive` (default): `#[derive(AsStd140)]`, which implements [AsStd140] only together with
+//!   `std` and the other traits of this crate without it. Implies `alloc`.
+//! - `std` (default): The [AsStd140] trait and the [std140] dependency, which needs `std`.
+//!   Implies `alloc`.
+//! - `safe`: Deny unsafe code in this crate, guaranteeing that every path producing or reading
+//!   bytes goes through safe per-scalar writes without transmutes or pointer casts, for running
+//!   under Miri. The exemptions are the slot handover inside [UniformSwap] and the chunk
+//!   reservation inside [Std140ConcurrentArena], neither of which touches bytes itself.
+//! - `repr`: The [repr] module of plain `#[repr(C)]` std140 types with named fields, as an
+//!   alternative to the tuple structs of the [std140] crate.
+//! - `presets`: The [presets] module of ready-made blocks for common cases such as cameras.
+//!   Implies `derive`.
+//! - `nontemporal`: Make [Std140StreamWriter] copy to its destination with non-temporal stores
+//!   on x86-64, bypassing the cache. Has no effect together with `safe`.
+//! - `audit`: The [audit] module, which records the precision lost by quantized fields and by
+//!   [RelativeEye] per frame, for a one-time audit. Implies `std`.
+//! - `stats`: The [stats] module, which counts the bytes packed per block type and frame for a
+//!   stats overlay. Implies `std`.
 
+#[cfg(feature = "std")]
 use std140::*;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+extern crate self as mint_std140;
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+mod arena;
+#[cfg(feature = "alloc")]
+mod blocks;
+#[cfg(feature = "alloc")]
+mod buffer;
+mod bytes;
+#[cfg(feature = "alloc")]
+mod c_header;
+#[cfg(feature = "alloc")]
+mod cache;
+#[cfg(feature = "alloc")]
+mod clusters;
+mod components;
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "safe", allow(unsafe_code))]
+mod concurrent;
+mod counted;
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+mod draws;
+#[cfg(feature = "alloc")]
+mod dump;
+mod dynamic;
+mod element;
+mod error;
+mod explicit;
+#[cfg(feature = "alloc")]
+mod glsl;
+#[cfg(feature = "alloc")]
+mod instances;
+#[cfg(feature = "alloc")]
+mod interner;
+#[cfg(feature = "alloc")]
+mod layout;
+mod lerp;
+mod math;
+#[cfg(feature = "alloc")]
+mod migrate;
+mod offset;
+#[cfg(feature = "alloc")]
+mod padding;
+#[cfg(feature = "alloc")]
+mod partial;
+mod quantize;
+#[cfg(feature = "alloc")]
+mod registry;
+mod relative;
+#[cfg(feature = "alloc")]
+mod renderdoc;
+#[cfg(feature = "alloc")]
+mod report;
+#[cfg(feature = "alloc")]
+mod shader;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod soa;
+#[cfg(feature = "alloc")]
+mod split;
+#[cfg(feature = "alloc")]
+mod storage;
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(feature = "alloc")]
+mod svg;
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "safe", allow(unsafe_code))]
+mod swap;
+#[cfg(feature = "alloc")]
+mod texel;
+#[cfg(feature = "alloc")]
+mod tracked;
+mod transform;
+#[cfg(feature = "alloc")]
+mod uniform;
+mod validate;
+#[cfg(feature = "alloc")]
+mod vec;
+#[cfg(feature = "alloc")]
+mod version;
+mod writer;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod fallible;
+#[cfg(feature = "alloc")]
+pub mod gl;
+#[cfg(feature = "presets")]
+pub mod presets;
+#[cfg(feature = "alloc")]
+pub mod reference;
+#[cfg(feature = "repr")]
+pub mod repr;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod vulkan;
+
+#[cfg(feature = "alloc")]
+pub use arena::{Handle, Std140Arena};
+#[cfg(feature = "alloc")]
+pub use blocks::{BlockBinding, Std140BlockPacker};
+#[cfg(feature = "alloc")]
+pub use buffer::AlignedStd140Buffer;
+use bytes::Scalar;
+pub use bytes::{
+    std140_align_of, std140_array_size_of, std140_array_stride_of, std140_size_of, Endian,
+    FromStd140, Std140Bytes, Std140Sized,
+};
+#[cfg(feature = "alloc")]
+pub use c_header::c_header;
+#[cfg(feature = "alloc")]
+pub use cache::{CacheSlot, UploadCache};
+#[cfg(feature = "alloc")]
+pub use clusters::{pack_clusters, pack_clusters_with, ClusterBlocks, ClusterOffsets};
+pub use components::{MatrixColumns, VectorComponents};
+#[cfg(feature = "alloc")]
+pub use concurrent::{ArenaChunk, Std140ConcurrentArena};
+pub use counted::CountedArray;
+#[cfg(feature = "alloc")]
+pub use diff::{diff_fields, relative_diff_fields, FieldDiff};
+#[cfg(feature = "alloc")]
+pub use draws::{pack_draw_parameters, pack_draw_parameters_with, DrawParameters};
+#[cfg(feature = "alloc")]
+pub use dump::{dump_annotated, dump_annotated_with};
+pub use dynamic::DynamicOffsets;
+pub use element::{write_element_at, write_element_at_with};
+pub use error::Std140Error;
+pub use explicit::F16;
+#[cfg(feature = "alloc")]
+pub use glsl::GlslLiteral;
+#[cfg(feature = "alloc")]
+pub use instances::{InstanceBatch, InstancePacker};
+#[cfg(feature = "alloc")]
+pub use interner::{InternedBlock, Std140Interner};
+#[cfg(feature = "alloc")]
+pub use layout::{Component, Field, Layout, LayoutKind, ScalarKind, Std140Layout, Std140Value};
+pub use lerp::{Interpolated, Lerp};
+#[cfg(feature = "alloc")]
+pub use migrate::{migrate, LayoutManifest, Migration};
+#[cfg(feature = "derive")]
+pub use mint_std140_derive::AsStd140;
+#[cfg(feature = "alloc")]
+pub use padding::{analyze_padding, PaddingAnalysis};
+#[cfg(feature = "alloc")]
+pub use partial::{field_mask, partial_update, partial_update_with, PartialUpdate};
+#[cfg(feature = "alloc")]
+pub use quantize::glsl_unpack;
+pub use quantize::{
+    pack_half2x16, pack_unorm4x8, unpack_half2x16, unpack_unorm4x8, Quantization, QuantizeHalf,
+    QuantizeUnorm8,
+};
+#[cfg(feature = "alloc")]
+pub use registry::{RegisteredType, Std140Registry};
+pub use relative::RelativeEye;
+#[cfg(feature = "alloc")]
+pub use renderdoc::renderdoc_format;
+#[cfg(feature = "alloc")]
+pub use shader::{glsl_block, glsl_block_conservative, wgsl_struct};
+#[cfg(feature = "alloc")]
+pub use snapshot::{layout_snapshot, snapshot_diff};
+#[cfg(feature = "alloc")]
+pub use soa::{pack_soa, pack_soa_with, SoaArray, SoaBlock};
+#[cfg(feature = "alloc")]
+pub use split::{split_array, ArrayChunk};
+#[cfg(feature = "alloc")]
+pub use storage::{pack_array, pack_array_with, BufferBinding, PackedArray};
+#[cfg(feature = "alloc")]
+pub use stream::Std140StreamWriter;
+#[cfg(feature = "alloc")]
+pub use svg::layout_svg;
+#[cfg(feature = "alloc")]
+pub use swap::{SwapPublisher, SwapReader, UniformSwap};
+#[cfg(feature = "alloc")]
+pub use texel::{pack_texels, pack_texels_with, TexelBuffer, TexelFormat};
+#[cfg(feature = "alloc")]
+pub use tracked::Tracked;
+pub use transform::{normal_matrix, transform_2d, transform_3d};
+#[cfg(feature = "alloc")]
+pub use uniform::UniformBlock;
+pub use validate::{Std140Scalars, Std140Validate};
+#[cfg(feature = "alloc")]
+pub use vec::Std140Vec;
+#[cfg(feature = "alloc")]
+pub use version::{glsl_layout_version_check, layout_fingerprint, Std140LayoutVersion};
+pub use writer::Std140Writer;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "audit")]
+    pub use crate::audit::audit_quantized;
+    pub use crate::bytes::{debug_check_len, debug_check_offset};
+    #[cfg(feature = "alloc")]
+    pub use crate::diff::{assert_relative_eq, assert_value_relative_eq};
+    #[cfg(feature = "alloc")]
+    pub use crate::layout::validate_field;
+    pub use crate::offset::{element_offset, field_id, Std140Element, Std140Field};
+    #[cfg(feature = "alloc")]
+    pub use crate::uniform::glsl_types;
+    #[cfg(feature = "alloc")]
+    pub use crate::version::LayoutVersionCache;
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec;
+    #[cfg(feature = "std")]
+    pub use std140;
+
+    /// Expand to the given items only with the `std` feature, for the parts of derived impls
+    /// that name the types of the std140 crate.
+    #[cfg(feature = "std")]
+    #[macro_export]
+    #[doc(hidden)]
+    macro_rules! __with_std140 {
+        ($($items:tt)*) => { $($items)* };
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[macro_export]
+    #[doc(hidden)]
+    macro_rules! __with_std140 {
+        ($($items:tt)*) => {};
+    }
+
+    pub use crate::__with_std140 as with_std140;
+
+    #[cfg(not(feature = "audit"))]
+    #[inline]
+    pub fn audit_quantized<T>(
+        _path: &'static str,
+        _quantization: crate::Quantization,
+        _value: &T,
+        _stored: impl FnOnce() -> T,
+    ) {
+    }
+
+    pub const fn align_up(offset: usize, align: usize) -> usize {
+        (offset + align - 1) & !(align - 1)
+    }
+}
+
 /// A type that can be converted to a std140 type.
+#[cfg(feature = "std")]
 pub trait AsStd140 {
     /// The std140 type that this type can be converted to.
     type Std140Type;
@@ -31,53 +338,229 @@ pub trait AsStd140 {
     fn as_std140(&self) -> Self::Std140Type;
 }
 
-macro_rules! impl_as_std140_for_vector {
-    ($mint_type:ty, $std140_name:ident, [$($field:ident),+]) => {
-        impl AsStd140 for $mint_type {
+macro_rules! impl_as_std140_for_scalar {
+    ($rust_type:ty, $std140_name:ident, $value:expr) => {
+        #[cfg(feature = "std")]
+        impl AsStd140 for $rust_type {
             type Std140Type = $std140_name;
 
             fn as_std140(&self) -> Self::Std140Type {
-                $std140_name($(self.$field),+)
+                let value: fn(&$rust_type) -> $std140_name = $value;
+                value(self)
+            }
+        }
+
+        impl Std140Sized for $rust_type {
+            const SIZE: usize = 4;
+            const ALIGN: usize = 4;
+        }
+
+        impl Std140Bytes for $rust_type {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                bytes::debug_check_len::<Self>(bytes);
+                endian.write_u32(bytes, self.to_bits());
+            }
+        }
+
+        impl FromStd140 for $rust_type {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                Scalar::from_bits(endian.read_u32(bytes))
             }
         }
     };
 }
 
-impl_as_std140_for_vector!(mint::Vector2<f32>, vec2, [x, y]);
-impl_as_std140_for_vector!(mint::Vector3<f32>, vec3, [x, y, z]);
-impl_as_std140_for_vector!(mint::Vector4<f32>, vec4, [x, y, z, w]);
-impl_as_std140_for_vector!(mint::Vector2<i32>, ivec2, [x, y]);
-impl_as_std140_for_vector!(mint::Vector3<i32>, ivec3, [x, y, z]);
-impl_as_std140_for_vector!(mint::Vector4<i32>, ivec4, [x, y, z, w]);
-impl_as_std140_for_vector!(mint::Vector2<u32>, uvec2, [x, y]);
-impl_as_std140_for_vector!(mint::Vector3<u32>, uvec3, [x, y, z]);
-impl_as_std140_for_vector!(mint::Vector4<u32>, uvec4, [x, y, z, w]);
+impl_as_std140_for_scalar!(f32, float, |value| float(*value));
+impl_as_std140_for_scalar!(i32, int, |value| int(*value));
+impl_as_std140_for_scalar!(u32, uint, |value| uint(*value));
+impl_as_std140_for_scalar!(bool, boolean, |value| boolean::from(*value));
+
+macro_rules! impl_as_std140_for_vector {
+    ($mint_type:ident, $std140_type:ident, $constructor:ident, [$($field:ident),+]) => {
+        #[cfg(feature = "std")]
+        impl<T: VectorComponents> AsStd140 for mint::$mint_type<T> {
+            type Std140Type = T::$std140_type;
+
+            fn as_std140(&self) -> Self::Std140Type {
+                T::$constructor($(self.$field),+)
+            }
+        }
+
+        impl<T: VectorComponents> Std140Sized for mint::$mint_type<T> {
+            const SIZE: usize = 4 * [$(stringify!($field)),+].len();
+            const ALIGN: usize = if Self::SIZE == 12 { 16 } else { Self::SIZE };
+        }
+
+        impl<T: VectorComponents> Std140Bytes for mint::$mint_type<T> {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                bytes::debug_check_len::<Self>(bytes);
+                let bytes = &mut bytes[..Self::SIZE];
+                let mut offset = 0;
+                $(
+                    endian.write_u32(&mut bytes[offset..], self.$field.to_bits());
+                    offset += 4;
+                )+
+                debug_assert_eq!(offset, Self::SIZE);
+            }
+        }
+
+        impl<T: VectorComponents> FromStd140 for mint::$mint_type<T> {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                let bytes = &bytes[..Self::SIZE];
+                let mut offset = 0;
+                $(
+                    let $field = Scalar::from_bits(endian.read_u32(&bytes[offset..]));
+                    offset += 4;
+                )+
+                debug_assert_eq!(offset, Self::SIZE);
+                Self { $($field),+ }
+            }
+        }
+    };
+}
+
+impl_as_std140_for_vector!(Vector2, Vec2, vec2, [x, y]);
+impl_as_std140_for_vector!(Vector3, Vec3, vec3, [x, y, z]);
+impl_as_std140_for_vector!(Vector4, Vec4, vec4, [x, y, z, w]);
+
+/// Quaternions are stored as a `vec4` with the vector part in `xyz` and the scalar part in `w`.
+#[cfg(feature = "std")]
+impl AsStd140 for mint::Quaternion<f32> {
+    type Std140Type = vec4;
+
+    fn as_std140(&self) -> Self::Std140Type {
+        vec4(self.v.x, self.v.y, self.v.z, self.s)
+    }
+}
+
+impl Std140Sized for mint::Quaternion<f32> {
+    const SIZE: usize = 16;
+    const ALIGN: usize = 16;
+}
+
+impl Std140Bytes for mint::Quaternion<f32> {
+    fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+        let vector = mint::Vector4 { x: self.v.x, y: self.v.y, z: self.v.z, w: self.s };
+        vector.write_std140(bytes, endian);
+    }
+}
+
+impl FromStd140 for mint::Quaternion<f32> {
+    fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+        let vector = mint::Vector4::<f32>::from_std140_bytes(bytes, endian);
+        mint::Quaternion { v: mint::Vector3 { x: vector.x, y: vector.y, z: vector.z }, s: vector.w }
+    }
+}
 
 macro_rules! impl_as_std140_for_column_matrix {
-    ($mint_type:ty, $std140_name:ident, [$($field:ident),+]) => {
-        impl AsStd140 for $mint_type {
-            type Std140Type = $std140_name;
+    ($mint_type:ident, $column:ident, $std140_type:ident, $constructor:ident, [$($field:ident),+]) => {
+        #[cfg(feature = "std")]
+        impl<T> AsStd140 for mint::$mint_type<T>
+        where
+            mint::$column<T>: MatrixColumns,
+        {
+            type Std140Type = <mint::$column<T> as MatrixColumns>::$std140_type;
 
             fn as_std140(&self) -> Self::Std140Type {
-                $std140_name(
-                    $(self.$field.as_std140()),+
-                )
+                MatrixColumns::$constructor($(self.$field),+)
+            }
+        }
+
+        impl<T> Std140Sized for mint::$mint_type<T>
+        where
+            mint::$column<T>: MatrixColumns,
+        {
+            const SIZE: usize = 16 * [$(stringify!($field)),+].len();
+            const ALIGN: usize = 16;
+        }
+
+        impl<T> Std140Bytes for mint::$mint_type<T>
+        where
+            mint::$column<T>: MatrixColumns,
+        {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                bytes::debug_check_len::<Self>(bytes);
+                let bytes = &mut bytes[..Self::SIZE];
+                let mut offset = 0;
+                $(
+                    bytes[offset..offset + 16].fill(0);
+                    self.$field.write_std140(&mut bytes[offset..], endian);
+                    offset += 16;
+                )+
+                debug_assert_eq!(offset, Self::SIZE);
+            }
+        }
+
+        impl<T> FromStd140 for mint::$mint_type<T>
+        where
+            mint::$column<T>: MatrixColumns,
+        {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                let bytes = &bytes[..Self::SIZE];
+                let mut offset = 0;
+                $(
+                    let $field = FromStd140::from_std140_bytes(&bytes[offset..], endian);
+                    offset += 16;
+                )+
+                debug_assert_eq!(offset, Self::SIZE);
+                Self { $($field),+ }
             }
         }
     };
 }
 
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix2<f32>, mat2x2, [x, y]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix3x2<f32>, mat2x3, [x, y]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix4x2<f32>, mat2x4, [x, y]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix2x3<f32>, mat3x2, [x, y, z]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix3<f32>, mat3x3, [x, y, z]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix4x3<f32>, mat3x4, [x, y, z]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix2x4<f32>, mat4x2, [x, y, z, w]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix3x4<f32>, mat4x3, [x, y, z, w]);
-impl_as_std140_for_column_matrix!(mint::ColumnMatrix4<f32>, mat4x4, [x, y, z, w]);
-
-#[cfg(test)]
+impl_as_std140_for_column_matrix!(ColumnMatrix2, Vector2, Mat2, mat2, [x, y]);
+impl_as_std140_for_column_matrix!(ColumnMatrix3x2, Vector3, Mat2, mat2, [x, y]);
+impl_as_std140_for_column_matrix!(ColumnMatrix4x2, Vector4, Mat2, mat2, [x, y]);
+impl_as_std140_for_column_matrix!(ColumnMatrix2x3, Vector2, Mat3, mat3, [x, y, z]);
+impl_as_std140_for_column_matrix!(ColumnMatrix3, Vector3, Mat3, mat3, [x, y, z]);
+impl_as_std140_for_column_matrix!(ColumnMatrix4x3, Vector4, Mat3, mat3, [x, y, z]);
+impl_as_std140_for_column_matrix!(ColumnMatrix2x4, Vector2, Mat4, mat4, [x, y, z, w]);
+impl_as_std140_for_column_matrix!(ColumnMatrix3x4, Vector3, Mat4, mat4, [x, y, z, w]);
+impl_as_std140_for_column_matrix!(ColumnMatrix4, Vector4, Mat4, mat4, [x, y, z, w]);
+
+#[cfg(feature = "std")]
+impl<T, const N: usize> AsStd140 for [T; N]
+where
+    T: AsStd140,
+    T::Std140Type: Std140ArrayElement,
+{
+    type Std140Type = array<T::Std140Type, N>;
+
+    fn as_std140(&self) -> Self::Std140Type {
+        array::from_wrapped(core::array::from_fn(|i| ArrayElementWrapper {
+            element: self[i].as_std140(),
+        }))
+    }
+}
+
+impl<T: Std140Sized, const N: usize> Std140Sized for [T; N] {
+    const SIZE: usize = T::ARRAY_STRIDE * N;
+    const ALIGN: usize = 16;
+}
+
+impl<T: Std140Bytes, const N: usize> Std140Bytes for [T; N] {
+    fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+        bytes::debug_check_len::<Self>(bytes);
+        let bytes = &mut bytes[..Self::SIZE];
+        bytes.fill(0);
+        let stride = bytes::array_stride(T::SIZE);
+        for (i, element) in self.iter().enumerate() {
+            bytes::debug_check_offset::<T>(i * stride);
+            element.write_std140(&mut bytes[i * stride..], endian);
+        }
+    }
+}
+
+impl<T: FromStd140, const N: usize> FromStd140 for [T; N] {
+    fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+        let bytes = &bytes[..Self::SIZE];
+        let stride = bytes::array_stride(T::SIZE);
+        core::array::from_fn(|i| T::from_std140_bytes(&bytes[i * stride..], endian))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::AsStd140;
 
@@ -137,4 +620,111 @@ mod tests {
         assert_eq!(vector.z, std140_vector[2]);
         assert_eq!(vector.w, std140_vector[3]);
     }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive() {
+        use crate::{LayoutKind, Std140Layout, Std140Sized};
+
+        #[derive(AsStd140)]
+        #[std140(max_size)]
+        struct Inner {
+            a: f32,
+            b: mint::Vector3<f32>,
+            c: f32,
+        }
+
+        #[derive(AsStd140)]
+        #[std140(max_size = 160)]
+        struct Outer<const N: usize> {
+            inner: Inner,
+            d: mint::Vector2<u32>,
+            e: [Inner; N],
+            f: mint::ColumnMatrix3<f32>,
+        }
+
+        assert_eq!(Inner::SIZE, 32);
+        assert_eq!(Outer::<2>::SIZE, 160);
+        let offsets = match Outer::<2>::layout().kind {
+            LayoutKind::Struct { fields, .. } => {
+                fields.iter().map(|field| field.offset).collect::<Vec<_>>()
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(offsets, [0, 32, 48, 112]);
+        // The std140 type of a generic struct is a repr(C) struct of std140 types.
+        assert_eq!(core::mem::size_of::<OuterStd140<2>>(), 160);
+        assert_eq!(core::mem::offset_of!(OuterStd140<2>, d), 32);
+        assert_eq!(core::mem::offset_of!(OuterStd140<2>, f), 112);
+
+        let inner = Inner { a: 1.0, b: mint::Vector3 { x: 2.0, y: 3.0, z: 4.0 }, c: 5.0 };
+        let std140_inner = inner.as_std140();
+        assert_eq!(core::mem::size_of::<InnerStd140>(), 32);
+        assert_eq!(core::mem::offset_of!(InnerStd140, c), 28);
+        assert_eq!(std140_inner.b[4..8], 3.0f32.to_ne_bytes());
+        assert_eq!(std140_inner.c, 5.0f32.to_ne_bytes());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn from_std140() {
+        use crate::{Endian, FromStd140, Std140Bytes, Std140Error};
+
+        #[derive(AsStd140, Debug, PartialEq)]
+        struct Old {
+            color: mint::Vector3<f32>,
+            matrices: [mint::ColumnMatrix2<f32>; 2],
+        }
+
+        #[derive(AsStd140, Debug, PartialEq)]
+        #[std140(default)]
+        struct New {
+            #[std140(default = [1.0, 1.0, 1.0].into())]
+            color: mint::Vector3<f32>,
+            #[std140(default = [[[1.0, 0.0], [0.0, 1.0]].into(); 2])]
+            matrices: [mint::ColumnMatrix2<f32>; 2],
+            #[std140(default = true)]
+            enabled: bool,
+            #[std140(quantize = "half", default = 0.0)]
+            scale: f32,
+        }
+
+        let old =
+            Old { color: [0.5, 0.25, 1.0].into(), matrices: [[[2.0, 3.0], [4.0, 5.0]].into(); 2] };
+        let bytes = old.as_std140_be_bytes();
+        assert_eq!(Old::from_std140_bytes(&bytes, Endian::Big), old);
+        assert_eq!(
+            Old::try_from_std140_bytes(&bytes[..64], Endian::Big),
+            Err(Std140Error::TooShort { field: "matrices", required: 80, available: 64 })
+        );
+        assert_eq!(
+            f32::try_from_std140_bytes(&bytes[..2], Endian::Big),
+            Err(Std140Error::TooShort { field: "", required: 4, available: 2 })
+        );
+
+        let new = New::from_std140_bytes(&bytes, Endian::Big);
+        assert_eq!((new.color, new.matrices), (old.color, old.matrices));
+        assert_eq!((new.enabled, new.scale), (true, 0.0));
+
+        let new = New { scale: 0.5, ..New::default() };
+        assert_eq!(New::from_std140_bytes(&new.as_std140_le_bytes(), Endian::Little), new);
+        assert_eq!(New::default().color, [1.0, 1.0, 1.0].into());
+    }
+
+    #[test]
+    fn bool_vectors() {
+        use std140::boolean::{False, True};
+
+        let vector = mint::Vector3 { x: true, y: false, z: true };
+        assert_eq!(vector.as_std140(), std140::bvec3(True, False, True));
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(1.0f32.as_std140(), std140::float(1.0));
+        assert_eq!((-1i32).as_std140(), std140::int(-1));
+        assert_eq!(1u32.as_std140(), std140::uint(1));
+        assert_eq!(true.as_std140(), std140::boolean::True);
+        assert_eq!(false.as_std140(), std140::boolean::False);
+    }
 }
@@ -0,0 +1,100 @@
+use core::f64::consts::{FRAC_PI_2, PI};
+
+// The float functions of `std` that interpolation and transforms need, for builds without it.
+// They evaluate in `f64`, which keeps them within a rounding or two of `std` for `f32` inputs.
+
+/// The square root of `x`, or NaN if `x` is negative.
+pub(crate) fn sqrt(x: f32) -> f32 {
+    if x.is_nan() || x < 0.0 {
+        return f32::NAN;
+    }
+    if x == 0.0 || x.is_infinite() {
+        return x;
+    }
+    sqrt_f64(x as f64) as f32
+}
+
+/// The square root of a positive, finite `x`.
+fn sqrt_f64(x: f64) -> f64 {
+    // Halving the exponent gets within a few percent, and each Newton step doubles the number of
+    // correct bits.
+    let mut root = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..6 {
+        root = 0.5 * (root + x / root);
+    }
+    root
+}
+
+/// The sine and cosine of `x` radians.
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    if !x.is_finite() {
+        return (f32::NAN, f32::NAN);
+    }
+
+    // Reduce to within an eighth of a turn of a multiple of a quarter turn, where the Taylor
+    // series converge quickly.
+    let x = x as f64;
+    let quadrant = (x / FRAC_PI_2 + if x < 0.0 { -0.5 } else { 0.5 }) as i64;
+    let r = x - quadrant as f64 * FRAC_PI_2;
+    let (mut sin, mut cos) = (0.0, 0.0);
+    let (mut sin_term, mut cos_term) = (r, 1.0);
+    for n in 0..10 {
+        sin += sin_term;
+        cos += cos_term;
+        let n = n as f64;
+        sin_term *= -r * r / ((2.0 * n + 2.0) * (2.0 * n + 3.0));
+        cos_term *= -r * r / ((2.0 * n + 1.0) * (2.0 * n + 2.0));
+    }
+
+    let (sin, cos) = match quadrant.rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    };
+    (sin as f32, cos as f32)
+}
+
+/// The angle in `0..=π` whose cosine is `x`, or NaN outside `-1..=1`.
+pub(crate) fn acos(x: f32) -> f32 {
+    if x.is_nan() || !(-1.0..=1.0).contains(&x) {
+        return f32::NAN;
+    }
+
+    // acos(x) = 2 asin(sqrt((1 - x) / 2)), where the argument of the arcsine is at most sqrt(1/2)
+    // for positive x, so its series gains a bit per term.
+    let positive = |x: f64| {
+        let y = if x == 1.0 { 0.0 } else { sqrt_f64((1.0 - x) / 2.0) };
+        let (mut asin, mut term) = (0.0, y);
+        for n in 0..64 {
+            asin += term;
+            let n = n as f64;
+            term *= y * y * (2.0 * n + 1.0) * (2.0 * n + 1.0) / ((2.0 * n + 2.0) * (2.0 * n + 3.0));
+        }
+        2.0 * asin
+    };
+    let x = x as f64;
+    let angle = if x < 0.0 { PI - positive(-x) } else { positive(x) };
+    angle as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{acos, sin_cos, sqrt};
+
+    #[test]
+    fn against_std() {
+        let close = |a: f32, b: f32| (a - b).abs() <= 2.0 * f32::EPSILON * b.abs().max(1.0);
+        for i in -400..=400 {
+            let x = i as f32 / 40.0;
+            let (sin, cos) = sin_cos(x);
+            assert!(close(sin, x.sin()) && close(cos, x.cos()), "sin_cos({})", x);
+            assert!(close(sqrt(x.abs()), x.abs().sqrt()), "sqrt({})", x.abs());
+            if x.abs() <= 1.0 {
+                assert!(close(acos(x), x.acos()), "acos({})", x);
+            }
+        }
+        assert_eq!(sqrt(4e30), 2e15);
+        assert!(sqrt(-1.0).is_nan() && acos(1.5).is_nan() && sin_cos(f32::INFINITY).0.is_nan());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{Endian, Layout, ScalarKind, Std140Bytes, Std140Error, Std140Layout, Std140Value};
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// A record of where every scalar of a std140 layout lives, to store alongside packed data so
+/// it can still be read after the type changes shape.
+///
+/// The text form lists the size, then one scalar per line with its offset, type and path:
+///
+/// ```text
+/// size=32
+/// 0 float intensity
+/// 16 float color.x
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutManifest {
+    /// The size of the layout in bytes.
+    pub size: usize,
+    /// The offset, type and path of every scalar.
+    pub components: Vec<(usize, ScalarKind, String)>,
+}
+
+impl LayoutManifest {
+    /// The manifest of a layout.
+    pub fn new(layout: &Layout) -> Self {
+        let components = layout
+            .components()
+            .into_iter()
+            .map(|component| (component.offset, component.scalar, component.path))
+            .collect();
+        LayoutManifest { size: layout.size, components }
+    }
+
+    /// The manifest of the std140 layout of `T`.
+    pub fn of<T: Std140Layout>() -> Self {
+        Self::new(&T::layout())
+    }
+
+    /// Parse the text form of a manifest, rejecting scalars that end past its size.
+    pub fn parse(text: &str) -> Result<Self, Std140Error> {
+        let mut lines = text
+            .lines()
+            .map(str::trim_end)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| (index + 1, line));
+        let (line, header) = lines.next().ok_or(Std140Error::InvalidManifest { line: 1 })?;
+        let size = header
+            .strip_prefix("size=")
+            .and_then(|size| size.parse().ok())
+            .ok_or(Std140Error::InvalidManifest { line })?;
+
+        let mut components = Vec::new();
+        for (line, text) in lines {
+            let mut parts = text.splitn(3, ' ');
+            let offset = parts.next().and_then(|offset| offset.parse().ok());
+            let scalar = match parts.next() {
+                Some("float") => Some(ScalarKind::Float),
+                Some("int") => Some(ScalarKind::Int),
+                Some("uint") => Some(ScalarKind::Uint),
+                Some("bool") => Some(ScalarKind::Bool),
+                Some("float16_t") => Some(ScalarKind::Float16),
+                Some("int8_t") => Some(ScalarKind::Int8),
+                Some("uint8_t") => Some(ScalarKind::Uint8),
+                Some("int16_t") => Some(ScalarKind::Int16),
+                Some("uint16_t") => Some(ScalarKind::Uint16),
+                _ => None,
+            };
+            // Every scalar must end inside the layout.
+            let (offset, scalar) = offset
+                .zip(scalar)
+                .filter(|&(offset, scalar): &(usize, ScalarKind)| {
+                    offset.checked_add(scalar.size()).is_some_and(|end| end <= size)
+                })
+                .ok_or(Std140Error::InvalidManifest { line })?;
+            let path = parts.next().unwrap_or("").to_string();
+            components.push((offset, scalar, path));
+        }
+        Ok(LayoutManifest { size, components })
+    }
+}
+
+impl fmt::Display for LayoutManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "size={}", self.size)?;
+        for (offset, scalar, path) in &self.components {
+            write!(f, "{} {}", offset, scalar.glsl_name())?;
+            if !path.is_empty() {
+                write!(f, " {}", path)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of [migrate].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migration {
+    /// The data in the current layout.
+    pub bytes: Vec<u8>,
+    /// The paths of the scalars that only exist in the current layout and were filled with
+    /// defaults.
+    pub added: Vec<String>,
+    /// The paths of the scalars that only existed in the old layout and were dropped.
+    pub dropped: Vec<String>,
+}
+
+/// Move packed data written with the `old` layout into the current std140 layout of `T`,
+/// matching scalars by path.
+///
+/// Scalars that only exist in the current layout keep their values from `defaults`. Scalars that
+/// changed type are converted, so an `int` field that became a `float` keeps its value. Data
+/// shorter than the old layout, such as a truncated cache entry, is treated as missing those
+/// scalars.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{migrate, AsStd140, Endian, FromStd140, LayoutManifest, Std140Bytes};
+///
+/// #[derive(AsStd140)]
+/// struct MaterialV1 {
+///     roughness: f32,
+///     tint: mint::Vector3<f32>,
+/// }
+///
+/// #[derive(AsStd140, Debug, PartialEq)]
+/// struct MaterialV2 {
+///     tint: mint::Vector3<f32>,
+///     metallic: f32,
+/// }
+///
+/// let saved = MaterialV1 { roughness: 0.5, tint: [1.0, 0.5, 0.0].into() };
+/// let manifest = LayoutManifest::of::<MaterialV1>().to_string();
+/// let bytes = saved.as_std140_le_bytes();
+///
+/// let defaults = MaterialV2 { tint: [1.0, 1.0, 1.0].into(), metallic: 0.25 };
+/// let old = LayoutManifest::parse(&manifest).unwrap();
+/// let migration = migrate(&old, &bytes, &defaults, Endian::Little);
+/// assert_eq!(migration.added, ["metallic"]);
+/// assert_eq!(migration.dropped, ["roughness"]);
+///
+/// let material = MaterialV2::from_std140_bytes(&migration.bytes, Endian::Little);
+/// assert_eq!(material, MaterialV2 { tint: [1.0, 0.5, 0.0].into(), metallic: 0.25 });
+/// ```
+pub fn migrate<T: Std140Layout + Std140Bytes>(
+    old: &LayoutManifest,
+    old_bytes: &[u8],
+    defaults: &T,
+    endian: Endian,
+) -> Migration {
+    let mut bytes = vec![0; T::SIZE];
+    defaults.write_std140(&mut bytes, endian);
+
+    let old_components: Vec<_> = old
+        .components
+        .iter()
+        .filter(|(offset, scalar, _)| {
+            offset.checked_add(scalar.size()).is_some_and(|end| end <= old_bytes.len())
+        })
+        .collect();
+    let new_components = T::layout().components();
+
+    let mut added = Vec::new();
+    for component in &new_components {
+        let old = old_components.iter().find(|(_, _, path)| *path == component.path);
+        let (old_offset, old_scalar) = match old {
+            Some((offset, scalar, _)) => (*offset, *scalar),
+            None => {
+                added.push(component.path.clone());
+                continue;
+            }
+        };
+
+        let to = &mut bytes[component.offset..component.offset + component.scalar.size()];
+        if old_scalar == component.scalar {
+            to.copy_from_slice(&old_bytes[old_offset..old_offset + old_scalar.size()]);
+        } else {
+            Std140Value::read(old_scalar, &old_bytes[old_offset..], endian).write(
+                component.scalar,
+                to,
+                endian,
+            );
+        }
+    }
+
+    let dropped = old
+        .components
+        .iter()
+        .map(|(_, _, path)| path)
+        .filter(|path| !new_components.iter().any(|component| component.path == **path))
+        .cloned()
+        .collect();
+    Migration { bytes, added, dropped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{migrate, LayoutManifest};
+    use crate::{Endian, Layout, ScalarKind, Std140Bytes, Std140Error};
+
+    #[test]
+    fn manifest() {
+        let manifest = LayoutManifest::of::<[mint::Vector2<u32>; 2]>();
+        let text = manifest.to_string();
+        assert_eq!(text, "size=32\n0 uint [0].x\n4 uint [0].y\n16 uint [1].x\n20 uint [1].y\n");
+        assert_eq!(LayoutManifest::parse(&text), Ok(manifest));
+        assert_eq!(
+            LayoutManifest::new(&Layout::scalar(ScalarKind::Int)).to_string(),
+            "size=4\n0 int\n"
+        );
+        assert_eq!(
+            LayoutManifest::parse("size=4\n\n0 double x"),
+            Err(Std140Error::InvalidManifest { line: 3 })
+        );
+        assert_eq!(
+            LayoutManifest::parse(&format!("size=4\n{} float x", usize::MAX)),
+            Err(Std140Error::InvalidManifest { line: 2 })
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        // An `ivec2[2]` that became a `vec2[3]`, from data cut off after the first element.
+        let old = LayoutManifest::of::<[mint::Vector2<i32>; 2]>();
+        let bytes =
+            [mint::Vector2 { x: -3, y: 4 }, mint::Vector2 { x: 5, y: 6 }].as_std140_be_bytes();
+
+        let defaults = [mint::Vector2 { x: 9.0f32, y: 9.0 }; 3];
+        let migration = migrate(&old, &bytes[..16], &defaults, Endian::Big);
+        assert_eq!(
+            &migration.bytes[..8],
+            &[(-3.0f32).to_be_bytes(), 4.0f32.to_be_bytes()].concat()[..]
+        );
+        assert_eq!(&migration.bytes[16..20], &9.0f32.to_be_bytes());
+        assert_eq!(migration.added, ["[1].x", "[1].y", "[2].x", "[2].y"]);
+        assert!(migration.dropped.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::bytes::array_stride;
+use crate::Std140Sized;
+
+/// A field of a derived struct, identified by the [field_id] of its name.
+pub trait Std140Field<const ID: u64> {
+    /// The type the field is stored as.
+    type Type: Std140Sized;
+    /// The std140 offset of the field.
+    const OFFSET: usize;
+}
+
+/// An array type that [std140_offset_of!](crate::std140_offset_of) can index.
+pub trait Std140Element {
+    /// The element type.
+    type Element: Std140Sized;
+    /// The number of elements.
+    const LEN: usize;
+}
+
+impl<T: Std140Sized, const N: usize> Std140Element for [T; N] {
+    type Element = T;
+    const LEN: usize = N;
+}
+
+/// The FNV-1a hash of a field name, which stands in for the name as a const generic parameter.
+pub const fn field_id(name: &str) -> u64 {
+    let bytes = name.as_bytes();
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u64).wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// The offset of element `index` of the array type `T`, panicking at compile time if it is out
+/// of bounds.
+pub const fn element_offset<T: Std140Element>(index: usize) -> usize {
+    assert!(index < T::LEN, "std140_offset_of! array index out of bounds");
+    index * array_stride(T::Element::SIZE)
+}
+
+/// The std140 offset in bytes of a field of a derived struct, as a constant.
+///
+/// The path may go through nested derived structs and index arrays, such as `lights[2].color`.
+/// Indices must be constants and are checked against the array length at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use mint_std140::{std140_offset_of, AsStd140};
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// #[derive(AsStd140)]
+/// struct Scene {
+///     ambient: f32,
+///     lights: [Light; 4],
+/// }
+///
+/// const COLOR: usize = std140_offset_of!(Scene, lights[2].color);
+/// assert_eq!(COLOR, 16 + 2 * 32 + 16);
+/// assert_eq!(std140_offset_of!(Scene, ambient), 0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! std140_offset_of {
+    (@path $ty:ty, $offset:expr; . $field:ident $($path:tt)*) => {
+        $crate::std140_offset_of!(
+            @path <$ty as $crate::__private::Std140Field<
+                { $crate::__private::field_id(stringify!($field)) },
+            >>::Type,
+            $offset + <$ty as $crate::__private::Std140Field<
+                { $crate::__private::field_id(stringify!($field)) },
+            >>::OFFSET;
+            $($path)*
+        )
+    };
+    (@path $ty:ty, $offset:expr; [$index:expr] $($path:tt)*) => {
+        $crate::std140_offset_of!(
+            @path <$ty as $crate::__private::Std140Element>::Element,
+            $offset + $crate::__private::element_offset::<$ty>($index);
+            $($path)*
+        )
+    };
+    (@path $ty:ty, $offset:expr;) => {
+        $offset
+    };
+    ($ty:ty, $field:ident $($path:tt)*) => {{
+        const OFFSET: usize = $crate::std140_offset_of!(@path $ty, 0; . $field $($path)*);
+        OFFSET
+    }};
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::{AsStd140, Std140Layout};
+
+    #[derive(AsStd140)]
+    struct Material {
+        albedo: mint::Vector3<f32>,
+        roughness: f32,
+        layers: [mint::Vector2<f32>; 3],
+    }
+
+    #[derive(AsStd140)]
+    struct Block {
+        time: f32,
+        materials: [Material; 2],
+        count: u32,
+    }
+
+    #[test]
+    fn nested() {
+        let components = Block::layout().components();
+        let offset = |path: &str| components.iter().find(|c| c.path == path).unwrap().offset;
+        assert_eq!(std140_offset_of!(Block, count), offset("count"));
+        assert_eq!(
+            std140_offset_of!(Block, materials[1].layers[2]),
+            offset("materials[1].layers[2].x")
+        );
+        assert_eq!(std140_offset_of!(Block, materials), 16);
+        assert_eq!(std140_offset_of!(Block, materials[1]), 16 + 64);
+        assert_eq!(std140_offset_of!(Block, materials[1].roughness), 16 + 64 + 12);
+        assert_eq!(std140_offset_of!(Block, materials[1].layers[2]), 16 + 64 + 16 + 32);
+    }
+}
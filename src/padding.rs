@@ -0,0 +1,179 @@
+use crate::{Field, LayoutKind, Std140Layout};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// How much of a struct's std140 layout is padding, and a field order that wastes less, as
+/// reported by [analyze_padding].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaddingAnalysis {
+    /// The name of the struct.
+    pub name: &'static str,
+    /// The size of the struct in bytes.
+    pub size: usize,
+    /// The bytes that do not hold a scalar, including padding inside array elements and nested
+    /// structs.
+    pub padding: usize,
+    /// The field names in a smaller order found greedily, which is the declaration order if
+    /// that order is no larger. It is not always the smallest order possible.
+    pub suggested_order: Vec<&'static str>,
+    /// The size of the struct with its fields in [PaddingAnalysis::suggested_order].
+    pub suggested_size: usize,
+}
+
+impl PaddingAnalysis {
+    /// The fraction of the struct that is padding, between 0 and 1.
+    pub fn waste(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.padding as f64 / self.size as f64
+        }
+    }
+
+    /// How many bytes reordering the fields would save.
+    pub fn savings(&self) -> usize {
+        self.size - self.suggested_size
+    }
+}
+
+impl fmt::Display for PaddingAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} bytes, {} of them padding ({:.1}%)",
+            self.name,
+            self.size,
+            self.padding,
+            100.0 * self.waste()
+        )?;
+        if self.savings() > 0 {
+            write!(
+                f,
+                "; reordering the fields as {} saves {} bytes",
+                self.suggested_order.join(", "),
+                self.savings()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Measure the padding in the std140 layout of the struct `T` and look for a field order that
+/// makes it smaller. Nothing is reordered; apply the suggestion to the declaration by hand.
+///
+/// Only the fields of `T` itself are reordered. Nested structs are taken as they are, and can be
+/// analyzed on their own.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{analyze_padding, AsStd140};
+///
+/// #[derive(AsStd140)]
+/// struct Particle {
+///     age: f32,
+///     position: mint::Vector3<f32>,
+///     size: f32,
+///     velocity: mint::Vector3<f32>,
+/// }
+///
+/// let analysis = analyze_padding::<Particle>();
+/// assert_eq!((analysis.size, analysis.padding), (48, 16));
+/// assert_eq!(analysis.suggested_order, ["position", "age", "velocity", "size"]);
+/// assert_eq!(analysis.savings(), 16);
+/// assert_eq!(
+///     analysis.to_string(),
+///     "Particle: 48 bytes, 16 of them padding (33.3%); \
+///      reordering the fields as position, age, velocity, size saves 16 bytes"
+/// );
+/// ```
+pub fn analyze_padding<T: Std140Layout>() -> PaddingAnalysis {
+    let layout = T::layout();
+    let (name, fields) = match &layout.kind {
+        LayoutKind::Struct { name, fields } => (*name, fields),
+        _ => panic!("padding can only be analyzed for structs, not {}", layout.glsl_type()),
+    };
+
+    let declared: Vec<&Field> = fields.iter().collect();
+    let reordered = greedy_order(fields);
+    let suggested =
+        if packed_size(&reordered) < packed_size(&declared) { reordered } else { declared };
+
+    PaddingAnalysis {
+        name,
+        size: layout.size,
+        padding: layout.size - layout.scalar_bytes(),
+        suggested_order: suggested.iter().map(|field| field.name).collect(),
+        suggested_size: packed_size(&suggested),
+    }
+}
+
+/// Order fields by repeatedly taking the one that needs the least padding at the current offset,
+/// preferring larger alignments and sizes, then declaration order.
+fn greedy_order(fields: &[Field]) -> Vec<&Field> {
+    let mut remaining: Vec<&Field> = fields.iter().collect();
+    let mut order = Vec::with_capacity(fields.len());
+    let mut offset: usize = 0;
+    while !remaining.is_empty() {
+        let (i, _) = remaining
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, field)| {
+                let padding = offset.next_multiple_of(field.layout.align) - offset;
+                (padding, usize::MAX - field.layout.align, usize::MAX - field.layout.size, *i)
+            })
+            .unwrap();
+        let field = remaining.remove(i);
+        offset = offset.next_multiple_of(field.layout.align) + field.layout.size;
+        order.push(field);
+    }
+    order
+}
+
+/// The size of a struct with `fields` in this order.
+fn packed_size(fields: &[&Field]) -> usize {
+    let end = fields.iter().fold(0, |offset: usize, field| {
+        offset.next_multiple_of(field.layout.align) + field.layout.size
+    });
+    end.next_multiple_of(16)
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::analyze_padding;
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Packed {
+        color: mint::Vector3<f32>,
+        alpha: f32,
+        uv: mint::Vector2<f32>,
+    }
+
+    #[derive(AsStd140)]
+    struct Loose {
+        enabled: bool,
+        weights: [f32; 2],
+        offset: mint::Vector2<f32>,
+        scale: f32,
+        tint: mint::Vector3<f32>,
+    }
+
+    #[test]
+    fn suggestions() {
+        let packed = analyze_padding::<Packed>();
+        assert_eq!(packed.suggested_order, ["color", "alpha", "uv"]);
+        assert_eq!((packed.size, packed.padding, packed.savings()), (32, 8, 0));
+        assert_eq!(packed.to_string(), "Packed: 32 bytes, 8 of them padding (25.0%)");
+
+        let loose = analyze_padding::<Loose>();
+        assert_eq!(loose.size, 80);
+        assert_eq!(loose.suggested_order, ["weights", "tint", "enabled", "offset", "scale"]);
+        assert_eq!(loose.suggested_size, 64);
+        assert_eq!(loose.padding, 80 - 4 * 9);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{Endian, LayoutKind, Std140Bytes, Std140Layout};
+use alloc::{vec, vec::Vec};
+use core::any::type_name;
+use core::ops::Range;
+
+/// The writes that bring a buffer holding an older version of a struct up to date, made by
+/// [partial_update] from the fields the caller knows changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialUpdate {
+    /// The whole value packed in std140.
+    pub bytes: Vec<u8>,
+    /// The byte ranges to write, one for each run of consecutive changed fields.
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl PartialUpdate {
+    /// The `(offset, bytes)` pairs to write, like those returned by [Tracked::flush].
+    ///
+    /// [Tracked::flush]: crate::Tracked::flush
+    pub fn writes(&self) -> impl Iterator<Item = (usize, &[u8])> + '_ {
+        self.ranges.iter().map(move |range| (range.start, &self.bytes[range.clone()]))
+    }
+}
+
+/// The mask selecting the fields of the struct `T` with the given names, where bit `i` stands for
+/// field `i` in declaration order.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct, or if one of the fields is missing or past the 64th.
+pub fn field_mask<T: Std140Layout>(names: &[&str]) -> u64 {
+    let layout = T::layout();
+    let fields = match &layout.kind {
+        LayoutKind::Struct { fields, .. } => fields,
+        _ => panic!("field masks can only be made for structs, not {}", type_name::<T>()),
+    };
+    names.iter().fold(0, |mask, name| match fields.iter().position(|field| field.name == *name) {
+        Some(i) if i < 64 => mask | 1 << i,
+        Some(_) => {
+            panic!("field {} of {} is past the 64 a mask can select", name, type_name::<T>())
+        }
+        None => panic!("{} has no field {}", type_name::<T>(), name),
+    })
+}
+
+/// Pack `value` in the host byte order and compute the minimal writes updating the fields
+/// selected by `mask`, where bit `i` stands for field `i` in declaration order, without keeping
+/// the previous value like [Tracked](crate::Tracked) does.
+///
+/// Consecutive selected fields are merged into one write along with the padding between them.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct, or if `mask` selects a field past its last one.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{field_mask, partial_update, AsStd140};
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     radius: f32,
+///     color: mint::Vector3<f32>,
+///     intensity: f32,
+/// }
+///
+/// let light = Light {
+///     position: [0.0; 3].into(),
+///     radius: 5.0,
+///     color: [1.0; 3].into(),
+///     intensity: 2.0,
+/// };
+/// let update = partial_update(&light, field_mask::<Light>(&["radius", "color", "intensity"]));
+/// let writes: Vec<_> = update.writes().collect();
+/// assert_eq!(writes.len(), 1);
+/// assert_eq!(writes[0].0, 12);
+/// assert_eq!(writes[0].1.len(), 20);
+/// ```
+pub fn partial_update<T: Std140Bytes + Std140Layout>(value: &T, mask: u64) -> PartialUpdate {
+    partial_update_with(value, mask, Endian::NATIVE)
+}
+
+/// Compute the writes updating the fields selected by `mask` like [partial_update], in the given
+/// byte order.
+pub fn partial_update_with<T: Std140Bytes + Std140Layout>(
+    value: &T,
+    mask: u64,
+    endian: Endian,
+) -> PartialUpdate {
+    let layout = T::layout();
+    let fields = match &layout.kind {
+        LayoutKind::Struct { fields, .. } => fields,
+        _ => panic!("partial updates can only be made for structs, not {}", type_name::<T>()),
+    };
+    if fields.len() < 64 && mask >> fields.len() != 0 {
+        panic!(
+            "field mask {:#x} selects fields past the {} of {}",
+            mask,
+            fields.len(),
+            type_name::<T>()
+        );
+    }
+
+    let mut bytes = vec![0; T::SIZE];
+    value.write_std140(&mut bytes, endian);
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut previous = false;
+    for (i, field) in fields.iter().enumerate() {
+        let selected = i < 64 && mask & 1 << i != 0;
+        let range = field.offset..field.offset + field.layout.size;
+        match ranges.last_mut() {
+            Some(last) if selected && previous => last.end = range.end,
+            _ if selected => ranges.push(range),
+            _ => {}
+        }
+        previous = selected;
+    }
+    PartialUpdate { bytes, ranges }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{field_mask, partial_update_with};
+    use crate::{AsStd140, Endian};
+
+    #[derive(AsStd140)]
+    struct Block {
+        a: f32,
+        b: mint::Vector3<f32>,
+        c: f32,
+        d: [f32; 2],
+    }
+
+    #[test]
+    fn ranges() {
+        let block = Block { a: 1.0, b: [2.0; 3].into(), c: 3.0, d: [4.0, 5.0] };
+        assert_eq!(field_mask::<Block>(&["d", "a"]), 0b1001);
+
+        let update = partial_update_with(&block, 0b1101, Endian::Big);
+        assert_eq!(update.ranges, [0..4, 28..64]);
+        let writes: Vec<_> = update.writes().collect();
+        assert_eq!(writes[0], (0, &1.0f32.to_be_bytes()[..]));
+        assert_eq!(&writes[1].1[..4], &3.0f32.to_be_bytes());
+        assert!(partial_update_with(&block, 0, Endian::Big).ranges.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "field mask 0x10 selects fields past the 4 of")]
+    fn past_last_field() {
+        partial_update_with(
+            &Block { a: 1.0, b: [2.0; 3].into(), c: 3.0, d: [4.0, 5.0] },
+            0x10,
+            Endian::Big,
+        );
+    }
+}
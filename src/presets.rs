@@ -0,0 +1,439 @@
+//! Ready-made derived blocks for the cases nearly every renderer starts with, which double as
+//! examples of `#[derive(AsStd140)]`: cameras, object transforms, light lists, materials and
+//! shadow cascades.
+//!
+//! Each block comes with a constructor that fills in the derived members, such as inverse
+//! matrices, so the CPU side only has to supply the inputs.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mint_std140::presets::{CameraBlock, ObjectBlock};
+//! use mint_std140::{std140_offset_of, Std140Bytes};
+//!
+//! let identity: mint::ColumnMatrix4<f32> = [
+//!     [1.0, 0.0, 0.0, 0.0],
+//!     [0.0, 1.0, 0.0, 0.0],
+//!     [0.0, 0.0, 1.0, 0.0],
+//!     [0.0, 0.0, -5.0, 1.0],
+//! ]
+//! .into();
+//! let camera = CameraBlock::new(identity, identity);
+//! assert_eq!(camera.position, [0.0, 0.0, 5.0].into());
+//! assert_eq!(std140_offset_of!(CameraBlock, position), 320);
+//! assert_eq!(camera.as_std140_bytes().len(), 336);
+//!
+//! let object = ObjectBlock::new(identity);
+//! assert_eq!(object.as_std140_bytes().len(), 112);
+//! ```
+
+use crate::transform::{inverse, multiply};
+use crate::{normal_matrix, AsStd140};
+
+/// The matrix with all elements zero, which stands in for the inverse of a singular matrix.
+const ZERO: mint::ColumnMatrix4<f32> = mint::ColumnMatrix4 {
+    x: mint::Vector4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 },
+    y: mint::Vector4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 },
+    z: mint::Vector4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 },
+    w: mint::Vector4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 },
+};
+
+/// Per-view camera data, matching
+///
+/// ```glsl
+/// layout(std140) uniform Camera {
+///     mat4 view;
+///     mat4 projection;
+///     mat4 view_projection;
+///     mat4 inverse_view;
+///     mat4 inverse_projection;
+///     vec3 position;
+/// };
+/// ```
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+pub struct CameraBlock {
+    /// The world to view transform.
+    pub view: mint::ColumnMatrix4<f32>,
+    /// The view to clip transform.
+    pub projection: mint::ColumnMatrix4<f32>,
+    /// `projection * view`, the world to clip transform.
+    pub view_projection: mint::ColumnMatrix4<f32>,
+    /// The view to world transform.
+    pub inverse_view: mint::ColumnMatrix4<f32>,
+    /// The clip to view transform.
+    pub inverse_projection: mint::ColumnMatrix4<f32>,
+    /// The position of the camera in world space.
+    pub position: mint::Vector3<f32>,
+}
+
+impl CameraBlock {
+    /// Derive a camera block from its view and projection matrices. The inverse of a singular
+    /// matrix is stored as zero.
+    pub fn new(view: mint::ColumnMatrix4<f32>, projection: mint::ColumnMatrix4<f32>) -> Self {
+        let inverse_view = inverse(&view).unwrap_or(ZERO);
+        CameraBlock {
+            view,
+            projection,
+            view_projection: multiply(&projection, &view),
+            inverse_view,
+            inverse_projection: inverse(&projection).unwrap_or(ZERO),
+            position: [inverse_view.w.x, inverse_view.w.y, inverse_view.w.z].into(),
+        }
+    }
+}
+
+/// Per-object transforms, matching
+///
+/// ```glsl
+/// layout(std140) uniform Object {
+///     mat4 model;
+///     mat3 normal;
+/// };
+/// ```
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+pub struct ObjectBlock {
+    /// The object to world transform.
+    pub model: mint::ColumnMatrix4<f32>,
+    /// The normal matrix of `model`, as computed by [normal_matrix].
+    pub normal: mint::ColumnMatrix3<f32>,
+}
+
+impl ObjectBlock {
+    /// Derive an object block from its model matrix.
+    pub fn new(model: mint::ColumnMatrix4<f32>) -> Self {
+        ObjectBlock { model, normal: normal_matrix(&model) }
+    }
+}
+
+/// A light shining from infinitely far away in one direction, such as the sun.
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+#[std140(default)]
+pub struct DirectionalLight {
+    /// The direction the light travels in, in world space.
+    #[std140(default = [0.0, -1.0, 0.0].into())]
+    pub direction: mint::Vector3<f32>,
+    /// The multiplier applied to `color`.
+    #[std140(default = 1.0)]
+    pub intensity: f32,
+    /// The linear color of the light.
+    #[std140(default = [1.0; 3].into())]
+    pub color: mint::Vector3<f32>,
+}
+
+/// A light shining in all directions from a point.
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+#[std140(default)]
+pub struct PointLight {
+    /// The position of the light in world space.
+    #[std140(default = [0.0; 3].into())]
+    pub position: mint::Vector3<f32>,
+    /// The distance beyond which the light has no effect.
+    pub range: f32,
+    /// The linear color of the light.
+    #[std140(default = [1.0; 3].into())]
+    pub color: mint::Vector3<f32>,
+    /// The multiplier applied to `color`.
+    #[std140(default = 1.0)]
+    pub intensity: f32,
+}
+
+/// A light shining in a cone from a point.
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+#[std140(default)]
+pub struct SpotLight {
+    /// The position of the light in world space.
+    #[std140(default = [0.0; 3].into())]
+    pub position: mint::Vector3<f32>,
+    /// The distance beyond which the light has no effect.
+    pub range: f32,
+    /// The axis of the cone, in world space.
+    #[std140(default = [0.0, -1.0, 0.0].into())]
+    pub direction: mint::Vector3<f32>,
+    /// The multiplier applied to `color`.
+    #[std140(default = 1.0)]
+    pub intensity: f32,
+    /// The linear color of the light.
+    #[std140(default = [1.0; 3].into())]
+    pub color: mint::Vector3<f32>,
+    /// The cosine of the angle from the axis inside which the light is at full intensity.
+    pub inner_cone_cos: f32,
+    /// The cosine of the angle from the axis outside which the light has no effect.
+    pub outer_cone_cos: f32,
+}
+
+/// Up to `MAX_LIGHTS` lights of each kind with their counts, matching
+///
+/// ```glsl
+/// layout(std140) uniform Lights {
+///     uint directional_count;
+///     uint point_count;
+///     uint spot_count;
+///     DirectionalLight directional[MAX_LIGHTS];
+///     PointLight point[MAX_LIGHTS];
+///     SpotLight spot[MAX_LIGHTS];
+/// };
+/// ```
+///
+/// [glsl_block](crate::glsl_block) and [wgsl_struct](crate::wgsl_struct) generate the shader
+/// declarations for a given `MAX_LIGHTS`, including the light structs.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::presets::{LightBlock, PointLight};
+/// use mint_std140::{glsl_block, std140_offset_of, Std140Sized};
+///
+/// let lamp = PointLight { range: 10.0, intensity: 2.0, ..Default::default() };
+/// let lights = LightBlock::<8>::new(&[], &[lamp; 3], &[]);
+/// assert_eq!(lights.point_count, 3);
+/// assert_eq!(std140_offset_of!(LightBlock<8>, point[2]), 16 + 8 * 32 + 2 * 32);
+/// assert_eq!(LightBlock::<8>::SIZE, 16 + 8 * (32 + 32 + 64));
+///
+/// let glsl = glsl_block::<LightBlock<8>>("Lights");
+/// assert!(glsl.contains("    SpotLight spot[8];\n"));
+/// ```
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+pub struct LightBlock<const MAX_LIGHTS: usize> {
+    /// The number of directional lights in use.
+    pub directional_count: u32,
+    /// The number of point lights in use.
+    pub point_count: u32,
+    /// The number of spot lights in use.
+    pub spot_count: u32,
+    /// The directional lights, of which the first `directional_count` are in use.
+    pub directional: [DirectionalLight; MAX_LIGHTS],
+    /// The point lights, of which the first `point_count` are in use.
+    pub point: [PointLight; MAX_LIGHTS],
+    /// The spot lights, of which the first `spot_count` are in use.
+    pub spot: [SpotLight; MAX_LIGHTS],
+}
+
+impl<const MAX_LIGHTS: usize> LightBlock<MAX_LIGHTS> {
+    /// Fill a light block with the given lights, setting the unused ones to their defaults.
+    /// Lights past `MAX_LIGHTS` of a kind are dropped, like in a
+    /// [CountedArray](crate::CountedArray).
+    pub fn new(directional: &[DirectionalLight], point: &[PointLight], spot: &[SpotLight]) -> Self {
+        LightBlock {
+            directional_count: directional.len().min(MAX_LIGHTS) as u32,
+            point_count: point.len().min(MAX_LIGHTS) as u32,
+            spot_count: spot.len().min(MAX_LIGHTS) as u32,
+            directional: bounded(directional),
+            point: bounded(point),
+            spot: bounded(spot),
+        }
+    }
+}
+
+/// The first `N` of `values`, followed by defaults if there are fewer.
+fn bounded<T: Copy + Default, const N: usize>(values: &[T]) -> [T; N] {
+    core::array::from_fn(|i| values.get(i).copied().unwrap_or_default())
+}
+
+/// Parameters of a metallic-roughness PBR material with bindless texture indices, matching
+///
+/// ```glsl
+/// layout(std140) uniform Material {
+///     vec4 base_color;
+///     vec3 emissive;
+///     float metallic;
+///     float roughness;
+///     float normal_scale;
+///     float occlusion_strength;
+///     float alpha_cutoff;
+///     uint base_color_texture;
+///     uint metallic_roughness_texture;
+///     uint normal_texture;
+///     uint emissive_texture;
+///     uint occlusion_texture;
+/// };
+/// ```
+///
+/// Texture indices refer to a bindless texture array, with [MaterialBlock::NO_TEXTURE] marking an
+/// unused slot. `metallic` and `roughness` are validated to lie in `0.0..=1.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::presets::MaterialBlock;
+/// use mint_std140::{std140_offset_of, Std140Validate};
+///
+/// let material = MaterialBlock { roughness: 0.3, base_color_texture: 7, ..Default::default() };
+/// assert_eq!(material.normal_texture, MaterialBlock::NO_TEXTURE);
+/// assert_eq!(std140_offset_of!(MaterialBlock, metallic), 28);
+/// assert_eq!(std140_offset_of!(MaterialBlock, base_color_texture), 48);
+///
+/// let invalid = MaterialBlock { metallic: 1.5, ..material };
+/// assert!(invalid.validate().is_err());
+/// ```
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+#[std140(default)]
+pub struct MaterialBlock {
+    /// The linear base color and alpha, multiplied with the base color texture.
+    #[std140(default = [1.0; 4].into())]
+    pub base_color: mint::Vector4<f32>,
+    /// The linear emitted color, multiplied with the emissive texture.
+    #[std140(default = [0.0; 3].into())]
+    pub emissive: mint::Vector3<f32>,
+    /// How metallic the surface is, multiplied with the blue channel of the metallic-roughness
+    /// texture.
+    #[std140(range = 0.0..=1.0, default = 1.0)]
+    pub metallic: f32,
+    /// How rough the surface is, multiplied with the green channel of the metallic-roughness
+    /// texture.
+    #[std140(range = 0.0..=1.0, default = 1.0)]
+    pub roughness: f32,
+    /// The scale applied to the X and Y of normals from the normal texture.
+    #[std140(default = 1.0)]
+    pub normal_scale: f32,
+    /// How much the occlusion texture darkens ambient light.
+    #[std140(default = 1.0)]
+    pub occlusion_strength: f32,
+    /// The alpha below which fragments are discarded, or 0 to disable alpha testing.
+    #[std140(default = 0.0)]
+    pub alpha_cutoff: f32,
+    /// The index of the base color texture.
+    #[std140(default = MaterialBlock::NO_TEXTURE)]
+    pub base_color_texture: u32,
+    /// The index of the metallic-roughness texture.
+    #[std140(default = MaterialBlock::NO_TEXTURE)]
+    pub metallic_roughness_texture: u32,
+    /// The index of the tangent space normal texture.
+    #[std140(default = MaterialBlock::NO_TEXTURE)]
+    pub normal_texture: u32,
+    /// The index of the emissive texture.
+    #[std140(default = MaterialBlock::NO_TEXTURE)]
+    pub emissive_texture: u32,
+    /// The index of the occlusion texture.
+    #[std140(default = MaterialBlock::NO_TEXTURE)]
+    pub occlusion_texture: u32,
+}
+
+impl MaterialBlock {
+    /// The texture index of an unused texture slot.
+    pub const NO_TEXTURE: u32 = u32::MAX;
+}
+
+/// The light-space matrices and split depths of up to four shadow map cascades, matching
+///
+/// ```glsl
+/// layout(std140) uniform Cascades {
+///     mat4 light_space[4];
+///     vec4 split_depths;
+///     uint cascade_count;
+/// };
+/// ```
+///
+/// The split depths are packed into one `vec4` rather than declared as `float[4]`, which std140
+/// would pad to 16 bytes per element. A fragment uses the first cascade `i` whose
+/// `split_depths[i]` lies beyond its view depth.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::presets::ShadowCascadeBlock;
+/// use mint_std140::{std140_offset_of, Std140Sized};
+///
+/// let identity: mint::ColumnMatrix4<f32> =
+///     [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]
+///         .into();
+/// let cascades = ShadowCascadeBlock::new(&[identity; 3], &[10.0, 40.0, 150.0]);
+/// assert_eq!(cascades.cascade_count, 3);
+/// assert_eq!(cascades.split_depths, [10.0, 40.0, 150.0, 0.0].into());
+/// assert_eq!(std140_offset_of!(ShadowCascadeBlock, split_depths), 256);
+/// assert_eq!(std140_offset_of!(ShadowCascadeBlock, cascade_count), 272);
+/// assert_eq!(ShadowCascadeBlock::SIZE, 288);
+/// ```
+#[derive(AsStd140, Clone, Copy, Debug, PartialEq)]
+pub struct ShadowCascadeBlock {
+    /// The matrix from world space to the clip space of each cascade's shadow map.
+    pub light_space: [mint::ColumnMatrix4<f32>; 4],
+    /// The view depth at which each cascade ends.
+    pub split_depths: mint::Vector4<f32>,
+    /// The number of cascades in use.
+    pub cascade_count: u32,
+}
+
+impl ShadowCascadeBlock {
+    /// The number of cascades the block has room for.
+    pub const MAX_CASCADES: usize = 4;
+
+    /// Fill a cascade block with a light-space matrix and split depth per cascade, zeroing the
+    /// unused ones. Cascades past [ShadowCascadeBlock::MAX_CASCADES] are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `light_space` and `split_depths` have different lengths.
+    pub fn new(light_space: &[mint::ColumnMatrix4<f32>], split_depths: &[f32]) -> Self {
+        assert_eq!(
+            light_space.len(),
+            split_depths.len(),
+            "every shadow cascade needs a light-space matrix and a split depth"
+        );
+        let count = light_space.len().min(Self::MAX_CASCADES);
+        let depths: [f32; 4] =
+            core::array::from_fn(|i| split_depths.get(i).copied().unwrap_or(0.0));
+        ShadowCascadeBlock {
+            light_space: core::array::from_fn(|i| light_space.get(i).copied().unwrap_or(ZERO)),
+            split_depths: depths.into(),
+            cascade_count: count as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CameraBlock, DirectionalLight, LightBlock};
+    use crate::{std140_offset_of, transform_3d, wgsl_struct};
+
+    #[test]
+    fn camera() {
+        let rotation = mint::Quaternion { v: [0.0, 0.0, 0.0].into(), s: 1.0 };
+        let world = transform_3d([1.0, 2.0, 3.0].into(), rotation, [1.0; 3].into());
+        let view = crate::transform::inverse(&world).unwrap();
+        let projection = [[2.0, 0.0, 0.0, 0.0], [0.0; 4], [0.0; 4], [0.0; 4]].into();
+
+        let camera = CameraBlock::new(view, projection);
+        assert_eq!(camera.position, [1.0, 2.0, 3.0].into());
+        assert_eq!(camera.inverse_projection, super::ZERO);
+        assert_eq!(camera.view_projection.w, [-2.0, 0.0, 0.0, 0.0].into());
+        assert_eq!(std140_offset_of!(CameraBlock, inverse_view), 192);
+    }
+
+    #[test]
+    fn lights() {
+        let sun = DirectionalLight { direction: [0.0, -1.0, 0.0].into(), ..Default::default() };
+        let lights = LightBlock::<2>::new(&[sun; 3], &[], &[]);
+        assert_eq!((lights.directional_count, lights.point_count), (2, 0));
+        assert_eq!(lights.directional, [sun; 2]);
+
+        let wgsl = wgsl_struct::<LightBlock<2>>();
+        assert!(wgsl.contains("    directional: array<DirectionalLight, 2>,\n"));
+        assert!(wgsl.contains("    outer_cone_cos: f32,\n"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn std140_type() {
+        use super::LightBlockStd140;
+        use crate::{AsStd140, LayoutKind, Std140Layout, Std140Sized};
+
+        // The std140 type of the generic block follows its layout.
+        let sun = DirectionalLight { direction: [0.0, -1.0, 0.0].into(), ..Default::default() };
+        let lights = LightBlock::<2>::new(&[sun; 3], &[], &[]);
+        let fields = match LightBlock::<2>::layout().kind {
+            LayoutKind::Struct { fields, .. } => fields,
+            _ => unreachable!(),
+        };
+        let offsets = [
+            core::mem::offset_of!(LightBlockStd140<2>, directional_count),
+            core::mem::offset_of!(LightBlockStd140<2>, point_count),
+            core::mem::offset_of!(LightBlockStd140<2>, spot_count),
+            core::mem::offset_of!(LightBlockStd140<2>, directional),
+            core::mem::offset_of!(LightBlockStd140<2>, point),
+            core::mem::offset_of!(LightBlockStd140<2>, spot),
+        ];
+        assert!(fields.iter().map(|field| field.offset).eq(offsets.iter().copied()));
+        assert_eq!(core::mem::size_of::<LightBlockStd140<2>>(), LightBlock::<2>::SIZE);
+        assert_eq!(lights.as_std140().directional_count, std140::uint(2));
+    }
+}
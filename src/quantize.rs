@@ -0,0 +1,363 @@
+#[cfg(feature = "alloc")]
+use crate::{Field, LayoutKind, Std140Layout};
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
+
+/// How a quantized float field of a derived struct is packed, with the number of float
+/// components it had before packing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Quantization {
+    /// Four 8-bit unsigned normalized components per `uint`, read with `unpackUnorm4x8`.
+    Unorm8(usize),
+    /// Two 16-bit floats per `uint`, read with `unpackHalf2x16`.
+    Half(usize),
+}
+
+impl Quantization {
+    /// The GLSL type of the unpacked value.
+    pub fn glsl_type(self) -> &'static str {
+        match self {
+            Quantization::Unorm8(1) | Quantization::Half(1) => "float",
+            Quantization::Unorm8(2) | Quantization::Half(2) => "vec2",
+            Quantization::Unorm8(3) | Quantization::Half(3) => "vec3",
+            _ => "vec4",
+        }
+    }
+
+    /// A GLSL expression unpacking the packed value `packed`.
+    #[cfg(feature = "alloc")]
+    pub fn glsl_unpack(self, packed: &str) -> String {
+        match self {
+            Quantization::Unorm8(1) => format!("unpackUnorm4x8({}).x", packed),
+            Quantization::Unorm8(2) => format!("unpackUnorm4x8({}).xy", packed),
+            Quantization::Unorm8(3) => format!("unpackUnorm4x8({}).xyz", packed),
+            Quantization::Unorm8(_) => format!("unpackUnorm4x8({})", packed),
+            Quantization::Half(1) => format!("unpackHalf2x16({}).x", packed),
+            Quantization::Half(2) => format!("unpackHalf2x16({})", packed),
+            Quantization::Half(3) => {
+                format!("vec3(unpackHalf2x16({0}.x), unpackHalf2x16({0}.y).x)", packed)
+            }
+            Quantization::Half(_) => {
+                format!("vec4(unpackHalf2x16({0}.x), unpackHalf2x16({0}.y))", packed)
+            }
+        }
+    }
+}
+
+/// Pack four floats clamped to `[0, 1]` into 8-bit unsigned normalized values, like GLSL's
+/// `packUnorm4x8`. The first component ends up in the least significant byte.
+pub fn pack_unorm4x8(values: [f32; 4]) -> u32 {
+    values.iter().rev().fold(0, |packed, value| {
+        // Adding 0.5 before truncating rounds to the nearest integer, and NaN becomes 0.
+        (packed << 8) | (value.clamp(0.0, 1.0) * 255.0 + 0.5) as u32
+    })
+}
+
+/// Pack two floats into 16-bit floats, like GLSL's `packHalf2x16`. The first component ends up
+/// in the least significant half.
+pub fn pack_half2x16(values: [f32; 2]) -> u32 {
+    u32::from(f32_to_f16(values[0])) | u32::from(f32_to_f16(values[1])) << 16
+}
+
+/// Unpack four 8-bit unsigned normalized values, like GLSL's `unpackUnorm4x8`.
+pub fn unpack_unorm4x8(packed: u32) -> [f32; 4] {
+    core::array::from_fn(|i| (packed >> (8 * i) & 0xff) as f32 / 255.0)
+}
+
+/// Unpack two 16-bit floats, like GLSL's `unpackHalf2x16`.
+pub fn unpack_half2x16(packed: u32) -> [f32; 2] {
+    [f16_to_f32(packed as u16), f16_to_f32((packed >> 16) as u16)]
+}
+
+/// Convert the bits of a 16-bit float to a float, which is always exact.
+pub(crate) fn f16_to_f32(half: u16) -> f32 {
+    let sign = u32::from(half & 0x8000) << 16;
+    let exponent = u32::from(half >> 10 & 0x1f);
+    let mantissa = u32::from(half & 0x3ff);
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Normalize the subnormal by shifting the leading one into the implicit bit.
+            let shift = mantissa.leading_zeros() - 21;
+            sign | (113 - shift) << 23 | (mantissa << shift & 0x3ff) << 13
+        }
+        (0x1f, _) => sign | 0x7f80_0000 | mantissa << 13,
+        _ => sign | (exponent + 112) << 23 | mantissa << 13,
+    };
+    f32::from_bits(bits)
+}
+
+/// Convert a float to the bits of the nearest 16-bit float, rounding ties to even.
+pub(crate) fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return (sign | 0x7c00 | nan) as u16;
+    }
+
+    let exponent = exponent - 127 + 15;
+    let (half, remainder, halfway) = if exponent >= 0x1f {
+        return (sign | 0x7c00) as u16;
+    } else if exponent <= 0 {
+        if exponent < -10 {
+            return sign as u16;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        (mantissa >> shift, mantissa & ((1 << shift) - 1), 1 << (shift - 1))
+    } else {
+        ((exponent as u32) << 10 | mantissa >> 13, mantissa & 0x1fff, 0x1000)
+    };
+
+    // Rounding up may carry into the exponent, which correctly produces the next power of two or
+    // infinity.
+    let round_up = remainder > halfway || (remainder == halfway && half & 1 == 1);
+    (sign | (half + round_up as u32)) as u16
+}
+
+/// Floats that can be stored as 8-bit unsigned normalized values, for
+/// `#[std140(quantize = "unorm8")]`.
+pub trait QuantizeUnorm8 {
+    /// The packed type.
+    type Packed;
+
+    /// The number of float components.
+    const COMPONENTS: usize;
+
+    /// Pack the value.
+    fn quantize_unorm8(&self) -> Self::Packed;
+
+    /// Unpack a value packed with [QuantizeUnorm8::quantize_unorm8].
+    fn dequantize_unorm8(packed: &Self::Packed) -> Self;
+}
+
+/// Floats that can be stored as 16-bit floats, for `#[std140(quantize = "half")]`.
+pub trait QuantizeHalf {
+    /// The packed type.
+    type Packed;
+
+    /// The number of float components.
+    const COMPONENTS: usize;
+
+    /// Pack the value.
+    fn quantize_half(&self) -> Self::Packed;
+
+    /// Unpack a value packed with [QuantizeHalf::quantize_half].
+    fn dequantize_half(packed: &Self::Packed) -> Self;
+}
+
+impl QuantizeUnorm8 for f32 {
+    type Packed = u32;
+    const COMPONENTS: usize = 1;
+
+    fn quantize_unorm8(&self) -> u32 {
+        pack_unorm4x8([*self, 0.0, 0.0, 0.0])
+    }
+
+    fn dequantize_unorm8(packed: &u32) -> Self {
+        unpack_unorm4x8(*packed)[0]
+    }
+}
+
+impl QuantizeUnorm8 for mint::Vector2<f32> {
+    type Packed = u32;
+    const COMPONENTS: usize = 2;
+
+    fn quantize_unorm8(&self) -> u32 {
+        pack_unorm4x8([self.x, self.y, 0.0, 0.0])
+    }
+
+    fn dequantize_unorm8(packed: &u32) -> Self {
+        let [x, y, _, _] = unpack_unorm4x8(*packed);
+        mint::Vector2 { x, y }
+    }
+}
+
+impl QuantizeUnorm8 for mint::Vector3<f32> {
+    type Packed = u32;
+    const COMPONENTS: usize = 3;
+
+    fn quantize_unorm8(&self) -> u32 {
+        pack_unorm4x8([self.x, self.y, self.z, 0.0])
+    }
+
+    fn dequantize_unorm8(packed: &u32) -> Self {
+        let [x, y, z, _] = unpack_unorm4x8(*packed);
+        mint::Vector3 { x, y, z }
+    }
+}
+
+impl QuantizeUnorm8 for mint::Vector4<f32> {
+    type Packed = u32;
+    const COMPONENTS: usize = 4;
+
+    fn quantize_unorm8(&self) -> u32 {
+        pack_unorm4x8([self.x, self.y, self.z, self.w])
+    }
+
+    fn dequantize_unorm8(packed: &u32) -> Self {
+        unpack_unorm4x8(*packed).into()
+    }
+}
+
+impl QuantizeHalf for f32 {
+    type Packed = u32;
+    const COMPONENTS: usize = 1;
+
+    fn quantize_half(&self) -> u32 {
+        pack_half2x16([*self, 0.0])
+    }
+
+    fn dequantize_half(packed: &u32) -> Self {
+        unpack_half2x16(*packed)[0]
+    }
+}
+
+impl QuantizeHalf for mint::Vector2<f32> {
+    type Packed = u32;
+    const COMPONENTS: usize = 2;
+
+    fn quantize_half(&self) -> u32 {
+        pack_half2x16([self.x, self.y])
+    }
+
+    fn dequantize_half(packed: &u32) -> Self {
+        unpack_half2x16(*packed).into()
+    }
+}
+
+impl QuantizeHalf for mint::Vector3<f32> {
+    type Packed = mint::Vector2<u32>;
+    const COMPONENTS: usize = 3;
+
+    fn quantize_half(&self) -> mint::Vector2<u32> {
+        mint::Vector2 { x: pack_half2x16([self.x, self.y]), y: pack_half2x16([self.z, 0.0]) }
+    }
+
+    fn dequantize_half(packed: &mint::Vector2<u32>) -> Self {
+        let ([x, y], [z, _]) = (unpack_half2x16(packed.x), unpack_half2x16(packed.y));
+        mint::Vector3 { x, y, z }
+    }
+}
+
+impl QuantizeHalf for mint::Vector4<f32> {
+    type Packed = mint::Vector2<u32>;
+    const COMPONENTS: usize = 4;
+
+    fn quantize_half(&self) -> mint::Vector2<u32> {
+        mint::Vector2 { x: pack_half2x16([self.x, self.y]), y: pack_half2x16([self.z, self.w]) }
+    }
+
+    fn dequantize_half(packed: &mint::Vector2<u32>) -> Self {
+        let ([x, y], [z, w]) = (unpack_half2x16(packed.x), unpack_half2x16(packed.y));
+        mint::Vector4 { x, y, z, w }
+    }
+}
+
+/// Generate GLSL statements unpacking every quantized field of the struct `T`, including fields
+/// of nested structs but not of arrays, from the block instance named `instance`.
+///
+/// Each field is unpacked into a local variable named after its path, with `_` instead of `.`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Material {
+///     #[std140(quantize = "unorm8")]
+///     albedo: mint::Vector3<f32>,
+///     #[std140(quantize = "half")]
+///     roughness: f32,
+/// }
+///
+/// assert_eq!(
+///     mint_std140::glsl_unpack::<Material>("material"),
+///     "vec3 albedo = unpackUnorm4x8(material.albedo).xyz;\n\
+///      float roughness = unpackHalf2x16(material.roughness).x;\n"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn glsl_unpack<T: Std140Layout>(instance: &str) -> String {
+    fn collect(fields: &[Field], path: &str, glsl: &mut String) {
+        for field in fields {
+            let path = format!("{}.{}", path, field.name);
+            match (&field.layout.kind, field.quantization) {
+                (_, Some(quantization)) => writeln!(
+                    glsl,
+                    "{} {} = {};",
+                    quantization.glsl_type(),
+                    path.split_once('.').unwrap().1.replace('.', "_"),
+                    quantization.glsl_unpack(&path)
+                )
+                .unwrap(),
+                (LayoutKind::Struct { fields, .. }, None) => collect(fields, &path, glsl),
+                _ => {}
+            }
+        }
+    }
+
+    let mut glsl = String::new();
+    if let LayoutKind::Struct { fields, .. } = &T::layout().kind {
+        collect(fields, instance, &mut glsl);
+    }
+    glsl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f16_to_f32, f32_to_f16, pack_half2x16, pack_unorm4x8, unpack_unorm4x8};
+
+    #[test]
+    fn packing() {
+        assert_eq!(pack_unorm4x8([0.0, 1.0, 0.5, 2.0]), 0xff80_ff00);
+        assert_eq!(pack_unorm4x8([-1.0, f32::NAN, 0.25, 1.0 / 255.0]), 0x0140_0000);
+
+        assert_eq!(pack_half2x16([1.0, -2.0]), 0xc000_3c00);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(65520.0), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+        assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+        assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+        assert_eq!(f32_to_f16(1.0e-8), 0x0000);
+        assert_eq!(f32_to_f16(1.000_488_3), 0x3c00); // A tie rounds to the even 1.0.
+        assert_eq!(f32_to_f16(1.001_464_8), 0x3c02); // A tie rounds to the even 1.001953125.
+
+        for half in (0..0x7c00).chain(0x8000..0xfc01) {
+            assert_eq!(f32_to_f16(f16_to_f32(half)), half);
+        }
+        assert!(f16_to_f32(0x7e00).is_nan());
+        assert_eq!(unpack_unorm4x8(0xff80_ff00), [0.0, 1.0, 128.0 / 255.0, 1.0]);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive() {
+        use crate::{AsStd140, Std140Bytes, Std140Sized};
+
+        #[derive(AsStd140)]
+        struct Material {
+            #[std140(quantize = "half")]
+            emission: mint::Vector4<f32>,
+            #[std140(quantize = "unorm8")]
+            albedo: mint::Vector3<f32>,
+        }
+
+        let material =
+            Material { emission: [1.0, -2.0, 0.0, 1.0].into(), albedo: [1.0, 0.0, 1.0].into() };
+        assert_eq!(Material::SIZE, 16);
+        let bytes = material.as_std140_le_bytes();
+        assert_eq!(bytes[0..4], 0xc000_3c00u32.to_le_bytes());
+        assert_eq!(bytes[4..8], 0x3c00_0000u32.to_le_bytes());
+        assert_eq!(bytes[8..12], 0x00ff_00ffu32.to_le_bytes());
+        #[cfg(feature = "std")]
+        assert_eq!(material.as_std140().albedo, 0x00ff_00ffu32.to_ne_bytes());
+    }
+}
@@ -0,0 +1,312 @@
+//! Reference std140 layouts derived from the OpenGL specification, for checking Rust types,
+//! toolchains and drivers against known-good offsets and bytes.
+//!
+//! Each [ReferenceBlock] lists its members with their spec-mandated offsets. Its
+//! [pattern](ReferenceBlock::pattern) assigns the `n`-th scalar in the block the value `n`,
+//! starting from 1 (booleans are all `true`), so a shader that writes the same values produces the
+//! same bytes.
+
+use crate::{Component, Endian, Layout, Std140Error, Std140Layout, Std140Value};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::{vec, vec::Vec};
+
+/// A uniform block with its expected std140 layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReferenceBlock {
+    /// The name of the block.
+    pub name: &'static str,
+    /// The GLSL declaration of the block.
+    pub glsl: &'static str,
+    /// The size of the block in bytes.
+    pub size: usize,
+    /// The path, GLSL type and offset of every scalar, vector and matrix in the block.
+    pub members: &'static [(&'static str, &'static str, usize)],
+}
+
+/// The example block of section 7.6.2.2 "Standard Uniform Block Layout" of the OpenGL 4.6
+/// specification.
+pub const SPEC_EXAMPLE: ReferenceBlock = ReferenceBlock {
+    name: "Example",
+    glsl: "layout(std140) uniform Example {
+    float a;
+    vec2 b;
+    vec3 c;
+    struct {
+        int d;
+        bvec2 e;
+    } f;
+    float g;
+    float h[2];
+    mat2x3 i;
+    struct {
+        uvec3 j;
+        vec2 k;
+        float l[2];
+        vec2 m;
+        mat3 n[2];
+    } o[2];
+};",
+    size: 480,
+    members: &[
+        ("a", "float", 0),
+        ("b", "vec2", 8),
+        ("c", "vec3", 16),
+        ("f.d", "int", 32),
+        ("f.e", "bvec2", 40),
+        ("g", "float", 48),
+        ("h[0]", "float", 64),
+        ("h[1]", "float", 80),
+        ("i", "mat2x3", 96),
+        ("o[0].j", "uvec3", 128),
+        ("o[0].k", "vec2", 144),
+        ("o[0].l[0]", "float", 160),
+        ("o[0].l[1]", "float", 176),
+        ("o[0].m", "vec2", 192),
+        ("o[0].n[0]", "mat3", 208),
+        ("o[0].n[1]", "mat3", 256),
+        ("o[1].j", "uvec3", 304),
+        ("o[1].k", "vec2", 320),
+        ("o[1].l[0]", "float", 336),
+        ("o[1].l[1]", "float", 352),
+        ("o[1].m", "vec2", 368),
+        ("o[1].n[0]", "mat3", 384),
+        ("o[1].n[1]", "mat3", 432),
+    ],
+};
+
+/// A `vec3` following a `float` is aligned to 16 bytes.
+pub const VEC3_AFTER_FLOAT: ReferenceBlock = ReferenceBlock {
+    name: "Vec3AfterFloat",
+    glsl: "layout(std140) uniform Vec3AfterFloat {
+    float a;
+    vec3 b;
+};",
+    size: 32,
+    members: &[("a", "float", 0), ("b", "vec3", 16)],
+};
+
+/// A `float` following a `vec3` is packed into the last 4 bytes of the `vec3`'s alignment.
+pub const FLOAT_AFTER_VEC3: ReferenceBlock = ReferenceBlock {
+    name: "FloatAfterVec3",
+    glsl: "layout(std140) uniform FloatAfterVec3 {
+    vec3 a;
+    float b;
+};",
+    size: 16,
+    members: &[("a", "vec3", 0), ("b", "float", 12)],
+};
+
+/// Every column of every `mat2` in an array is padded to 16 bytes.
+pub const MAT2_ARRAY: ReferenceBlock = ReferenceBlock {
+    name: "Mat2Array",
+    glsl: "layout(std140) uniform Mat2Array {
+    mat2 a[2];
+    float b;
+};",
+    size: 80,
+    members: &[("a[0]", "mat2", 0), ("a[1]", "mat2", 32), ("b", "float", 64)],
+};
+
+/// Every element of a `vec2` array is padded to 16 bytes.
+pub const VEC2_ARRAY: ReferenceBlock = ReferenceBlock {
+    name: "Vec2Array",
+    glsl: "layout(std140) uniform Vec2Array {
+    vec2 a[3];
+    vec2 b;
+};",
+    size: 64,
+    members: &[("a[0]", "vec2", 0), ("a[1]", "vec2", 16), ("a[2]", "vec2", 32), ("b", "vec2", 48)],
+};
+
+/// All reference blocks in this module.
+pub const ALL: &[ReferenceBlock] =
+    &[SPEC_EXAMPLE, VEC3_AFTER_FLOAT, FLOAT_AFTER_VEC3, MAT2_ARRAY, VEC2_ARRAY];
+
+impl ReferenceBlock {
+    /// Every scalar in the block, in declaration order.
+    pub fn components(&self) -> Vec<Component> {
+        let mut components = Vec::new();
+        for &(path, glsl_type, offset) in self.members {
+            let layout =
+                Layout::from_glsl_type(glsl_type).expect("reference members are primitives");
+            for component in layout.components() {
+                let path = match component.path.chars().next() {
+                    None => path.to_string(),
+                    Some('[') => format!("{}{}", path, component.path),
+                    Some(_) => format!("{}.{}", path, component.path),
+                };
+                components.push(Component { path, offset: offset + component.offset, ..component });
+            }
+        }
+        components
+    }
+
+    /// The bytes of the block when the `n`-th scalar holds the value `n`, starting from 1, and all
+    /// padding is zero.
+    pub fn pattern(&self, endian: Endian) -> Vec<u8> {
+        let mut bytes = vec![0; self.size];
+        for (i, component) in self.components().into_iter().enumerate() {
+            let n = Std140Value::Uint(i as u32 + 1);
+            n.write(component.scalar, &mut bytes[component.offset..], endian);
+        }
+        bytes
+    }
+
+    /// Check that the std140 layout of `T` puts scalars of the same types at the same offsets as
+    /// this block, ignoring names.
+    pub fn check_layout<T: Std140Layout>(&self) -> Result<(), Std140Error> {
+        if T::SIZE != self.size {
+            return Err(Std140Error::SizeMismatch { size: T::SIZE, expected: self.size });
+        }
+
+        let expected = self.components();
+        let actual = T::layout().components();
+        for (expected, actual) in expected.iter().zip(&actual) {
+            if (expected.offset, expected.scalar) != (actual.offset, actual.scalar) {
+                return Err(Std140Error::ComponentMismatch {
+                    path: actual.path.clone(),
+                    actual: (actual.offset, actual.scalar),
+                    expected_path: expected.path.clone(),
+                    expected: (expected.offset, expected.scalar),
+                });
+            }
+        }
+        if expected.len() != actual.len() {
+            return Err(Std140Error::ComponentCount {
+                count: actual.len(),
+                expected: expected.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that `bytes`, such as a buffer written by a shader, holds this block's
+    /// [pattern](ReferenceBlock::pattern). Padding is not checked.
+    pub fn check_bytes(&self, bytes: &[u8], endian: Endian) -> Result<(), Std140Error> {
+        if bytes.len() < self.size {
+            return Err(Std140Error::TooShort {
+                field: "",
+                required: self.size,
+                available: bytes.len(),
+            });
+        }
+
+        let pattern = self.pattern(endian);
+        for component in self.components() {
+            let range = component.offset..component.offset + component.scalar.size();
+            if bytes[range.clone()] != pattern[range.clone()] {
+                return Err(Std140Error::ValueMismatch {
+                    value: Std140Value::read(component.scalar, &bytes[range.clone()], endian),
+                    expected: Std140Value::read(component.scalar, &pattern[range], endian),
+                    path: component.path,
+                    offset: component.offset,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Panic with a description of the first difference if [check_layout](Self::check_layout)
+    /// fails.
+    pub fn assert_layout<T: Std140Layout>(&self) {
+        if let Err(error) = self.check_layout::<T>() {
+            panic!("{}: {}", self.name, error);
+        }
+    }
+
+    /// Panic with a description of the first difference if [check_bytes](Self::check_bytes)
+    /// fails.
+    pub fn assert_bytes(&self, bytes: &[u8], endian: Endian) {
+        if let Err(error) = self.check_bytes(bytes, endian) {
+            panic!("{}: {}", self.name, error);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{FLOAT_AFTER_VEC3, MAT2_ARRAY, SPEC_EXAMPLE, VEC2_ARRAY, VEC3_AFTER_FLOAT};
+    use crate::{AsStd140, Endian, Std140Bytes, Std140Error};
+
+    #[derive(AsStd140)]
+    struct F {
+        d: i32,
+        e: mint::Vector2<bool>,
+    }
+
+    #[derive(AsStd140)]
+    struct O {
+        j: mint::Vector3<u32>,
+        k: mint::Vector2<f32>,
+        l: [f32; 2],
+        m: mint::Vector2<f32>,
+        n: [mint::ColumnMatrix3<f32>; 2],
+    }
+
+    #[derive(AsStd140)]
+    struct Example {
+        a: f32,
+        b: mint::Vector2<f32>,
+        c: mint::Vector3<f32>,
+        f: F,
+        g: f32,
+        h: [f32; 2],
+        i: mint::ColumnMatrix3x2<f32>,
+        o: [O; 2],
+    }
+
+    #[test]
+    fn spec_example() {
+        SPEC_EXAMPLE.assert_layout::<Example>();
+        assert_eq!(SPEC_EXAMPLE.components().len(), 72);
+
+        let pattern = SPEC_EXAMPLE.pattern(Endian::Little);
+        assert_eq!(&pattern[40..48], &[1, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(&pattern[472..476], &72.0f32.to_le_bytes());
+        SPEC_EXAMPLE.assert_bytes(&pattern, Endian::Little);
+
+        let mut corrupted = pattern.clone();
+        corrupted[208] ^= 1;
+        match SPEC_EXAMPLE.check_bytes(&corrupted, Endian::Little) {
+            Err(Std140Error::ValueMismatch { path, offset: 208, .. }) => {
+                assert_eq!(path, "o[0].n[0][0][0]")
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[derive(AsStd140)]
+    struct Vec3AfterFloat {
+        a: f32,
+        b: mint::Vector3<f32>,
+    }
+
+    #[derive(AsStd140)]
+    struct FloatAfterVec3 {
+        a: mint::Vector3<f32>,
+        b: f32,
+    }
+
+    #[derive(AsStd140)]
+    struct Mat2Array {
+        a: [mint::ColumnMatrix2<f32>; 2],
+        b: f32,
+    }
+
+    #[test]
+    fn corner_cases() {
+        VEC3_AFTER_FLOAT.assert_layout::<Vec3AfterFloat>();
+        FLOAT_AFTER_VEC3.assert_layout::<FloatAfterVec3>();
+        MAT2_ARRAY.assert_layout::<Mat2Array>();
+        assert_eq!(
+            VEC3_AFTER_FLOAT.check_layout::<FloatAfterVec3>(),
+            Err(Std140Error::SizeMismatch { size: 16, expected: 32 })
+        );
+
+        let vec2s: [mint::Vector2<f32>; 4] =
+            [[1.0, 2.0].into(), [3.0, 4.0].into(), [5.0, 6.0].into(), [7.0, 8.0].into()];
+        let bytes = vec2s.as_std140_be_bytes();
+        VEC2_ARRAY.assert_bytes(&bytes, Endian::Big);
+    }
+}
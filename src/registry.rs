@@ -0,0 +1,179 @@
+use crate::bytes::array_stride;
+use crate::{Endian, Layout, Std140Bytes, Std140Error, Std140Layout};
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+use core::any::{Any, TypeId};
+
+/// How a [Std140Registry] packs values of one type.
+#[derive(Clone, Debug)]
+pub struct RegisteredType {
+    /// The name of the type, for error messages and tools.
+    pub type_name: &'static str,
+    /// The std140 layout of the type.
+    pub layout: Layout,
+    /// Write a value of the type to the start of a destination of at least `layout.size` bytes.
+    /// The registry only calls it with values of the type it was registered for.
+    pub write: fn(&dyn Any, &mut [u8], Endian),
+}
+
+impl RegisteredType {
+    /// Describe how to pack `T`.
+    pub fn of<T: Any + Std140Bytes + Std140Layout>() -> Self {
+        RegisteredType {
+            type_name: core::any::type_name::<T>(),
+            layout: T::layout(),
+            write: |value, bytes, endian| match value.downcast_ref::<T>() {
+                Some(value) => value.write_std140(bytes, endian),
+                None => panic!("value is not a {}", core::any::type_name::<T>()),
+            },
+        }
+    }
+}
+
+/// A table from [TypeId] to the packing function and layout of a type, for packing values whose
+/// type is only known at run time, such as parameter structs passed as `&dyn Any` by plugins.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{AsStd140, Endian, Std140Registry};
+/// use std::any::{Any, TypeId};
+///
+/// #[derive(AsStd140)]
+/// struct Ripple {
+///     center: mint::Vector2<f32>,
+///     speed: f32,
+/// }
+///
+/// let mut registry = Std140Registry::new();
+/// registry.register::<Ripple>();
+///
+/// let parameters: Box<dyn Any> = Box::new(Ripple { center: [0.5; 2].into(), speed: 2.0 });
+/// let bytes = registry.pack(&*parameters, Endian::Little).unwrap();
+/// assert_eq!(bytes.len(), registry.get(TypeId::of::<Ripple>()).unwrap().layout.size);
+/// assert_eq!(&bytes[8..12], &2.0f32.to_le_bytes());
+///
+/// assert!(registry.pack(&1.0f64, Endian::Little).is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Std140Registry {
+    types: BTreeMap<TypeId, RegisteredType>,
+}
+
+impl Std140Registry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `T` with its derived packing, returning whether it was not registered before.
+    pub fn register<T: Any + Std140Bytes + Std140Layout>(&mut self) -> bool {
+        self.insert(TypeId::of::<T>(), RegisteredType::of::<T>()).is_none()
+    }
+
+    /// Register a type by its [TypeId] with a packing function and layout of its own, returning
+    /// the previous registration of the type.
+    pub fn insert(
+        &mut self,
+        type_id: TypeId,
+        registered: RegisteredType,
+    ) -> Option<RegisteredType> {
+        self.types.insert(type_id, registered)
+    }
+
+    /// Remove the registration of a type, such as when the plugin defining it is unloaded.
+    pub fn remove(&mut self, type_id: TypeId) -> Option<RegisteredType> {
+        self.types.remove(&type_id)
+    }
+
+    /// The registration of a type.
+    pub fn get(&self, type_id: TypeId) -> Option<&RegisteredType> {
+        self.types.get(&type_id)
+    }
+
+    /// The number of registered types.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Whether no type is registered.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Write `value` to the start of `bytes` in the given byte order, returning the number of
+    /// bytes written.
+    pub fn write(
+        &self,
+        value: &dyn Any,
+        bytes: &mut [u8],
+        endian: Endian,
+    ) -> Result<usize, Std140Error> {
+        let registered = self.lookup(value)?;
+        let size = registered.layout.size;
+        if bytes.len() < size {
+            return Err(Std140Error::Overflow {
+                field: registered.type_name,
+                offset: 0,
+                required: size,
+                available: bytes.len(),
+            });
+        }
+        (registered.write)(value, &mut bytes[..size], endian);
+        Ok(size)
+    }
+
+    /// Pack `value` in the given byte order, padded to a multiple of 16 bytes like a std140
+    /// array element.
+    pub fn pack(&self, value: &dyn Any, endian: Endian) -> Result<Vec<u8>, Std140Error> {
+        let registered = self.lookup(value)?;
+        let mut bytes = vec![0; array_stride(registered.layout.size)];
+        (registered.write)(value, &mut bytes, endian);
+        Ok(bytes)
+    }
+
+    fn lookup(&self, value: &dyn Any) -> Result<&RegisteredType, Std140Error> {
+        let type_id = value.type_id();
+        self.get(type_id).ok_or(Std140Error::UnregisteredType { type_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegisteredType, Std140Registry};
+    use crate::{Endian, Std140Error};
+    use core::any::TypeId;
+
+    #[test]
+    fn registration() {
+        let mut registry = Std140Registry::new();
+        assert!(registry.register::<mint::Vector3<f32>>());
+        assert!(!registry.register::<mint::Vector3<f32>>());
+        assert_eq!(registry.len(), 1);
+
+        let value = mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 };
+        let mut bytes = [0; 8];
+        assert_eq!(
+            registry.write(&value, &mut bytes, Endian::Big),
+            Err(Std140Error::Overflow {
+                field: "mint::vector::Vector3<f32>",
+                offset: 0,
+                required: 12,
+                available: 8
+            })
+        );
+        assert_eq!(registry.pack(&value, Endian::Big).unwrap()[4..8], 2.0f32.to_be_bytes());
+
+        // A plugin can register its own packing for a type.
+        let mut custom = RegisteredType::of::<u32>();
+        custom.write = |_, bytes, endian| endian.write_u32(bytes, 7);
+        registry.insert(TypeId::of::<u32>(), custom);
+        assert_eq!(registry.pack(&1u32, Endian::Little).unwrap()[..4], 7u32.to_le_bytes());
+
+        assert!(registry.remove(TypeId::of::<u32>()).is_some());
+        assert_eq!(
+            registry.pack(&1u32, Endian::Little),
+            Err(Std140Error::UnregisteredType { type_id: TypeId::of::<u32>() })
+        );
+    }
+}
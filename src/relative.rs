@@ -0,0 +1,129 @@
+use mint::{ColumnMatrix4, Vector3, Vector4};
+
+/// Converts `f64` world-space values to `f32` values relative to a camera origin, subtracting in
+/// double precision before narrowing, for renderers whose worlds are too large for `f32`
+/// positions.
+///
+/// The shader then works in a space centered on the camera, where `f32` keeps its precision:
+/// positions come from [RelativeEye::position] or [RelativeEye::point], model matrices from
+/// [RelativeEye::model_matrix] and the view matrix from [RelativeEye::view_matrix], whose
+/// translation only holds the small offset of the eye from the origin.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::RelativeEye;
+///
+/// let eye = RelativeEye::new([6_371_000.0, 0.0, 1.5].into());
+/// let position = eye.position([6_371_000.25, 2.0, 1.5].into());
+/// assert_eq!(position, [0.25, 2.0, 0.0].into());
+///
+/// let model: mint::ColumnMatrix4<f64> = [
+///     [1.0, 0.0, 0.0, 0.0],
+///     [0.0, 1.0, 0.0, 0.0],
+///     [0.0, 0.0, 1.0, 0.0],
+///     [6_371_010.0, 0.0, 2.5, 1.0],
+/// ]
+/// .into();
+/// assert_eq!(eye.model_matrix(&model).w, [10.0, 0.0, 1.0, 1.0].into());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RelativeEye {
+    /// The world-space origin of the camera-relative space, usually the camera position.
+    pub origin: Vector3<f64>,
+}
+
+impl RelativeEye {
+    /// Convert values relative to `origin`.
+    pub fn new(origin: Vector3<f64>) -> Self {
+        RelativeEye { origin }
+    }
+
+    /// The offset of `position` from the origin, packing as a std140 `vec3`.
+    pub fn position(&self, position: Vector3<f64>) -> Vector3<f32> {
+        Vector3 {
+            x: narrow("RelativeEye.position", position.x - self.origin.x),
+            y: narrow("RelativeEye.position", position.y - self.origin.y),
+            z: narrow("RelativeEye.position", position.z - self.origin.z),
+        }
+    }
+
+    /// The offset of `position` from the origin as a point with a `w` of 1, packing as a std140
+    /// `vec4`.
+    pub fn point(&self, position: Vector3<f64>) -> Vector4<f32> {
+        let Vector3 { x, y, z } = self.position(position);
+        Vector4 { x, y, z, w: 1.0 }
+    }
+
+    /// The model matrix `model` followed by a translation by minus the origin, mapping to
+    /// camera-relative space.
+    pub fn model_matrix(&self, model: &ColumnMatrix4<f64>) -> ColumnMatrix4<f32> {
+        let column = |column: Vector4<f64>| Vector4 {
+            x: narrow("RelativeEye.model_matrix", column.x - self.origin.x * column.w),
+            y: narrow("RelativeEye.model_matrix", column.y - self.origin.y * column.w),
+            z: narrow("RelativeEye.model_matrix", column.z - self.origin.z * column.w),
+            w: narrow("RelativeEye.model_matrix", column.w),
+        };
+        ColumnMatrix4 {
+            x: column(model.x),
+            y: column(model.y),
+            z: column(model.z),
+            w: column(model.w),
+        }
+    }
+
+    /// A translation by the origin followed by the world-space view matrix `view`, mapping from
+    /// camera-relative space to view space without the jitter of a large `f32` translation.
+    pub fn view_matrix(&self, view: &ColumnMatrix4<f64>) -> ColumnMatrix4<f32> {
+        let column = |column: Vector4<f64>| Vector4 {
+            x: narrow("RelativeEye.view_matrix", column.x),
+            y: narrow("RelativeEye.view_matrix", column.y),
+            z: narrow("RelativeEye.view_matrix", column.z),
+            w: narrow("RelativeEye.view_matrix", column.w),
+        };
+        let Vector3 { x, y, z } = self.origin;
+        let translation = Vector4 {
+            x: view.x.x * x + view.y.x * y + view.z.x * z + view.w.x,
+            y: view.x.y * x + view.y.y * y + view.z.y * z + view.w.y,
+            z: view.x.z * x + view.y.z * y + view.z.z * z + view.w.z,
+            w: view.x.w * x + view.y.w * y + view.z.w * z + view.w.w,
+        };
+        ColumnMatrix4 {
+            x: column(view.x),
+            y: column(view.y),
+            z: column(view.z),
+            w: column(translation),
+        }
+    }
+}
+
+/// Narrow `value` to `f32`, recording the loss under `path` with the `audit` feature.
+#[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+fn narrow(path: &'static str, value: f64) -> f32 {
+    let narrowed = value as f32;
+    #[cfg(feature = "audit")]
+    crate::audit::record_narrowed(path, value, narrowed);
+    narrowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RelativeEye;
+
+    #[test]
+    fn view_matrix() {
+        let eye = RelativeEye::new([1.0e9, -2.0e9, 3.0].into());
+        // A view matrix looking down -z from the eye, translating by minus its position.
+        let view: mint::ColumnMatrix4<f64> = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0e9 - 0.5, 2.0e9, -3.0, 1.0],
+        ]
+        .into();
+        let relative = eye.view_matrix(&view);
+        assert_eq!(relative.w, [-0.5, 0.0, 0.0, 1.0].into());
+        assert_eq!(relative.x, [1.0, 0.0, 0.0, 0.0].into());
+        assert_eq!(eye.point([1.0e9 + 0.125, -2.0e9, 3.0].into()), [0.125, 0.0, 0.0, 1.0].into());
+    }
+}
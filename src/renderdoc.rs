@@ -0,0 +1,81 @@
+use crate::{LayoutKind, Std140Layout};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Generate a RenderDoc buffer format declaration for the std140 layout of `T`, to paste into
+/// RenderDoc's buffer viewer.
+///
+/// The fields of a struct are declared at the top level, so they show up by name in the viewer;
+/// any other type is declared as a single member named `value`.
+///
+/// # Examples
+///
+/// ```rust
+/// let format = mint_std140::renderdoc_format::<[mint::Vector3<f32>; 4]>();
+/// assert_eq!(format, "#pack(std140)\n\nvec3 value[4];\n");
+/// ```
+pub fn renderdoc_format<T: Std140Layout>() -> String {
+    let layout = T::layout();
+    let mut format = String::from("#pack(std140)\n\n");
+
+    let definitions = layout.struct_definitions();
+    let nested = match layout.kind {
+        LayoutKind::Struct { .. } => &definitions[..definitions.len() - 1],
+        _ => &definitions[..],
+    };
+    for (name, fields) in nested {
+        writeln!(format, "struct {}\n{{", name).unwrap();
+        for field in fields.iter() {
+            writeln!(format, "    {};", field.layout.glsl_declaration(field.name)).unwrap();
+        }
+        format.push_str("};\n\n");
+    }
+
+    match &layout.kind {
+        LayoutKind::Struct { fields, .. } => {
+            for field in fields {
+                writeln!(format, "{};", field.layout.glsl_declaration(field.name)).unwrap();
+            }
+        }
+        _ => writeln!(format, "{};", layout.glsl_declaration("value")).unwrap(),
+    }
+
+    format
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::renderdoc_format;
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        enabled: bool,
+    }
+
+    #[derive(AsStd140)]
+    struct Scene {
+        view_projection: mint::ColumnMatrix4<f32>,
+        sun: Light,
+        lights: [Light; 2],
+    }
+
+    #[test]
+    fn structs() {
+        assert_eq!(
+            renderdoc_format::<Scene>(),
+            "#pack(std140)\n\
+             \n\
+             struct Light\n\
+             {\n    \
+                 vec3 position;\n    \
+                 bool enabled;\n\
+             };\n\
+             \n\
+             mat4 view_projection;\n\
+             Light sun;\n\
+             Light lights[2];\n"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{Layout, LayoutKind};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// A row of a layout report.
+struct Row {
+    field: String,
+    glsl_type: String,
+    offset: usize,
+    size: usize,
+    padding: usize,
+}
+
+const HEADER: [&str; 5] = ["field", "type", "offset", "size", "padding"];
+
+/// Render the table of [Std140Layout::layout_report](crate::Std140Layout::layout_report).
+pub(crate) fn layout_report(layout: &Layout) -> String {
+    let rows = rows(layout);
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.field.clone(),
+                row.glsl_type.clone(),
+                row.offset.to_string(),
+                row.size.to_string(),
+                row.padding.to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = HEADER.map(str::len);
+    for cells in &cells {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut report = String::new();
+    let mut write_line = |cells: [&str; 5]| {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {:>w4$}",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            cells[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+        );
+        report.push_str(line.trim_end());
+        report.push('\n');
+    };
+    write_line(HEADER);
+    for cells in &cells {
+        write_line([&cells[0], &cells[1], &cells[2], &cells[3], &cells[4]]);
+    }
+    let padding = layout.size - layout.scalar_bytes();
+    writeln!(report, "size {}, {} bytes of padding", layout.size, padding).unwrap();
+    report
+}
+
+/// Render the CSV of [Std140Layout::layout_report_csv](crate::Std140Layout::layout_report_csv).
+pub(crate) fn layout_report_csv(layout: &Layout) -> String {
+    let mut csv = HEADER.join(",");
+    csv.push('\n');
+    for row in rows(layout) {
+        writeln!(
+            csv,
+            "{},{},{},{},{}",
+            row.field, row.glsl_type, row.offset, row.size, row.padding
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn rows(layout: &Layout) -> Vec<Row> {
+    let mut rows = Vec::new();
+    match &layout.kind {
+        LayoutKind::Struct { .. } => collect_rows("", 0, layout, &mut rows),
+        _ => rows.push(Row {
+            field: String::new(),
+            glsl_type: layout.glsl_type(),
+            offset: 0,
+            size: layout.size,
+            padding: 0,
+        }),
+    }
+    rows
+}
+
+/// Add a row for each member of the struct `layout` at `offset`, followed by the members of any
+/// struct they hold, or of the first element of any array of structs.
+fn collect_rows(prefix: &str, offset: usize, layout: &Layout, rows: &mut Vec<Row>) {
+    let fields = match &layout.kind {
+        LayoutKind::Struct { fields, .. } => fields,
+        _ => return,
+    };
+    for (i, field) in fields.iter().enumerate() {
+        let end = fields.get(i + 1).map_or(layout.size, |next| next.offset);
+        let path = if prefix.is_empty() {
+            field.name.into()
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        rows.push(Row {
+            field: path.clone(),
+            glsl_type: field.layout.glsl_type(),
+            offset: offset + field.offset,
+            size: field.layout.size,
+            padding: end - field.offset - field.layout.size,
+        });
+        match &field.layout.kind {
+            LayoutKind::Struct { .. } => {
+                collect_rows(&path, offset + field.offset, &field.layout, rows)
+            }
+            LayoutKind::Array { element, .. } => {
+                let path = format!("{}[0]", path);
+                collect_rows(&path, offset + field.offset, element, rows)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::{AsStd140, Std140Layout};
+
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        color: mint::Vector3<f32>,
+    }
+
+    #[derive(AsStd140)]
+    struct Scene {
+        time: f32,
+        lights: [Light; 2],
+        exposure: f32,
+    }
+
+    #[test]
+    fn report() {
+        assert_eq!(
+            Scene::layout_report(),
+            "field               type      offset  size  padding\n\
+             time                float          0     4       12\n\
+             lights              Light[2]      16    64        0\n\
+             lights[0].position  vec3          16    12        4\n\
+             lights[0].color     vec3          32    12        4\n\
+             exposure            float         80     4       12\n\
+             size 96, 40 bytes of padding\n"
+        );
+        assert_eq!(
+            Scene::layout_report_csv().lines().nth(3),
+            Some("lights[0].position,vec3,16,12,4")
+        );
+        assert_eq!(f32::layout_report_csv(), "field,type,offset,size,padding\n,float,0,4,0\n");
+    }
+}
@@ -0,0 +1,358 @@
+//! Plain `#[repr(C)]` std140 value types with named fields, as an alternative to the tuple
+//! structs of the [std140] crate.
+//!
+//! Vectors have the size of their std140 counterparts but only the alignment of their components,
+//! so a `vec3` followed by a `float` packs into 16 bytes as std140 requires. Structs holding them
+//! must place each member at its std140 offset, either with explicit padding fields or, for
+//! matrices and arrays, the padding these types already contain: matrix columns are stored as four
+//! components and array elements are aligned to 16 bytes. `#[std140(repr)]` on a derived struct
+//! generates such a mirror with a `_padN` member after every field.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mint_std140::repr::{AsStd140Repr, Vec3};
+//!
+//! let color = mint::Vector3 { x: 1.0f32, y: 0.5, z: 0.25 }.as_std140_repr();
+//! assert_eq!(color, Vec3 { x: 1.0, y: 0.5, z: 0.25 });
+//! assert_eq!(core::mem::size_of_val(&color), 12);
+//!
+//! let transform = mint::ColumnMatrix2 { x: [1.0f32, 2.0].into(), y: [3.0, 4.0].into() };
+//! let transform = transform.as_std140_repr();
+//! assert_eq!(transform.column(1), [3.0, 4.0]);
+//! assert_eq!(core::mem::size_of_val(&transform), 32);
+//! ```
+
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+/// A type that can be converted to one of the `#[repr(C)]` std140 types of this module.
+pub trait AsStd140Repr {
+    /// The `#[repr(C)]` std140 type.
+    type Repr: Copy + fmt::Debug;
+
+    /// Convert this value to its `#[repr(C)]` std140 type.
+    fn as_std140_repr(&self) -> Self::Repr;
+}
+
+/// A std140 `bool`, stored as a 32-bit integer that is 1 for `true` and 0 for `false`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bool(u32);
+
+impl Bool {
+    /// The boolean value.
+    pub fn get(self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl From<bool> for Bool {
+    fn from(value: bool) -> Self {
+        Bool(value as u32)
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(value: Bool) -> Self {
+        value.get()
+    }
+}
+
+/// A std140 `vec2`, `ivec2`, `uvec2` or `bvec2`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec2<T> {
+    /// The x component.
+    pub x: T,
+    /// The y component.
+    pub y: T,
+}
+
+/// A std140 `vec3`, `ivec3`, `uvec3` or `bvec3`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec3<T> {
+    /// The x component.
+    pub x: T,
+    /// The y component.
+    pub y: T,
+    /// The z component.
+    pub z: T,
+}
+
+/// A std140 `vec4`, `ivec4`, `uvec4` or `bvec4`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec4<T> {
+    /// The x component.
+    pub x: T,
+    /// The y component.
+    pub y: T,
+    /// The z component.
+    pub z: T,
+    /// The w component.
+    pub w: T,
+}
+
+/// A std140 `matC` or `matCxR` with `C` columns of `R` rows, each column padded to 16 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat<const C: usize, const R: usize> {
+    columns: [[f32; 4]; C],
+}
+
+impl<const C: usize, const R: usize> Mat<C, R> {
+    /// The matrix with every element zero.
+    pub const ZERO: Self = Mat { columns: [[0.0; 4]; C] };
+
+    /// Create a matrix from its columns.
+    pub fn from_columns(columns: [[f32; R]; C]) -> Self {
+        let mut matrix = Self::ZERO;
+        for (padded, column) in matrix.columns.iter_mut().zip(&columns) {
+            padded[..R].copy_from_slice(column);
+        }
+        matrix
+    }
+
+    /// The column at `index`, without its padding.
+    pub fn column(&self, index: usize) -> [f32; R] {
+        core::array::from_fn(|row| self.columns[index][row])
+    }
+
+    /// The element at `column` and `row`.
+    pub fn get(&self, column: usize, row: usize) -> f32 {
+        self.columns[column][..R][row]
+    }
+
+    /// Replace the element at `column` and `row`.
+    pub fn set(&mut self, column: usize, row: usize, value: f32) {
+        self.columns[column][..R][row] = value;
+    }
+}
+
+impl<const C: usize, const R: usize> Default for Mat<C, R> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// An element of a std140 array, padded to the 16 byte array stride.
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+struct Element<T>(T);
+
+/// A std140 array of `N` elements of type `T`, each padded to a multiple of 16 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Array<T, const N: usize> {
+    elements: [Element<T>; N],
+}
+
+impl<T, const N: usize> Array<T, N> {
+    /// Create an array from its elements.
+    pub fn new(elements: [T; N]) -> Self {
+        Array { elements: elements.map(Element) }
+    }
+
+    /// The number of elements, `N`.
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Iterate over the elements.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.elements.iter().map(|element| &element.0)
+    }
+}
+
+impl<T: Default, const N: usize> Default for Array<T, N> {
+    fn default() -> Self {
+        Array { elements: core::array::from_fn(|_| Element::default()) }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Array<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.elements[index].0
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Array<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.elements[index].0
+    }
+}
+
+macro_rules! impl_as_std140_repr_for_scalar {
+    ($($rust_type:ty => $repr:ty),+) => {
+        $(
+            impl AsStd140Repr for $rust_type {
+                type Repr = $repr;
+
+                fn as_std140_repr(&self) -> $repr {
+                    (*self).into()
+                }
+            }
+
+            impl AsStd140Repr for mint::Vector2<$rust_type> {
+                type Repr = Vec2<$repr>;
+
+                fn as_std140_repr(&self) -> Self::Repr {
+                    Vec2 { x: self.x.into(), y: self.y.into() }
+                }
+            }
+
+            impl AsStd140Repr for mint::Vector3<$rust_type> {
+                type Repr = Vec3<$repr>;
+
+                fn as_std140_repr(&self) -> Self::Repr {
+                    Vec3 { x: self.x.into(), y: self.y.into(), z: self.z.into() }
+                }
+            }
+
+            impl AsStd140Repr for mint::Vector4<$rust_type> {
+                type Repr = Vec4<$repr>;
+
+                fn as_std140_repr(&self) -> Self::Repr {
+                    Vec4 { x: self.x.into(), y: self.y.into(), z: self.z.into(), w: self.w.into() }
+                }
+            }
+        )+
+    };
+}
+
+impl_as_std140_repr_for_scalar!(f32 => f32, i32 => i32, u32 => u32, bool => Bool);
+
+/// Quaternions are stored as a `vec4` with the vector part in `xyz` and the scalar part in `w`.
+impl AsStd140Repr for mint::Quaternion<f32> {
+    type Repr = Vec4<f32>;
+
+    fn as_std140_repr(&self) -> Vec4<f32> {
+        Vec4 { x: self.v.x, y: self.v.y, z: self.v.z, w: self.s }
+    }
+}
+
+macro_rules! impl_as_std140_repr_for_column_matrix {
+    ($mint_type:ty, $columns:literal, $rows:literal, [$($field:ident),+]) => {
+        impl AsStd140Repr for $mint_type {
+            type Repr = Mat<$columns, $rows>;
+
+            fn as_std140_repr(&self) -> Self::Repr {
+                Mat::from_columns([$(self.$field.into()),+])
+            }
+        }
+    };
+}
+
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix2<f32>, 2, 2, [x, y]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix3x2<f32>, 2, 3, [x, y]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix4x2<f32>, 2, 4, [x, y]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix2x3<f32>, 3, 2, [x, y, z]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix3<f32>, 3, 3, [x, y, z]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix4x3<f32>, 3, 4, [x, y, z]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix2x4<f32>, 4, 2, [x, y, z, w]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix3x4<f32>, 4, 3, [x, y, z, w]);
+impl_as_std140_repr_for_column_matrix!(mint::ColumnMatrix4<f32>, 4, 4, [x, y, z, w]);
+
+impl<T: AsStd140Repr, const N: usize> AsStd140Repr for [T; N] {
+    type Repr = Array<T::Repr, N>;
+
+    fn as_std140_repr(&self) -> Self::Repr {
+        Array::new(core::array::from_fn(|i| self[i].as_std140_repr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsStd140Repr, Bool, Vec2};
+    use crate::Std140Sized;
+    use core::mem::{align_of, size_of};
+
+    fn assert_size<T: AsStd140Repr + Std140Sized>() {
+        assert_eq!(size_of::<T::Repr>(), T::SIZE, "{}", core::any::type_name::<T>());
+        assert!(align_of::<T::Repr>() <= T::ALIGN);
+    }
+
+    #[test]
+    fn sizes() {
+        assert_size::<bool>();
+        assert_size::<mint::Vector2<u32>>();
+        assert_size::<mint::Vector3<f32>>();
+        assert_size::<mint::Vector4<bool>>();
+        assert_size::<mint::Quaternion<f32>>();
+        assert_size::<mint::ColumnMatrix3x2<f32>>();
+        assert_size::<mint::ColumnMatrix2x4<f32>>();
+        assert_size::<[f32; 3]>();
+        assert_size::<[mint::Vector3<f32>; 2]>();
+        assert_size::<[mint::ColumnMatrix2<f32>; 2]>();
+    }
+
+    #[test]
+    fn values() {
+        let vectors = [mint::Vector2 { x: true, y: false }; 3].as_std140_repr();
+        assert_eq!(vectors[2], Vec2 { x: Bool::from(true), y: Bool::from(false) });
+        assert!(vectors.iter().all(|vector| vector.x.get()));
+
+        let matrix = mint::ColumnMatrix2x3 {
+            x: [1.0f32, 2.0].into(),
+            y: [3.0, 4.0].into(),
+            z: [5.0, 6.0].into(),
+        };
+        let mut matrix = matrix.as_std140_repr();
+        assert_eq!((matrix.get(2, 0), matrix.column(1)), (5.0, [3.0, 4.0]));
+        matrix.set(0, 1, 7.0);
+        assert_eq!(matrix.column(0), [1.0, 7.0]);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived() {
+        use super::Vec3;
+        use crate::AsStd140;
+
+        #[derive(AsStd140, Clone, Copy)]
+        #[std140(repr)]
+        struct Light {
+            position: mint::Vector3<f32>,
+            intensity: f32,
+            #[std140(quantize = "unorm8")]
+            color: mint::Vector4<f32>,
+        }
+
+        #[derive(AsStd140)]
+        #[std140(repr)]
+        struct Lights {
+            count: u32,
+            lights: [Light; 2],
+            transform: mint::ColumnMatrix3<f32>,
+        }
+
+        let light = Light {
+            position: [1.0, 2.0, 3.0].into(),
+            intensity: 4.0,
+            color: [1.0, 0.0, 0.0, 1.0].into(),
+        };
+        let repr = light.as_std140_repr();
+        assert_eq!(
+            (repr.position, repr.intensity, repr.color),
+            (Vec3 { x: 1.0, y: 2.0, z: 3.0 }, 4.0, 0xff0000ff)
+        );
+        assert_eq!(size_of::<LightRepr>(), 32);
+        assert_eq!(repr._pad2, [0; 12]);
+
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let lights = Lights { count: 1, lights: [light, light], transform: identity.into() };
+        let repr = lights.as_std140_repr();
+        assert_eq!(core::mem::offset_of!(LightsRepr, lights), 16);
+        assert_eq!(repr.lights[1].position, Vec3 { x: 1.0, y: 2.0, z: 3.0 });
+        assert_eq!(repr.transform.column(2), [0.0, 0.0, 1.0]);
+    }
+}
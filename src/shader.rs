@@ -0,0 +1,304 @@
+use crate::{Field, Layout, LayoutKind, ScalarKind, Std140Layout};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+/// Generate the GLSL declaration of a `layout(std140)` uniform block named `block_name` with the
+/// members of the struct `T`, preceded by the structs nested in it.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Globals {
+///     time: f32,
+///     offsets: [mint::Vector2<f32>; 2],
+/// }
+///
+/// assert_eq!(
+///     mint_std140::glsl_block::<Globals>("Globals"),
+///     "layout(std140) uniform Globals {\n    float time;\n    vec2 offsets[2];\n};\n"
+/// );
+/// ```
+pub fn glsl_block<T: Std140Layout>(block_name: &str) -> String {
+    glsl_block_of(&T::layout(), block_name)
+}
+
+/// Generate the GLSL declaration of a uniform block like [glsl_block], avoiding constructions
+/// that some mobile OpenGL ES drivers miscompile.
+///
+/// Arrays of three-component vectors are declared as arrays of four-component vectors, matrices
+/// with three rows as matrices with four, such as `mat3x4` for `mat3`, and booleans as `uint`s.
+/// Each replacement has the same std140 layout as the type it replaces, with the added components
+/// in the padding, so values pack the same way and are read back with swizzles and constructors
+/// such as `positions[i].xyz`, `mat3(normal_matrix)` and `enabled != 0u`.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Globals {
+///     normal_matrix: mint::ColumnMatrix3<f32>,
+///     offsets: [mint::Vector3<f32>; 2],
+///     enabled: bool,
+/// }
+///
+/// assert_eq!(
+///     mint_std140::glsl_block_conservative::<Globals>("Globals"),
+///     "layout(std140) uniform Globals {\n    \
+///          mat3x4 normal_matrix;\n    \
+///          vec4 offsets[2];\n    \
+///          uint enabled;\n\
+///      };\n"
+/// );
+/// ```
+pub fn glsl_block_conservative<T: Std140Layout>(block_name: &str) -> String {
+    glsl_block_of(&conservative(&T::layout()), block_name)
+}
+
+/// Replace the members of `layout` that [glsl_block_conservative] avoids.
+fn conservative(layout: &Layout) -> Layout {
+    let scalar = |scalar| match scalar {
+        ScalarKind::Bool => ScalarKind::Uint,
+        scalar => scalar,
+    };
+    match &layout.kind {
+        LayoutKind::Scalar(kind) => Layout::scalar(scalar(*kind)),
+        LayoutKind::Vector(kind, len) => Layout::vector(scalar(*kind), *len),
+        LayoutKind::Matrix { columns, rows: 3 } => Layout::matrix(*columns, 4),
+        LayoutKind::Matrix { .. } => layout.clone(),
+        LayoutKind::Array { element, len } => match element.kind {
+            LayoutKind::Vector(kind, 3) => Layout::array(Layout::vector(scalar(kind), 4), *len),
+            _ => Layout::array(conservative(element), *len),
+        },
+        LayoutKind::Struct { name, fields } => Layout::structure(
+            name,
+            fields
+                .iter()
+                .map(|field| Field { layout: conservative(&field.layout), ..field.clone() })
+                .collect(),
+        ),
+    }
+}
+
+fn glsl_block_of(layout: &Layout, block_name: &str) -> String {
+    let definitions = layout.struct_definitions();
+    let (nested, fields) = match definitions.split_last() {
+        Some(((_, fields), nested)) if matches!(layout.kind, LayoutKind::Struct { .. }) => {
+            (nested, fields)
+        }
+        _ => panic!("uniform blocks can only be generated for structs, not {}", layout.glsl_type()),
+    };
+
+    let mut glsl = String::new();
+    for (name, fields) in nested {
+        writeln!(glsl, "struct {} {{", name).unwrap();
+        for field in fields.iter() {
+            writeln!(glsl, "    {};", field.layout.glsl_declaration(field.name)).unwrap();
+        }
+        glsl.push_str("};\n\n");
+    }
+    writeln!(glsl, "layout(std140) uniform {} {{", block_name).unwrap();
+    for field in fields.iter() {
+        writeln!(glsl, "    {};", field.layout.glsl_declaration(field.name)).unwrap();
+    }
+    glsl.push_str("};\n");
+    glsl
+}
+
+/// Generate WGSL declarations of the struct `T` and the structs nested in it, with the std140
+/// layout of `T`, for binding as a `var<uniform>`.
+///
+/// WGSL lays out uniform buffers almost like std140, and members are annotated with `@align` or
+/// `@size` where it does not. Types without a std140-compatible WGSL counterpart are replaced:
+/// booleans become `u32`, matrices with two rows become arrays of `vec4<f32>` columns and the
+/// elements of arrays of scalars and two-component vectors are padded to `vec4`, so the second
+/// element of `h: array<vec4<f32>, 2>` is `h[1].x`. 16-bit floats become `f16`, which needs
+/// `enable f16;`.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct, or if it holds 8-bit or 16-bit integers or arrays of 16-bit
+/// floats, which have no WGSL counterpart.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Fog {
+///     density: f32,
+/// }
+///
+/// #[derive(AsStd140)]
+/// struct Globals {
+///     fog: Fog,
+///     enabled: bool,
+/// }
+///
+/// assert_eq!(
+///     mint_std140::wgsl_struct::<Globals>(),
+///     "struct Fog {\n    @size(16) density: f32,\n}\n\n\
+///      struct Globals {\n    @align(16) fog: Fog,\n    enabled: u32,\n}\n"
+/// );
+/// ```
+pub fn wgsl_struct<T: Std140Layout>() -> String {
+    let layout = T::layout();
+    if !matches!(layout.kind, LayoutKind::Struct { .. }) {
+        panic!("WGSL structs can only be generated for structs, not {}", layout.glsl_type());
+    }
+
+    let mut wgsl = String::new();
+    for (i, (name, fields)) in layout.struct_definitions().into_iter().enumerate() {
+        if i > 0 {
+            wgsl.push('\n');
+        }
+        writeln!(wgsl, "struct {} {{", name).unwrap();
+        let definition = Layout::structure(name, fields.to_vec());
+        let (size, align) = (definition.size, wgsl_align(&definition));
+        for (j, field) in fields.iter().enumerate() {
+            wgsl.push_str("    ");
+            if wgsl_align(&field.layout) != field.layout.align {
+                write!(wgsl, "@align({}) ", field.layout.align).unwrap();
+            }
+            let end = field.offset + field.layout.size;
+            // WGSL pads structs only to their own alignment, which can be less than 16.
+            if j + 1 == fields.len() && end.next_multiple_of(align) != size {
+                write!(wgsl, "@size({}) ", size - field.offset).unwrap();
+            }
+            writeln!(wgsl, "{}: {},", field.name, wgsl_type(&field.layout)).unwrap();
+        }
+        wgsl.push_str("}\n");
+    }
+    wgsl
+}
+
+fn wgsl_scalar(scalar: ScalarKind) -> &'static str {
+    match scalar {
+        ScalarKind::Float => "f32",
+        ScalarKind::Int => "i32",
+        ScalarKind::Uint | ScalarKind::Bool => "u32",
+        ScalarKind::Float16 => "f16",
+        scalar => panic!("WGSL has no {} type", scalar.glsl_name()),
+    }
+}
+
+fn wgsl_type(layout: &Layout) -> String {
+    match &layout.kind {
+        LayoutKind::Scalar(scalar) => wgsl_scalar(*scalar).to_string(),
+        LayoutKind::Vector(scalar, len) => format!("vec{}<{}>", len, wgsl_scalar(*scalar)),
+        LayoutKind::Matrix { columns, rows: 2 } => format!("array<vec4<f32>, {}>", columns),
+        LayoutKind::Matrix { columns, rows } => format!("mat{}x{}<f32>", columns, rows),
+        LayoutKind::Array { element, len } => match element.kind {
+            LayoutKind::Scalar(ScalarKind::Float16)
+            | LayoutKind::Vector(ScalarKind::Float16, _) => {
+                panic!("WGSL cannot pad the elements of {} to 16 bytes", layout.glsl_type())
+            }
+            LayoutKind::Scalar(scalar) | LayoutKind::Vector(scalar, 2) => {
+                format!("array<vec4<{}>, {}>", wgsl_scalar(scalar), len)
+            }
+            _ => format!("array<{}, {}>", wgsl_type(element), len),
+        },
+        LayoutKind::Struct { name, .. } => name.to_string(),
+    }
+}
+
+/// The alignment WGSL gives to the type [wgsl_type] declares for `layout`.
+fn wgsl_align(layout: &Layout) -> usize {
+    match &layout.kind {
+        LayoutKind::Scalar(_) | LayoutKind::Vector(_, _) | LayoutKind::Matrix { .. } => {
+            layout.align
+        }
+        LayoutKind::Array { element, .. } => match element.kind {
+            LayoutKind::Scalar(_) | LayoutKind::Vector(_, 2) => 16,
+            _ => wgsl_align(element),
+        },
+        // Members whose WGSL alignment differs are annotated with their std140 alignment.
+        LayoutKind::Struct { fields, .. } => {
+            fields.iter().map(|field| field.layout.align).max().unwrap_or(4)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{glsl_block, glsl_block_conservative, wgsl_struct};
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Cone {
+        angle: f32,
+        axis: mint::Vector2<f32>,
+    }
+
+    #[derive(AsStd140)]
+    struct Block {
+        weights: [f32; 2],
+        cones: [Cone; 2],
+        uv_transform: mint::ColumnMatrix3x2<f32>,
+        tint: mint::Vector3<f32>,
+    }
+
+    #[test]
+    fn declarations() {
+        assert_eq!(
+            glsl_block::<Block>("Block"),
+            "struct Cone {\n    float angle;\n    vec2 axis;\n};\n\n\
+             layout(std140) uniform Block {\n    \
+                 float weights[2];\n    \
+                 Cone cones[2];\n    \
+                 mat2x3 uv_transform;\n    \
+                 vec3 tint;\n\
+             };\n"
+        );
+        assert_eq!(
+            wgsl_struct::<Block>(),
+            "struct Cone {\n    angle: f32,\n    axis: vec2<f32>,\n}\n\n\
+             struct Block {\n    \
+                 weights: array<vec4<f32>, 2>,\n    \
+                 @align(16) cones: array<Cone, 2>,\n    \
+                 uv_transform: mat2x3<f32>,\n    \
+                 tint: vec3<f32>,\n\
+             }\n"
+        );
+    }
+
+    #[derive(AsStd140)]
+    struct Skin {
+        joints: [mint::Vector3<f32>; 2],
+        flags: mint::Vector2<bool>,
+    }
+
+    #[derive(AsStd140)]
+    struct Conservative {
+        skins: [Skin; 2],
+        basis: [mint::ColumnMatrix3x2<f32>; 2],
+        origin: mint::Vector3<f32>,
+    }
+
+    #[test]
+    fn conservative() {
+        assert_eq!(
+            glsl_block_conservative::<Conservative>("Conservative"),
+            "struct Skin {\n    vec4 joints[2];\n    uvec2 flags;\n};\n\n\
+             layout(std140) uniform Conservative {\n    \
+                 Skin skins[2];\n    \
+                 mat2x4 basis[2];\n    \
+                 vec3 origin;\n\
+             };\n"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{Layout, LayoutKind, Std140Layout};
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt::Write;
+
+/// Render the full std140 layout of `T` in a canonical text format, suitable for committing as a
+/// golden file and checking with [snapshot_diff].
+///
+/// Each member is listed on its own line with its offset relative to the enclosing struct, its
+/// GLSL type, size and base alignment, and its stride if it is an array or a matrix. Members of
+/// nested structs are indented below the member that holds them.
+///
+/// # Examples
+///
+/// ```rust
+/// let snapshot = mint_std140::layout_snapshot::<[mint::Vector3<f32>; 2]>();
+/// assert_eq!(snapshot, "vec3[2] size=32 align=16 stride=16\n");
+/// ```
+pub fn layout_snapshot<T: Std140Layout>() -> String {
+    let layout = T::layout();
+    let mut snapshot = String::new();
+    write_header(&mut snapshot, &layout);
+    snapshot.push('\n');
+    write_members(&mut snapshot, &layout, 1);
+    snapshot
+}
+
+fn write_header(snapshot: &mut String, layout: &Layout) {
+    write!(snapshot, "{} size={} align={}", layout.glsl_type(), layout.size, layout.align).unwrap();
+    match &layout.kind {
+        LayoutKind::Matrix { .. } => snapshot.push_str(" stride=16"),
+        LayoutKind::Array { element, .. } => {
+            write!(snapshot, " stride={}", element.array_stride()).unwrap()
+        }
+        _ => {}
+    }
+}
+
+fn write_members(snapshot: &mut String, layout: &Layout, depth: usize) {
+    match &layout.kind {
+        LayoutKind::Array { element, .. } => write_members(snapshot, element, depth),
+        LayoutKind::Struct { fields, .. } => {
+            for field in fields {
+                write!(
+                    snapshot,
+                    "{:indent$}{:<5} {}: ",
+                    "",
+                    field.offset,
+                    field.name,
+                    indent = 2 * depth
+                )
+                .unwrap();
+                write_header(snapshot, &field.layout);
+                snapshot.push('\n');
+                write_members(snapshot, &field.layout, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Compare two snapshots line by line, ignoring trailing whitespace and line ending style, and
+/// return a diff with removed lines prefixed by `-` and added lines prefixed by `+`, or `None` if
+/// they match.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{layout_snapshot, snapshot_diff};
+///
+/// let golden = "vec3[2] size=32 align=16 stride=16\n";
+/// assert_eq!(snapshot_diff(golden, &layout_snapshot::<[mint::Vector3<f32>; 2]>()), None);
+///
+/// let diff = snapshot_diff(golden, &layout_snapshot::<[mint::Vector3<f32>; 3]>()).unwrap();
+/// assert_eq!(
+///     diff,
+///     "- vec3[2] size=32 align=16 stride=16\n+ vec3[3] size=48 align=16 stride=16\n"
+/// );
+/// ```
+pub fn snapshot_diff(expected: &str, actual: &str) -> Option<String> {
+    let expected: Vec<_> = expected.lines().map(str::trim_end).collect();
+    let actual: Vec<_> = actual.lines().map(str::trim_end).collect();
+    if expected == actual {
+        return None;
+    }
+
+    // Longest common subsequence table, filled from the end.
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            writeln!(diff, "  {}", expected[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            writeln!(diff, "- {}", expected[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(diff, "+ {}", actual[j]).unwrap();
+            j += 1;
+        }
+    }
+    Some(diff)
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{layout_snapshot, snapshot_diff};
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        intensity: f32,
+    }
+
+    #[derive(AsStd140)]
+    struct Uniforms {
+        transform: mint::ColumnMatrix4<f32>,
+        weights: [f32; 3],
+        lights: [Light; 2],
+    }
+
+    #[test]
+    fn snapshot() {
+        let golden = "Uniforms size=144 align=16\n  \
+                      0     transform: mat4 size=64 align=16 stride=16\n  \
+                      64    weights: float[3] size=48 align=16 stride=16\n  \
+                      112   lights: Light[2] size=32 align=16 stride=16\n    \
+                      0     position: vec3 size=12 align=16\n    \
+                      12    intensity: float size=4 align=4\n";
+        let snapshot = layout_snapshot::<Uniforms>();
+        assert_eq!(snapshot_diff(golden, &snapshot.replace('\n', "  \r\n")), None);
+
+        let changed = golden.replace("112   lights", "128   lights");
+        let diff = snapshot_diff(&changed, &snapshot).unwrap();
+        let changes: Vec<_> = diff.lines().filter(|line| !line.starts_with(' ')).collect();
+        assert_eq!(
+            changes,
+            [
+                "-   128   lights: Light[2] size=32 align=16 stride=16",
+                "+   112   lights: Light[2] size=32 align=16 stride=16"
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{Endian, LayoutKind, Std140Bytes, Std140Layout};
+use alloc::{vec, vec::Vec};
+
+/// One array of a block packed by [pack_soa].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoaArray {
+    /// The name of the struct field the array holds.
+    pub name: &'static str,
+    /// The offset of the array from the start of the block.
+    pub offset: usize,
+    /// The distance in bytes between consecutive elements.
+    pub stride: usize,
+}
+
+/// A struct-of-arrays block packed by [pack_soa].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoaBlock {
+    /// One array per struct field, in declaration order.
+    pub arrays: Vec<SoaArray>,
+    /// The packed block.
+    pub bytes: Vec<u8>,
+}
+
+/// Pack `values` in the host byte order as a std140 block with one array per field of `T`,
+/// instead of one array of `T`.
+///
+/// The block matches a GLSL block that declares, for each field `type name` of `T` in order, a
+/// member `type name[N]` where `N` is the number of values.
+///
+/// # Panics
+///
+/// Panics if `T` is not a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{pack_soa, AsStd140};
+///
+/// #[derive(AsStd140)]
+/// struct Particle {
+///     position: mint::Vector3<f32>,
+///     mass: f32,
+/// }
+///
+/// let particles = [
+///     Particle { position: [0.0, 1.0, 2.0].into(), mass: 1.0 },
+///     Particle { position: [3.0, 4.0, 5.0].into(), mass: 2.0 },
+/// ];
+///
+/// // vec3 position[2]; float mass[2];
+/// let block = pack_soa(&particles);
+/// assert_eq!((block.arrays[1].name, block.arrays[1].offset), ("mass", 32));
+/// assert_eq!(&block.bytes[48..52], &2.0f32.to_ne_bytes());
+/// ```
+pub fn pack_soa<T: Std140Bytes + Std140Layout>(values: &[T]) -> SoaBlock {
+    pack_soa_with(values, Endian::NATIVE)
+}
+
+/// Pack `values` like [pack_soa], in the given byte order.
+pub fn pack_soa_with<T: Std140Bytes + Std140Layout>(values: &[T], endian: Endian) -> SoaBlock {
+    let layout = T::layout();
+    let fields = match &layout.kind {
+        LayoutKind::Struct { fields, .. } => fields,
+        _ => panic!("pack_soa needs a struct, got {}", layout.glsl_type()),
+    };
+
+    let mut offset = 0;
+    let arrays: Vec<SoaArray> = fields
+        .iter()
+        .map(|field| {
+            let array = SoaArray { name: field.name, offset, stride: field.layout.array_stride() };
+            offset += array.stride * values.len();
+            array
+        })
+        .collect();
+
+    let mut std140 = vec![0; T::SIZE];
+    let mut bytes = vec![0; offset];
+    for (i, value) in values.iter().enumerate() {
+        value.write_std140(&mut std140, endian);
+        for (field, array) in fields.iter().zip(&arrays) {
+            let to = array.offset + i * array.stride;
+            bytes[to..to + field.layout.size]
+                .copy_from_slice(&std140[field.offset..field.offset + field.layout.size]);
+        }
+    }
+    SoaBlock { arrays, bytes }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::pack_soa_with;
+    use crate::{AsStd140, Endian, FromStd140};
+
+    #[derive(AsStd140)]
+    struct Instance {
+        transform: mint::ColumnMatrix2<f32>,
+        tint: mint::Vector4<f32>,
+        visible: bool,
+    }
+
+    #[test]
+    fn arrays() {
+        let instances: Vec<_> = (0..3)
+            .map(|i| Instance {
+                transform: [[i as f32; 2]; 2].into(),
+                tint: [1.0, 0.5, 0.25, i as f32].into(),
+                visible: i != 1,
+            })
+            .collect();
+
+        let block = pack_soa_with(&instances, Endian::Big);
+        let offsets: Vec<_> = block.arrays.iter().map(|array| array.offset).collect();
+        assert_eq!(offsets, [0, 96, 144]);
+        assert_eq!(block.bytes.len(), 192);
+
+        let tint = mint::Vector4::<f32>::from_std140_bytes(&block.bytes[96 + 32..], Endian::Big);
+        assert_eq!(tint, [1.0, 0.5, 0.25, 2.0].into());
+        let visible = bool::from_std140_bytes(&block.bytes[144 + 16..], Endian::Big);
+        assert!(!visible);
+    }
+}
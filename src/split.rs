@@ -0,0 +1,88 @@
+use crate::bytes::array_stride;
+use crate::Std140Sized;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A part of a std140 array that fits in one uniform block.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArrayChunk {
+    /// The indices of the elements in this chunk.
+    pub elements: Range<usize>,
+    /// The bytes of this chunk within the packed array, such as
+    /// [Std140Vec::as_bytes](crate::Std140Vec::as_bytes).
+    pub bytes: Range<usize>,
+}
+
+/// Split a std140 array of `len` elements of type `T` into chunks that each fit in a uniform
+/// block of `max_uniform_block_size` bytes and start at a multiple of `offset_alignment` bytes,
+/// such as `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`, so each chunk can be bound as its own block.
+///
+/// Every chunk but the last holds the same number of elements, which is the array length the
+/// shader should declare.
+///
+/// # Panics
+///
+/// Panics if `offset_alignment` is not a power of two, or if no chunk of whole elements both
+/// fits in a block and ends at a multiple of `offset_alignment`.
+///
+/// # Examples
+///
+/// ```rust
+/// let chunks = mint_std140::split_array::<mint::ColumnMatrix4<f32>>(600, 16384, 256);
+/// assert_eq!(chunks.len(), 3);
+/// assert_eq!(chunks[1].elements, 256..512);
+/// assert_eq!(chunks[1].bytes, 16384..32768);
+/// assert_eq!(chunks[2].elements, 512..600);
+/// ```
+pub fn split_array<T: Std140Sized>(
+    len: usize,
+    max_uniform_block_size: usize,
+    offset_alignment: usize,
+) -> Vec<ArrayChunk> {
+    split_by_stride(len, array_stride(T::SIZE), max_uniform_block_size, offset_alignment)
+}
+
+/// Split an array of `len` elements `stride` bytes apart like [split_array].
+pub(crate) fn split_by_stride(
+    len: usize,
+    stride: usize,
+    max_block_size: usize,
+    offset_alignment: usize,
+) -> Vec<ArrayChunk> {
+    assert!(offset_alignment.is_power_of_two(), "invalid offset alignment {}", offset_alignment);
+
+    let per_chunk = (1..=max_block_size / stride)
+        .rev()
+        .find(|count| (count * stride).is_multiple_of(offset_alignment))
+        .unwrap_or_else(|| {
+            panic!(
+                "no chunk of {}-byte elements fits in {} bytes and is aligned to {} bytes",
+                stride, max_block_size, offset_alignment
+            )
+        });
+
+    (0..len)
+        .step_by(per_chunk)
+        .map(|start| {
+            let end = len.min(start + per_chunk);
+            ArrayChunk { elements: start..end, bytes: start * stride..end * stride }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_array;
+
+    #[test]
+    fn chunks() {
+        // vec3 elements have a 16 byte stride, so 1000 fit in 16000 bytes but only 992 end at a
+        // multiple of 256.
+        let chunks = split_array::<mint::Vector3<f32>>(2000, 16000, 256);
+        let elements: Vec<_> = chunks.iter().map(|chunk| chunk.elements.clone()).collect();
+        assert_eq!(elements, [0..992, 992..1984, 1984..2000]);
+        assert_eq!(chunks[2].bytes, 31744..32000);
+
+        assert!(split_array::<f32>(0, 16384, 256).is_empty());
+    }
+}
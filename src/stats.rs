@@ -0,0 +1,145 @@
+//! Per-frame accounting of the bytes packed into std140 blocks, for a stats overlay.
+//!
+//! While the `stats` feature is enabled, [Std140Writer](crate::Std140Writer),
+//! [Std140Arena](crate::Std140Arena) and [Std140BlockPacker](crate::Std140BlockPacker) record
+//! every value they pack into a global collector, keyed by its Rust type, along with the peak
+//! usage of any arena. Call [end_frame] once per frame to take the totals and start counting
+//! afresh.
+//!
+//! Recording takes a lock, which is cheap when uncontended but not free, so the feature is
+//! meant for development builds.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use mint_std140::{stats, Std140Arena};
+//!
+//! let mut arena = Std140Arena::new(1024);
+//! arena.alloc(&mint::Vector4 { x: 0.0f32, y: 0.0, z: 0.0, w: 1.0 }).unwrap();
+//! arena.alloc(&mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 1.0 }).unwrap();
+//!
+//! let frame = stats::end_frame();
+//! let vectors = frame.block::<mint::Vector4<f32>>().unwrap();
+//! assert_eq!((vectors.count, vectors.bytes), (2, 32));
+//! assert_eq!(frame.peak_arena_usage, 32);
+//! assert!(stats::end_frame().blocks.is_empty());
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::any::type_name;
+use std::sync::{Mutex, MutexGuard};
+
+/// The values of one type packed during a frame.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlockStats {
+    /// The Rust type of the values, as given by [core::any::type_name].
+    pub type_name: &'static str,
+    /// How many values were packed.
+    pub count: usize,
+    /// How many bytes were packed, excluding alignment padding between values.
+    pub bytes: usize,
+}
+
+/// Everything recorded since the last call to [end_frame].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrameStats {
+    /// The values packed, per type, sorted by type name.
+    pub blocks: Vec<BlockStats>,
+    /// The most bytes any [Std140Arena](crate::Std140Arena) had in use.
+    pub peak_arena_usage: usize,
+}
+
+impl FrameStats {
+    /// The values packed of type `T`.
+    pub fn block<T: ?Sized>(&self) -> Option<&BlockStats> {
+        self.blocks.iter().find(|block| block.type_name == type_name::<T>())
+    }
+
+    /// The number of bytes packed of all types.
+    pub fn total_bytes(&self) -> usize {
+        self.blocks.iter().map(|block| block.bytes).sum()
+    }
+}
+
+struct Collector {
+    blocks: BTreeMap<&'static str, (usize, usize)>,
+    peak_arena_usage: usize,
+}
+
+static COLLECTOR: Mutex<Collector> =
+    Mutex::new(Collector { blocks: BTreeMap::new(), peak_arena_usage: 0 });
+
+fn collector() -> MutexGuard<'static, Collector> {
+    // The collector only holds counters, which stay meaningful if a recording thread panicked.
+    COLLECTOR.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Collector {
+    fn stats(&self) -> FrameStats {
+        FrameStats {
+            blocks: self
+                .blocks
+                .iter()
+                .map(|(&type_name, &(count, bytes))| BlockStats { type_name, count, bytes })
+                .collect(),
+            peak_arena_usage: self.peak_arena_usage,
+        }
+    }
+}
+
+/// Everything recorded so far in the current frame.
+pub fn frame() -> FrameStats {
+    collector().stats()
+}
+
+/// Take everything recorded in the current frame and start the next one.
+pub fn end_frame() -> FrameStats {
+    let mut collector = collector();
+    let stats = collector.stats();
+    collector.blocks.clear();
+    collector.peak_arena_usage = 0;
+    stats
+}
+
+/// Record that a value of type `type_name` and `size` bytes was packed.
+pub(crate) fn record_block(type_name: &'static str, size: usize) {
+    let mut collector = collector();
+    let (count, bytes) = collector.blocks.entry(type_name).or_default();
+    *count += 1;
+    *bytes += size;
+}
+
+/// Record that an arena has `used` bytes in use.
+pub(crate) fn record_arena_usage(used: usize) {
+    let mut collector = collector();
+    collector.peak_arena_usage = collector.peak_arena_usage.max(used);
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::{AsStd140, Std140Arena, Std140BlockPacker, Std140Writer};
+
+    #[derive(AsStd140)]
+    struct Counted {
+        value: mint::Vector3<f32>,
+    }
+
+    #[test]
+    fn collect() {
+        let counted = Counted { value: [1.0; 3].into() };
+        let mut arena = Std140Arena::new(4096);
+        let handle = arena.alloc(&counted).unwrap();
+        arena.set(handle, &counted);
+        Std140BlockPacker::new(256).push("counted", &counted);
+        let mut bytes = [0; 64];
+        Std140Writer::new(&mut bytes).write(&counted);
+
+        // Other tests pack concurrently, so only this test's type is counted exactly.
+        let frame = super::frame();
+        let stats = frame.block::<Counted>().unwrap();
+        assert_eq!((stats.count, stats.bytes), (4, 64));
+        assert!(frame.total_bytes() >= 64);
+        assert!(frame.peak_arena_usage >= 16);
+    }
+}
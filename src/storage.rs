@@ -0,0 +1,178 @@
+use crate::{Endian, Layout, LayoutKind, Std140Bytes, Std140Layout};
+use alloc::{vec, vec::Vec};
+
+/// The kind of buffer binding a [PackedArray] was packed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BufferBinding {
+    /// A uniform buffer, packed with the std140 rules.
+    Uniform,
+    /// A shader storage buffer, packed with the std430 rules.
+    Storage,
+}
+
+/// An array packed by [pack_array].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedArray {
+    /// How the renderer should bind the bytes.
+    pub binding: BufferBinding,
+    /// The distance in bytes between consecutive elements.
+    pub stride: usize,
+    /// The packed elements.
+    pub bytes: Vec<u8>,
+}
+
+/// Pack `values` as a std140 array in the host byte order if it fits in a uniform block of
+/// `max_uniform_block_size` bytes, such as `GL_MAX_UNIFORM_BLOCK_SIZE` or Vulkan's
+/// `maxUniformBufferRange`, and as a std430 array for a shader storage buffer otherwise.
+///
+/// The shader must declare the array in a `uniform` block with `layout(std140)` or in a `buffer`
+/// block with `layout(std430)` to match the returned [BufferBinding].
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{pack_array, BufferBinding};
+///
+/// let offsets = vec![mint::Vector2 { x: 0.0f32, y: 0.0 }; 2048];
+///
+/// let packed = pack_array(&offsets, 65536);
+/// assert_eq!((packed.binding, packed.stride), (BufferBinding::Uniform, 16));
+///
+/// let packed = pack_array(&offsets, 16384);
+/// assert_eq!((packed.binding, packed.stride), (BufferBinding::Storage, 8));
+/// assert_eq!(packed.bytes.len(), 16384);
+/// ```
+pub fn pack_array<T: Std140Bytes + Std140Layout>(
+    values: &[T],
+    max_uniform_block_size: usize,
+) -> PackedArray {
+    pack_array_with(values, max_uniform_block_size, Endian::NATIVE)
+}
+
+/// Pack `values` like [pack_array], in the given byte order.
+pub fn pack_array_with<T: Std140Bytes + Std140Layout>(
+    values: &[T],
+    max_uniform_block_size: usize,
+    endian: Endian,
+) -> PackedArray {
+    if crate::bytes::array_stride(T::SIZE) * values.len() <= max_uniform_block_size {
+        pack_std140(values, endian)
+    } else {
+        pack_std430(values, endian)
+    }
+}
+
+/// Pack `values` as a std140 array.
+pub(crate) fn pack_std140<T: Std140Bytes>(values: &[T], endian: Endian) -> PackedArray {
+    let stride = crate::bytes::array_stride(T::SIZE);
+    let mut bytes = vec![0; stride * values.len()];
+    for (value, element) in values.iter().zip(bytes.chunks_exact_mut(stride)) {
+        value.write_std140(element, endian);
+    }
+    PackedArray { binding: BufferBinding::Uniform, stride, bytes }
+}
+
+/// Pack `values` as a std430 array, by moving each scalar of their std140 representation.
+pub(crate) fn pack_std430<T: Std140Bytes + Std140Layout>(
+    values: &[T],
+    endian: Endian,
+) -> PackedArray {
+    let layout = T::layout();
+    let std140_components = layout.components();
+    let mut std430_offsets = Vec::new();
+    let (size, align) = std430_placement(&layout, 0, &mut std430_offsets);
+    let stride = round_up(size, align);
+
+    let mut std140 = vec![0; T::SIZE];
+    let mut bytes = vec![0; stride * values.len()];
+    for (value, element) in values.iter().zip(bytes.chunks_exact_mut(stride)) {
+        value.write_std140(&mut std140, endian);
+        for (component, &to) in std140_components.iter().zip(&std430_offsets) {
+            let (from, size) = (component.offset, component.scalar.size());
+            element[to..to + size].copy_from_slice(&std140[from..from + size]);
+        }
+    }
+    PackedArray { binding: BufferBinding::Storage, stride, bytes }
+}
+
+fn round_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+/// The std430 size and alignment of `layout`, pushing the offset of each of its scalars, relative
+/// to `base`, in the order of [Layout::components].
+///
+/// std430 only differs from std140 in that arrays and structs are not aligned to 16 bytes, so
+/// scalars and vectors keep their std140 size and alignment.
+fn std430_placement(layout: &Layout, base: usize, offsets: &mut Vec<usize>) -> (usize, usize) {
+    match &layout.kind {
+        LayoutKind::Scalar(scalar) | LayoutKind::Vector(scalar, _) => {
+            offsets.extend((0..layout.size).step_by(scalar.size()).map(|offset| base + offset));
+            (layout.size, layout.align)
+        }
+        LayoutKind::Matrix { columns, rows } => {
+            let column = Layout::vector(crate::ScalarKind::Float, *rows);
+            let stride = round_up(column.size, column.align);
+            for i in 0..*columns {
+                std430_placement(&column, base + i * stride, offsets);
+            }
+            (stride * columns, column.align)
+        }
+        LayoutKind::Array { element, len } => {
+            let mut element_offsets = Vec::new();
+            let (size, align) = std430_placement(element, 0, &mut element_offsets);
+            let stride = round_up(size, align);
+            for i in 0..*len {
+                offsets.extend(element_offsets.iter().map(|offset| base + i * stride + offset));
+            }
+            (stride * len, align)
+        }
+        LayoutKind::Struct { fields, .. } => {
+            let (mut end, mut struct_align) = (0, 1);
+            for field in fields {
+                let mut field_offsets = Vec::new();
+                let (size, align) = std430_placement(&field.layout, 0, &mut field_offsets);
+                let offset = round_up(end, align);
+                offsets
+                    .extend(field_offsets.iter().map(|field_offset| base + offset + field_offset));
+                end = offset + size;
+                struct_align = struct_align.max(align);
+            }
+            (round_up(end, struct_align), struct_align)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{pack_array_with, BufferBinding};
+    use crate::{AsStd140, Endian};
+
+    #[derive(AsStd140)]
+    struct Particle {
+        weight: f32,
+        velocity: mint::Vector2<f32>,
+        corners: [f32; 2],
+    }
+
+    #[test]
+    fn std430_fallback() {
+        let particles = [
+            Particle { weight: 1.0, velocity: [2.0, 3.0].into(), corners: [4.0, 5.0] },
+            Particle { weight: 6.0, velocity: [7.0, 8.0].into(), corners: [9.0, 10.0] },
+        ];
+
+        let packed = pack_array_with(&particles, 96, Endian::Little);
+        assert_eq!((packed.binding, packed.stride), (BufferBinding::Uniform, 48));
+
+        // weight at 0, velocity at 8, corners at 16 and 20.
+        let packed = pack_array_with(&particles, 64, Endian::Little);
+        assert_eq!((packed.binding, packed.stride), (BufferBinding::Storage, 24));
+        let floats: Vec<f32> = packed
+            .bytes
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        assert_eq!(floats, [1.0, 0.0, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0, 7.0, 8.0, 9.0, 10.0]);
+    }
+}
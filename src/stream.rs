@@ -0,0 +1,203 @@
+use crate::{Endian, Std140Bytes, Std140Error};
+use alloc::vec::Vec;
+use core::any::type_name;
+
+/// The number of staged bytes copied to the destination at once, one cache line.
+const CHUNK: usize = 64;
+
+/// Writes std140 values strictly front to back into memory that must never be read, such as a
+/// persistently mapped write-combined buffer.
+///
+/// Values are packed into a small staging buffer in ordinary memory and copied out a cache line
+/// at a time, so every destination byte, padding included, is written exactly once and in order,
+/// and a partly filled line never sits in the write-combining buffers for long. There is no way
+/// to read or revisit the destination through the writer. The last partial line is copied when
+/// the writer is finished or dropped.
+///
+/// With the `nontemporal` feature on x86-64, lines are copied with non-temporal stores when the
+/// destination is 16-byte aligned, followed by a store fence when the writer finishes.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140StreamWriter;
+///
+/// let mut mapped = vec![0xffu8; 256];
+/// let mut writer = Std140StreamWriter::new(&mut mapped);
+/// writer.write(&1.0f32);
+/// assert_eq!(writer.write(&mint::Vector3 { x: 0.0f32, y: 1.0, z: 0.0 }), 16);
+/// assert_eq!(writer.finish(), 28);
+/// assert_eq!(&mapped[4..16], &[0; 12]);
+/// ```
+#[derive(Debug)]
+pub struct Std140StreamWriter<'a> {
+    destination: &'a mut [u8],
+    flushed: usize,
+    staging: Vec<u8>,
+    endian: Endian,
+}
+
+impl<'a> Std140StreamWriter<'a> {
+    /// Create a writer that starts at the beginning of `destination` and writes in the host byte
+    /// order.
+    pub fn new(destination: &'a mut [u8]) -> Self {
+        Self::with_endian(destination, Endian::NATIVE)
+    }
+
+    /// Create a writer that starts at the beginning of `destination` and writes in the given byte
+    /// order.
+    pub fn with_endian(destination: &'a mut [u8], endian: Endian) -> Self {
+        Std140StreamWriter {
+            destination,
+            flushed: 0,
+            staging: Vec::with_capacity(2 * CHUNK),
+            endian,
+        }
+    }
+
+    /// The offset at which the next value would be written, before alignment.
+    pub fn offset(&self) -> usize {
+        self.flushed + self.staging.len()
+    }
+
+    /// Zero bytes up to the next multiple of `align` and return the new offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two or the destination is too small.
+    pub fn align_to(&mut self, align: usize) -> usize {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        let aligned = self.offset().next_multiple_of(align);
+        self.reserve("padding", aligned).unwrap_or_else(|error| panic!("{}", error));
+        self.staging.resize(aligned - self.flushed, 0);
+        self.flush_lines();
+        aligned
+    }
+
+    /// Write `value` at its next aligned offset and return that offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the destination is too small.
+    pub fn write<T: Std140Bytes>(&mut self, value: &T) -> usize {
+        self.try_write(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like [write](Self::write), but return [Std140Error::Overflow] naming the type of `value`
+    /// instead of panicking if the destination is too small, writing nothing.
+    pub fn try_write<T: Std140Bytes>(&mut self, value: &T) -> Result<usize, Std140Error> {
+        let offset = self.offset().next_multiple_of(T::ALIGN);
+        self.reserve(type_name::<T>(), offset + T::SIZE)?;
+        let start = offset - self.flushed;
+        self.staging.resize(start + T::SIZE, 0);
+        value.write_std140(&mut self.staging[start..], self.endian);
+        #[cfg(feature = "stats")]
+        crate::stats::record_block(type_name::<T>(), T::SIZE);
+        self.flush_lines();
+        Ok(offset)
+    }
+
+    /// Copy the remaining staged bytes to the destination and return the number of bytes written.
+    pub fn finish(mut self) -> usize {
+        self.flush();
+        self.flushed
+    }
+
+    fn reserve(&self, field: &'static str, required: usize) -> Result<(), Std140Error> {
+        if required > self.destination.len() {
+            return Err(Std140Error::Overflow {
+                field,
+                offset: self.offset(),
+                required,
+                available: self.destination.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Copy the staged whole lines to the destination, keeping the partial one staged.
+    fn flush_lines(&mut self) {
+        let len = self.staging.len() / CHUNK * CHUNK;
+        if len > 0 {
+            self.copy_out(len);
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.staging.is_empty() {
+            self.copy_out(self.staging.len());
+        }
+        #[cfg(all(feature = "nontemporal", target_arch = "x86_64", not(feature = "safe")))]
+        // SAFETY: SSE2 is part of the x86-64 baseline.
+        unsafe {
+            core::arch::x86_64::_mm_sfence()
+        };
+    }
+
+    fn copy_out(&mut self, len: usize) {
+        let destination = &mut self.destination[self.flushed..self.flushed + len];
+        store(destination, &self.staging[..len]);
+        self.staging.drain(..len);
+        self.flushed += len;
+    }
+}
+
+impl Drop for Std140StreamWriter<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(not(all(feature = "nontemporal", target_arch = "x86_64", not(feature = "safe"))))]
+fn store(destination: &mut [u8], source: &[u8]) {
+    destination.copy_from_slice(source);
+}
+
+#[cfg(all(feature = "nontemporal", target_arch = "x86_64", not(feature = "safe")))]
+fn store(destination: &mut [u8], source: &[u8]) {
+    use core::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_stream_si128};
+
+    if !(destination.as_ptr() as usize).is_multiple_of(16) {
+        return destination.copy_from_slice(source);
+    }
+    let mut destination = destination.chunks_exact_mut(16);
+    let mut source = source.chunks_exact(16);
+    for (to, from) in (&mut destination).zip(&mut source) {
+        // SAFETY: Both chunks are 16 bytes long and `to` is 16-byte aligned, since the whole
+        // destination is. SSE2 is part of the x86-64 baseline.
+        unsafe {
+            let value = _mm_loadu_si128(from.as_ptr() as *const __m128i);
+            _mm_stream_si128(to.as_mut_ptr() as *mut __m128i, value);
+        }
+    }
+    destination.into_remainder().copy_from_slice(source.remainder());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140StreamWriter;
+    use crate::{Endian, Std140Error};
+
+    #[test]
+    fn stream() {
+        let mut bytes = [0xffu8; 160];
+        let mut writer = Std140StreamWriter::with_endian(&mut bytes, Endian::Little);
+        assert_eq!(writer.write(&[7u32; 4]), 0);
+        assert_eq!(writer.offset(), 64);
+        assert_eq!(writer.write(&mint::Vector2 { x: 1.0f32, y: 2.0 }), 64);
+        assert_eq!(writer.align_to(16), 80);
+        assert!(matches!(
+            writer.try_write(&[0u32; 6]),
+            Err(Std140Error::Overflow { offset: 80, required: 176, available: 160, .. })
+        ));
+        assert_eq!(writer.write(&3u32), 80);
+        drop(writer);
+
+        assert_eq!(&bytes[48..52], &7u32.to_le_bytes());
+        assert_eq!(&bytes[52..64], &[0; 12]);
+        assert_eq!(&bytes[68..72], &2.0f32.to_le_bytes());
+        assert_eq!(&bytes[72..80], &[0; 8]);
+        assert_eq!(&bytes[80..84], &3u32.to_le_bytes());
+        assert_eq!(&bytes[84..], &[0xff; 76]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Layout, LayoutKind, ScalarKind, Std140Layout};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::ops::Range;
+
+const BYTE_WIDTH: usize = 32;
+const ROW_HEIGHT: usize = 32;
+const MARGIN: usize = 48;
+const COLORS: [&str; 6] = ["#8ecae6", "#ffb703", "#90be6d", "#f4a261", "#cdb4db", "#f28482"];
+
+/// A run of bytes holding a scalar, a vector or a matrix column.
+struct Member {
+    path: String,
+    glsl_type: String,
+    bytes: Range<usize>,
+}
+
+/// Draw the std140 layout of `T` to scale as an SVG image, one 16-byte row per line, with every
+/// member as a labelled box and every padding region hatched.
+///
+/// Each array element and matrix column gets its own box, so the padding between elements shows
+/// up. Hovering a box shows its full path, type, offset and size. The fields of a struct are drawn
+/// by name; any other type is drawn as a single member named `value`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Fog {
+///     density: f32,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// let svg = mint_std140::layout_svg::<Fog>();
+/// assert!(svg.starts_with("<svg "));
+/// assert!(svg.contains("<title>color: vec3 (offset 16, 12 bytes)</title>"));
+/// assert!(svg.contains("<title>padding (offset 4, 12 bytes)</title>"));
+/// ```
+pub fn layout_svg<T: Std140Layout>() -> String {
+    let layout = T::layout();
+    let mut members = Vec::new();
+    match layout.kind {
+        LayoutKind::Struct { .. } => collect_members(&layout, "", 0, &mut members),
+        _ => collect_members(&layout, "value", 0, &mut members),
+    }
+    members.sort_by_key(|member| member.bytes.start);
+
+    let rows = layout.size.div_ceil(16);
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="11">"##,
+        MARGIN + 16 * BYTE_WIDTH + 1,
+        rows * ROW_HEIGHT + 1
+    )
+    .unwrap();
+    svg.push_str(
+        "  <defs><pattern id=\"padding\" width=\"6\" height=\"6\" patternUnits=\"userSpaceOnUse\" \
+         patternTransform=\"rotate(45)\"><rect width=\"6\" height=\"6\" fill=\"#eee\"/>\
+         <line y2=\"6\" stroke=\"#bbb\" stroke-width=\"2\"/></pattern></defs>\n",
+    );
+    for row in 0..rows {
+        writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            MARGIN - 6,
+            row * ROW_HEIGHT + 20,
+            row * 16
+        )
+        .unwrap();
+    }
+
+    let mut end = 0;
+    for (i, member) in members.iter().enumerate() {
+        if member.bytes.start > end {
+            write_padding(&mut svg, end..member.bytes.start);
+        }
+        let title = format!(
+            "{}: {} (offset {}, {} bytes)",
+            member.path,
+            member.glsl_type,
+            member.bytes.start,
+            member.bytes.len()
+        );
+        let label = member.path.rsplit('.').next().unwrap_or_default();
+        write_box(&mut svg, member.bytes.clone(), COLORS[i % COLORS.len()], label, &title);
+        end = member.bytes.end;
+    }
+    if layout.size > end {
+        write_padding(&mut svg, end..layout.size);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn write_padding(svg: &mut String, bytes: Range<usize>) {
+    let title = format!("padding (offset {}, {} bytes)", bytes.start, bytes.len());
+    write_box(svg, bytes, "url(#padding)", "", &title);
+}
+
+/// Draw `bytes` as one box per row they cover, labelling the first.
+fn write_box(svg: &mut String, bytes: Range<usize>, fill: &str, label: &str, title: &str) {
+    let mut start = bytes.start;
+    while start < bytes.end {
+        let end = bytes.end.min((start / 16 + 1) * 16);
+        let (x, y) = (MARGIN + start % 16 * BYTE_WIDTH, start / 16 * ROW_HEIGHT);
+        writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#333"><title>{}</title></rect>"##,
+            x,
+            y,
+            (end - start) * BYTE_WIDTH,
+            ROW_HEIGHT,
+            fill,
+            title
+        )
+        .unwrap();
+        if start == bytes.start && !label.is_empty() {
+            writeln!(svg, r#"  <text x="{}" y="{}">{}</text>"#, x + 4, y + 20, label).unwrap();
+        }
+        start = end;
+    }
+}
+
+fn collect_members(layout: &Layout, path: &str, offset: usize, members: &mut Vec<Member>) {
+    match &layout.kind {
+        LayoutKind::Scalar(_) | LayoutKind::Vector(_, _) => members.push(Member {
+            path: path.into(),
+            glsl_type: layout.glsl_type(),
+            bytes: offset..offset + layout.size,
+        }),
+        LayoutKind::Matrix { columns, rows } => {
+            for column in 0..*columns {
+                members.push(Member {
+                    path: format!("{}[{}]", path, column),
+                    glsl_type: Layout::vector(ScalarKind::Float, *rows).glsl_type(),
+                    bytes: offset + 16 * column..offset + 16 * column + 4 * rows,
+                })
+            }
+        }
+        LayoutKind::Array { element, len } => {
+            for i in 0..*len {
+                let path = format!("{}[{}]", path, i);
+                collect_members(element, &path, offset + i * element.array_stride(), members);
+            }
+        }
+        LayoutKind::Struct { fields, .. } => {
+            for field in fields {
+                let path = if path.is_empty() {
+                    field.name.into()
+                } else {
+                    format!("{}.{}", path, field.name)
+                };
+                collect_members(&field.layout, &path, offset + field.offset, members);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::layout_svg;
+    use crate::AsStd140;
+
+    #[derive(AsStd140)]
+    struct Block {
+        time: f32,
+        tint: mint::Vector3<f32>,
+        weights: [f32; 2],
+        uv: mint::ColumnMatrix3x2<f32>,
+    }
+
+    #[test]
+    fn boxes() {
+        let svg = layout_svg::<Block>();
+        assert_eq!(svg.matches("<rect ").count(), 12 + 1);
+        assert_eq!(svg.matches("fill=\"url(#padding)\"").count(), 6);
+        assert!(svg.contains(
+            r##"<rect x="48" y="32" width="384" height="32" fill="#ffb703" stroke="#333"><title>tint: vec3 (offset 16, 12 bytes)</title></rect>"##
+        ));
+        assert!(svg.contains("<title>uv[1]: vec3 (offset 80, 12 bytes)</title>"));
+        assert!(svg.contains(r#"<text x="52" y="116">weights[1]</text>"#));
+        assert!(layout_svg::<f32>().contains("<title>value: float (offset 0, 4 bytes)</title>"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Endian, Std140Bytes};
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Set in [UniformSwap::middle] when the middle slot holds a value the reader has not seen.
+const FRESH: usize = 4;
+
+/// A lock-free triple buffer that hands values and their std140 bytes from a publishing thread,
+/// such as a simulation, to a reading thread, such as a renderer.
+///
+/// Publishing never waits for the reader and refreshing never waits for the publisher. After a
+/// refresh, the reader sees the latest value that was completely published, together with bytes
+/// packed from exactly that value; values published in between two refreshes are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::UniformSwap;
+///
+/// let (mut publisher, mut reader) = UniformSwap::new(mint::Vector2 { x: 0.0f32, y: 0.0 });
+///
+/// let simulation = std::thread::spawn(move || {
+///     for step in 1..=100 {
+///         publisher.publish(mint::Vector2 { x: step as f32, y: 1.0 });
+///     }
+/// });
+/// simulation.join().unwrap();
+///
+/// assert!(reader.refresh());
+/// assert_eq!(reader.value().x, 100.0);
+/// assert_eq!(reader.bytes()[..4], 100.0f32.to_ne_bytes());
+/// ```
+pub struct UniformSwap<T> {
+    slots: [UnsafeCell<Slot<T>>; 3],
+    /// The index of the slot owned by neither side, or'ed with [FRESH].
+    middle: AtomicUsize,
+    endian: Endian,
+}
+
+struct Slot<T> {
+    value: T,
+    bytes: Vec<u8>,
+}
+
+// SAFETY: Each slot is only accessed by the side whose index currently refers to it, and
+// ownership of a slot is handed over through `middle` with acquire-release ordering.
+unsafe impl<T: Send + Sync> Sync for UniformSwap<T> {}
+
+/// The publishing side of a [UniformSwap].
+pub struct SwapPublisher<T> {
+    shared: Arc<UniformSwap<T>>,
+    index: usize,
+}
+
+/// The reading side of a [UniformSwap].
+pub struct SwapReader<T> {
+    shared: Arc<UniformSwap<T>>,
+    index: usize,
+}
+
+impl<T: Std140Bytes + Clone> UniformSwap<T> {
+    /// Create a triple buffer holding `value`, packing in the host byte order.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(value: T) -> (SwapPublisher<T>, SwapReader<T>) {
+        Self::with_endian(value, Endian::NATIVE)
+    }
+
+    /// Create a triple buffer holding `value`, packing in the given byte order.
+    pub fn with_endian(value: T, endian: Endian) -> (SwapPublisher<T>, SwapReader<T>) {
+        let mut bytes = vec![0; T::SIZE];
+        value.write_std140(&mut bytes, endian);
+        let slot = || UnsafeCell::new(Slot { value: value.clone(), bytes: bytes.clone() });
+
+        let shared = Arc::new(UniformSwap {
+            slots: [slot(), slot(), slot()],
+            middle: AtomicUsize::new(1),
+            endian,
+        });
+        (SwapPublisher { shared: shared.clone(), index: 0 }, SwapReader { shared, index: 2 })
+    }
+}
+
+impl<T: Std140Bytes> SwapPublisher<T> {
+    /// Pack `value` and make it the latest value for the reader.
+    pub fn publish(&mut self, value: T) {
+        // SAFETY: The publisher owns the slot at its index until it hands it over below.
+        let slot = unsafe { &mut *self.shared.slots[self.index].get() };
+        slot.value = value;
+        slot.value.write_std140(&mut slot.bytes, self.shared.endian);
+
+        let previous = self.shared.middle.swap(self.index | FRESH, Ordering::AcqRel);
+        self.index = previous & !FRESH;
+    }
+}
+
+impl<T> SwapReader<T> {
+    /// Whether a value was published since the last [SwapReader::refresh].
+    pub fn has_update(&self) -> bool {
+        self.shared.middle.load(Ordering::Relaxed) & FRESH != 0
+    }
+
+    /// Switch to the latest published value, returning whether there was a new one. Call this
+    /// once per frame before reading.
+    pub fn refresh(&mut self) -> bool {
+        if !self.has_update() {
+            return false;
+        }
+        let previous = self.shared.middle.swap(self.index, Ordering::AcqRel);
+        self.index = previous & !FRESH;
+        true
+    }
+
+    /// The value as of the last [SwapReader::refresh].
+    pub fn value(&self) -> &T {
+        &self.slot().value
+    }
+
+    /// The std140 bytes of [SwapReader::value].
+    pub fn bytes(&self) -> &[u8] {
+        &self.slot().bytes
+    }
+
+    fn slot(&self) -> &Slot<T> {
+        // SAFETY: The reader owns the slot at its index until it hands it back in `refresh`,
+        // which requires `&mut self` and so cannot happen while this borrow is alive.
+        unsafe { &*self.shared.slots[self.index].get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UniformSwap;
+    use crate::Endian;
+
+    #[test]
+    fn consistent_snapshots() {
+        let (mut publisher, mut reader) = UniformSwap::with_endian([0u32; 8], Endian::Little);
+        assert!(!reader.has_update());
+
+        let simulation = std::thread::spawn(move || {
+            for step in 1..=10_000u32 {
+                publisher.publish([step; 8]);
+            }
+        });
+
+        let mut last = 0;
+        while last < 10_000 {
+            reader.refresh();
+            let bytes = reader.bytes();
+            let step = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            assert!(bytes.chunks(16).all(|element| element[..4] == step.to_le_bytes()));
+            assert_eq!(reader.value()[7], step);
+            assert!(step >= last);
+            last = step;
+        }
+        simulation.join().unwrap();
+    }
+}
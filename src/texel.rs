@@ -0,0 +1,221 @@
+use crate::{Endian, ScalarKind, Std140Bytes, Std140Layout, Std140Value};
+use alloc::{vec, vec::Vec};
+
+/// A 32-bit per channel texel format of a texel buffer, or buffer texture in OpenGL.
+///
+/// There are no three-channel formats, since texel buffers are only required to support one,
+/// two and four channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TexelFormat {
+    /// One float channel, `GL_R32F` or `VK_FORMAT_R32_SFLOAT`.
+    R32Float,
+    /// Two float channels, `GL_RG32F` or `VK_FORMAT_R32G32_SFLOAT`.
+    Rg32Float,
+    /// Four float channels, `GL_RGBA32F` or `VK_FORMAT_R32G32B32A32_SFLOAT`.
+    Rgba32Float,
+    /// One signed integer channel, `GL_R32I` or `VK_FORMAT_R32_SINT`.
+    R32Sint,
+    /// Two signed integer channels, `GL_RG32I` or `VK_FORMAT_R32G32_SINT`.
+    Rg32Sint,
+    /// Four signed integer channels, `GL_RGBA32I` or `VK_FORMAT_R32G32B32A32_SINT`.
+    Rgba32Sint,
+    /// One unsigned integer channel, `GL_R32UI` or `VK_FORMAT_R32_UINT`.
+    R32Uint,
+    /// Two unsigned integer channels, `GL_RG32UI` or `VK_FORMAT_R32G32_UINT`.
+    Rg32Uint,
+    /// Four unsigned integer channels, `GL_RGBA32UI` or `VK_FORMAT_R32G32B32A32_UINT`.
+    Rgba32Uint,
+}
+
+impl TexelFormat {
+    /// The number of channels in a texel.
+    pub fn channels(self) -> usize {
+        match self {
+            TexelFormat::R32Float | TexelFormat::R32Sint | TexelFormat::R32Uint => 1,
+            TexelFormat::Rg32Float | TexelFormat::Rg32Sint | TexelFormat::Rg32Uint => 2,
+            TexelFormat::Rgba32Float | TexelFormat::Rgba32Sint | TexelFormat::Rgba32Uint => 4,
+        }
+    }
+
+    /// The size of a texel in bytes.
+    pub fn texel_size(self) -> usize {
+        4 * self.channels()
+    }
+
+    /// The OpenGL internal format to pass to `glTexBuffer`.
+    pub fn gl_internal_format(self) -> u32 {
+        match self {
+            TexelFormat::R32Float => 0x822e,
+            TexelFormat::Rg32Float => 0x8230,
+            TexelFormat::Rgba32Float => 0x8814,
+            TexelFormat::R32Sint => 0x8235,
+            TexelFormat::Rg32Sint => 0x823b,
+            TexelFormat::Rgba32Sint => 0x8d82,
+            TexelFormat::R32Uint => 0x8236,
+            TexelFormat::Rg32Uint => 0x823c,
+            TexelFormat::Rgba32Uint => 0x8d70,
+        }
+    }
+
+    /// The `VkFormat` of a `VkBufferView`.
+    pub fn vk_format(self) -> u32 {
+        match self {
+            TexelFormat::R32Float => 100,
+            TexelFormat::Rg32Float => 103,
+            TexelFormat::Rgba32Float => 109,
+            TexelFormat::R32Sint => 99,
+            TexelFormat::Rg32Sint => 102,
+            TexelFormat::Rgba32Sint => 108,
+            TexelFormat::R32Uint => 98,
+            TexelFormat::Rg32Uint => 101,
+            TexelFormat::Rgba32Uint => 107,
+        }
+    }
+
+    /// The GLSL type of a texel buffer in this format, such as `samplerBuffer` in OpenGL. For
+    /// Vulkan, replace `sampler` with `texture`.
+    pub fn glsl_sampler(self) -> &'static str {
+        match self {
+            TexelFormat::R32Float | TexelFormat::Rg32Float | TexelFormat::Rgba32Float => {
+                "samplerBuffer"
+            }
+            TexelFormat::R32Sint | TexelFormat::Rg32Sint | TexelFormat::Rgba32Sint => {
+                "isamplerBuffer"
+            }
+            TexelFormat::R32Uint | TexelFormat::Rg32Uint | TexelFormat::Rgba32Uint => {
+                "usamplerBuffer"
+            }
+        }
+    }
+
+    /// The format [pack_texels] picks for `T`: as many channels as `T` has scalars, up to four
+    /// with three rounded up, and the scalar type of `T`, or unsigned integers if it mixes types
+    /// or holds booleans. 8-bit and 16-bit scalars count as their 32-bit counterparts.
+    pub fn for_type<T: Std140Layout>() -> Self {
+        let components = T::layout().components();
+        let scalar = components.first().map_or(ScalarKind::Uint, |component| component.scalar);
+        let scalar = scalar.widened();
+        let scalar = if components.iter().all(|component| component.scalar.widened() == scalar) {
+            scalar
+        } else {
+            ScalarKind::Uint
+        };
+        match (scalar, components.len()) {
+            (ScalarKind::Float, 1) => TexelFormat::R32Float,
+            (ScalarKind::Float, 2) => TexelFormat::Rg32Float,
+            (ScalarKind::Float, _) => TexelFormat::Rgba32Float,
+            (ScalarKind::Int, 1) => TexelFormat::R32Sint,
+            (ScalarKind::Int, 2) => TexelFormat::Rg32Sint,
+            (ScalarKind::Int, _) => TexelFormat::Rgba32Sint,
+            (_, 1) => TexelFormat::R32Uint,
+            (_, 2) => TexelFormat::Rg32Uint,
+            (_, _) => TexelFormat::Rgba32Uint,
+        }
+    }
+}
+
+/// An array packed by [pack_texels] for a texel buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TexelBuffer {
+    /// The format to create the buffer view or buffer texture with.
+    pub format: TexelFormat,
+    /// The number of texels each element takes, so element `i` starts at texel
+    /// `i * texels_per_element`.
+    pub texels_per_element: usize,
+    /// The packed texels.
+    pub bytes: Vec<u8>,
+}
+
+/// Pack `values` in the host byte order as a texel buffer in the format
+/// [TexelFormat::for_type] picks, as an alternative to a std140 array when they do not fit in a
+/// uniform block.
+///
+/// Unlike in a std140 array, the scalars of each element follow each other without padding, and
+/// each element is only padded to a whole number of texels.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{pack_texels, AsStd140, TexelFormat};
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     radius: f32,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// let lights: Vec<_> = (0..1000)
+///     .map(|i| Light { position: [i as f32, 0.0, 0.0].into(), radius: 1.0, color: [1.0; 3].into() })
+///     .collect();
+///
+/// // In the shader, light i is texelFetch(lights, 2 * i) and texelFetch(lights, 2 * i + 1).
+/// let texels = pack_texels(&lights);
+/// assert_eq!(texels.format, TexelFormat::Rgba32Float);
+/// assert_eq!(texels.texels_per_element, 2);
+/// assert_eq!(texels.bytes.len(), 1000 * 32);
+/// assert_eq!(texels.bytes[12..16], 1.0f32.to_ne_bytes());
+/// ```
+pub fn pack_texels<T: Std140Bytes + Std140Layout>(values: &[T]) -> TexelBuffer {
+    pack_texels_with(values, TexelFormat::for_type::<T>(), Endian::NATIVE)
+}
+
+/// Pack `values` like [pack_texels], in the given format and byte order.
+///
+/// Scalars whose type differs from the channel type of `format` are stored as their bits, to be
+/// read back with `floatBitsToInt`, `intBitsToFloat` and the like, and booleans as 0 or 1.
+/// 8-bit and 16-bit scalars are first widened to the 32-bit scalar of the same kind, since every
+/// channel is 32 bits wide.
+pub fn pack_texels_with<T: Std140Bytes + Std140Layout>(
+    values: &[T],
+    format: TexelFormat,
+    endian: Endian,
+) -> TexelBuffer {
+    let components = T::layout().components();
+    let texels_per_element = components.len().div_ceil(format.channels()).max(1);
+    let stride = texels_per_element * format.texel_size();
+
+    let mut std140 = vec![0; T::SIZE];
+    let mut bytes = vec![0; stride * values.len()];
+    for (value, element) in values.iter().zip(bytes.chunks_exact_mut(stride)) {
+        value.write_std140(&mut std140, endian);
+        for (component, to) in components.iter().zip(element.chunks_exact_mut(4)) {
+            let from = &std140[component.offset..];
+            if component.scalar.size() == 4 {
+                to.copy_from_slice(&from[..4]);
+            } else {
+                let value = Std140Value::read(component.scalar, from, endian);
+                value.write(component.scalar.widened(), to, endian);
+            }
+        }
+    }
+    TexelBuffer { format, texels_per_element, bytes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_texels, pack_texels_with, TexelFormat};
+    use crate::Endian;
+
+    #[test]
+    fn formats() {
+        assert_eq!(TexelFormat::for_type::<mint::Vector3<u32>>(), TexelFormat::Rgba32Uint);
+        assert_eq!(TexelFormat::for_type::<mint::Vector2<bool>>(), TexelFormat::Rg32Uint);
+        assert_eq!(TexelFormat::for_type::<[i32; 5]>(), TexelFormat::Rgba32Sint);
+
+        let texels = pack_texels(&[mint::Vector2 { x: 1.0f32, y: 2.0 }; 3]);
+        assert_eq!((texels.format, texels.texels_per_element), (TexelFormat::Rg32Float, 1));
+        assert_eq!(texels.bytes.len(), 24);
+
+        let matrices = [mint::ColumnMatrix3 {
+            x: [1.0f32, 2.0, 3.0].into(),
+            y: [4.0, 5.0, 6.0].into(),
+            z: [7.0, 8.0, 9.0].into(),
+        }];
+        let texels = pack_texels_with(&matrices, TexelFormat::Rgba32Uint, Endian::Big);
+        assert_eq!(texels.texels_per_element, 3);
+        assert_eq!(texels.bytes[12..16], 4.0f32.to_be_bytes());
+        assert_eq!(texels.bytes[32..36], 9.0f32.to_be_bytes());
+        assert_eq!(texels.bytes[36..], [0; 12]);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Endian, Std140Bytes};
+use alloc::{vec, vec::Vec};
+use core::ops::Deref;
+
+/// A value that remembers the std140 bytes it had when last flushed, so only the parts that
+/// changed since then need to be uploaded again.
+///
+/// Changes are detected per 4-byte std140 component, and neighbouring changed components are
+/// merged into one range.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Tracked;
+///
+/// let mut color = Tracked::new(mint::Vector4 { x: 1.0f32, y: 1.0, z: 1.0, w: 1.0 });
+/// assert_eq!(color.flush().len(), 1); // The first flush uploads everything.
+///
+/// color.update(|color| color.z = 0.5);
+/// let ranges = color.flush();
+/// assert_eq!(ranges, vec![(8, &0.5f32.to_ne_bytes()[..])]);
+/// assert!(color.flush().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tracked<T> {
+    value: T,
+    current: Vec<u8>,
+    flushed: Option<Vec<u8>>,
+    endian: Endian,
+}
+
+impl<T: Std140Bytes> Tracked<T> {
+    /// Track `value`, packing in the host byte order.
+    pub fn new(value: T) -> Self {
+        Self::with_endian(value, Endian::NATIVE)
+    }
+
+    /// Track `value`, packing in the given byte order.
+    pub fn with_endian(value: T, endian: Endian) -> Self {
+        Tracked { value, current: vec![0; T::SIZE], flushed: None, endian }
+    }
+
+    /// Replace the tracked value.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+    }
+
+    /// Modify the tracked value in place.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.value)
+    }
+
+    /// Forget what was flushed so the next [Tracked::flush] reports the whole block.
+    pub fn invalidate(&mut self) {
+        self.flushed = None;
+    }
+
+    /// Return the `(offset, bytes)` ranges that changed since the last flush and mark them as
+    /// flushed.
+    pub fn flush(&mut self) -> Vec<(usize, &[u8])> {
+        self.value.write_std140(&mut self.current, self.endian);
+
+        let mut ranges = Vec::new();
+        match &mut self.flushed {
+            None => {
+                ranges.push((0, T::SIZE));
+                self.flushed = Some(self.current.clone());
+            }
+            Some(flushed) => {
+                let mut start = None;
+                for offset in (0..T::SIZE).step_by(4) {
+                    // The last word is cut short for 8-bit and 16-bit scalars and their vectors.
+                    let word = offset..T::SIZE.min(offset + 4);
+                    let changed = flushed[word.clone()] != self.current[word];
+                    match (changed, start) {
+                        (true, None) => start = Some(offset),
+                        (false, Some(range_start)) => {
+                            ranges.push((range_start, offset));
+                            start = None;
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(range_start) = start {
+                    ranges.push((range_start, T::SIZE));
+                }
+                flushed.copy_from_slice(&self.current);
+            }
+        }
+
+        let current = &self.current;
+        ranges.into_iter().map(|(start, end)| (start, &current[start..end])).collect()
+    }
+
+    /// Stop tracking and return the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tracked;
+    use crate::{Endian, Std140Bytes};
+
+    #[test]
+    fn ranges() {
+        let matrix = mint::ColumnMatrix4 {
+            x: mint::Vector4 { x: 1.0f32, y: 0.0, z: 0.0, w: 0.0 },
+            y: mint::Vector4 { x: 0.0f32, y: 1.0, z: 0.0, w: 0.0 },
+            z: mint::Vector4 { x: 0.0f32, y: 0.0, z: 1.0, w: 0.0 },
+            w: mint::Vector4 { x: 0.0f32, y: 0.0, z: 0.0, w: 1.0 },
+        };
+        let mut tracked = Tracked::with_endian(matrix, Endian::Little);
+        let expected = matrix.as_std140_le_bytes();
+        assert_eq!(tracked.flush(), vec![(0, &expected[..])]);
+
+        tracked.update(|matrix| {
+            matrix.w.x = 2.0;
+            matrix.w.y = 3.0;
+            matrix.x.x = 4.0;
+        });
+        let ranges = tracked.flush();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0], (0, &4.0f32.to_le_bytes()[..]));
+        assert_eq!(ranges[1].0, 48);
+        assert_eq!(ranges[1].1.len(), 8);
+
+        tracked.invalidate();
+        assert_eq!(tracked.flush()[0].1.len(), 64);
+    }
+
+    #[test]
+    fn short() {
+        let mut tracked = Tracked::new(5u8);
+        assert_eq!(tracked.flush(), vec![(0, &[5][..])]);
+        tracked.set(6);
+        assert_eq!(tracked.flush(), vec![(0, &[6][..])]);
+        assert!(tracked.flush().is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+/// The normal matrix of a model matrix: the inverse transpose of its upper-left 3x3 part, which
+/// transforms normals so they stay perpendicular to surfaces under non-uniform scaling.
+///
+/// The result packs as a std140 `mat3`, with each column padded to 16 bytes. For a singular model
+/// matrix, this returns the cofactor matrix instead, which still maps normals to the right
+/// directions for shaders that normalize them.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::normal_matrix;
+///
+/// let model: mint::ColumnMatrix4<f32> = [
+///     [2.0, 0.0, 0.0, 0.0],
+///     [0.0, 4.0, 0.0, 0.0],
+///     [0.0, 0.0, 1.0, 0.0],
+///     [5.0, 6.0, 7.0, 1.0],
+/// ]
+/// .into();
+/// let normal: [[f32; 3]; 3] = normal_matrix(&model).into();
+/// assert_eq!(normal, [[0.5, 0.0, 0.0], [0.0, 0.25, 0.0], [0.0, 0.0, 1.0]]);
+/// ```
+pub fn normal_matrix(model: &mint::ColumnMatrix4<f32>) -> mint::ColumnMatrix3<f32> {
+    let [a, b, c] = [model.x, model.y, model.z].map(|column| [column.x, column.y, column.z]);
+    // The columns of the cofactor matrix are the cross products of pairs of columns.
+    let cofactors = [cross(b, c), cross(c, a), cross(a, b)];
+    let determinant = dot(a, cofactors[0]);
+    let scale = if determinant == 0.0 { 1.0 } else { 1.0 / determinant };
+    let [x, y, z] = cofactors.map(|column| column.map(|value| value * scale).into());
+    mint::ColumnMatrix3 { x, y, z }
+}
+
+/// Compose a 2D transform that scales, then rotates counterclockwise by `rotation` radians, then
+/// translates, as the 3x3 matrix of homogeneous 2D coordinates.
+///
+/// The result packs as a std140 `mat3`, with each column padded to 16 bytes, which is also the
+/// layout of a `mat3x4` whose last row is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{transform_2d, Endian, Std140Bytes};
+///
+/// let sprite = transform_2d([10.0, 20.0].into(), 0.0, [2.0, 3.0].into());
+/// let columns: [[f32; 3]; 3] = sprite.into();
+/// assert_eq!(columns, [[2.0, 0.0, 0.0], [0.0, 3.0, 0.0], [10.0, 20.0, 1.0]]);
+/// let mut bytes = [0; 48];
+/// sprite.write_std140(&mut bytes, Endian::Little);
+/// assert_eq!(bytes[32..36], 10.0f32.to_le_bytes());
+/// ```
+pub fn transform_2d(
+    translation: mint::Vector2<f32>,
+    rotation: f32,
+    scale: mint::Vector2<f32>,
+) -> mint::ColumnMatrix3<f32> {
+    let (sin, cos) = crate::math::sin_cos(rotation);
+    mint::ColumnMatrix3 {
+        x: [cos * scale.x, sin * scale.x, 0.0].into(),
+        y: [-sin * scale.y, cos * scale.y, 0.0].into(),
+        z: [translation.x, translation.y, 1.0].into(),
+    }
+}
+
+/// Compose a 3D transform that scales, then rotates by the unit quaternion `rotation`, then
+/// translates, as a 4x4 model matrix that packs as a std140 `mat4`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::transform_3d;
+///
+/// let half = core::f32::consts::FRAC_1_SQRT_2;
+/// // A quarter turn about z.
+/// let rotation = mint::Quaternion { v: [0.0, 0.0, half].into(), s: half };
+/// let model = transform_3d([1.0, 2.0, 3.0].into(), rotation, [2.0, 2.0, 2.0].into());
+/// assert!((model.x.y - 2.0).abs() < 1e-6);
+/// assert_eq!(model.w, [1.0, 2.0, 3.0, 1.0].into());
+/// ```
+pub fn transform_3d(
+    translation: mint::Vector3<f32>,
+    rotation: mint::Quaternion<f32>,
+    scale: mint::Vector3<f32>,
+) -> mint::ColumnMatrix4<f32> {
+    let (x, y, z, w) = (rotation.v.x, rotation.v.y, rotation.v.z, rotation.s);
+    let column = |axis: [f32; 3], scale: f32| -> mint::Vector4<f32> {
+        [axis[0] * scale, axis[1] * scale, axis[2] * scale, 0.0].into()
+    };
+    mint::ColumnMatrix4 {
+        x: column(
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y + w * z), 2.0 * (x * z - w * y)],
+            scale.x,
+        ),
+        y: column(
+            [2.0 * (x * y - w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z + w * x)],
+            scale.y,
+        ),
+        z: column(
+            [2.0 * (x * z + w * y), 2.0 * (y * z - w * x), 1.0 - 2.0 * (x * x + y * y)],
+            scale.z,
+        ),
+        w: [translation.x, translation.y, translation.z, 1.0].into(),
+    }
+}
+
+/// The product `a * b` of two column-major matrices.
+#[cfg(any(test, feature = "presets"))]
+pub(crate) fn multiply(
+    a: &mint::ColumnMatrix4<f32>,
+    b: &mint::ColumnMatrix4<f32>,
+) -> mint::ColumnMatrix4<f32> {
+    let a: [[f32; 4]; 4] = (*a).into();
+    let b: [[f32; 4]; 4] = (*b).into();
+    let product: [[f32; 4]; 4] = core::array::from_fn(|column| {
+        core::array::from_fn(|row| (0..4).map(|i| a[i][row] * b[column][i]).sum())
+    });
+    product.into()
+}
+
+/// The inverse of a matrix, or `None` if it is singular.
+#[cfg(any(test, feature = "presets"))]
+pub(crate) fn inverse(m: &mint::ColumnMatrix4<f32>) -> Option<mint::ColumnMatrix4<f32>> {
+    // Laplace expansion over 2x2 minors. Written for row-major `a`, which is the transpose of the
+    // column-major input, and the inverse of the transpose is the transpose of the inverse.
+    let a: [[f32; 4]; 4] = (*m).into();
+    let s = [
+        a[0][0] * a[1][1] - a[1][0] * a[0][1],
+        a[0][0] * a[1][2] - a[1][0] * a[0][2],
+        a[0][0] * a[1][3] - a[1][0] * a[0][3],
+        a[0][1] * a[1][2] - a[1][1] * a[0][2],
+        a[0][1] * a[1][3] - a[1][1] * a[0][3],
+        a[0][2] * a[1][3] - a[1][2] * a[0][3],
+    ];
+    let c = [
+        a[2][0] * a[3][1] - a[3][0] * a[2][1],
+        a[2][0] * a[3][2] - a[3][0] * a[2][2],
+        a[2][0] * a[3][3] - a[3][0] * a[2][3],
+        a[2][1] * a[3][2] - a[3][1] * a[2][2],
+        a[2][1] * a[3][3] - a[3][1] * a[2][3],
+        a[2][2] * a[3][3] - a[3][2] * a[2][3],
+    ];
+    let determinant =
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+    if determinant == 0.0 {
+        return None;
+    }
+
+    let inverse = [
+        [
+            a[1][1] * c[5] - a[1][2] * c[4] + a[1][3] * c[3],
+            -a[0][1] * c[5] + a[0][2] * c[4] - a[0][3] * c[3],
+            a[3][1] * s[5] - a[3][2] * s[4] + a[3][3] * s[3],
+            -a[2][1] * s[5] + a[2][2] * s[4] - a[2][3] * s[3],
+        ],
+        [
+            -a[1][0] * c[5] + a[1][2] * c[2] - a[1][3] * c[1],
+            a[0][0] * c[5] - a[0][2] * c[2] + a[0][3] * c[1],
+            -a[3][0] * s[5] + a[3][2] * s[2] - a[3][3] * s[1],
+            a[2][0] * s[5] - a[2][2] * s[2] + a[2][3] * s[1],
+        ],
+        [
+            a[1][0] * c[4] - a[1][1] * c[2] + a[1][3] * c[0],
+            -a[0][0] * c[4] + a[0][1] * c[2] - a[0][3] * c[0],
+            a[3][0] * s[4] - a[3][1] * s[2] + a[3][3] * s[0],
+            -a[2][0] * s[4] + a[2][1] * s[2] - a[2][3] * s[0],
+        ],
+        [
+            -a[1][0] * c[3] + a[1][1] * c[1] - a[1][2] * c[0],
+            a[0][0] * c[3] - a[0][1] * c[1] + a[0][2] * c[0],
+            -a[3][0] * s[3] + a[3][1] * s[1] - a[3][2] * s[0],
+            a[2][0] * s[3] - a[2][1] * s[1] + a[2][2] * s[0],
+        ],
+    ];
+    Some(inverse.map(|row| row.map(|value| value / determinant)).into())
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normal_matrix;
+
+    #[test]
+    fn inverse_transpose() {
+        // A shear, whose normal matrix is not the matrix itself.
+        let model: mint::ColumnMatrix4<f32> =
+            [[1.0, 0.0, 0.0, 0.0], [1.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0; 4]].into();
+        let columns: [[f32; 3]; 3] = normal_matrix(&model).into();
+        assert_eq!(columns, [[1.0, -1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+        let flat: mint::ColumnMatrix4<f32> =
+            [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0; 4], [0.0; 4]].into();
+        let columns: [[f32; 3]; 3] = normal_matrix(&flat).into();
+        assert_eq!(columns, [[0.0; 3], [0.0; 3], [0.0, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn compose_3d() {
+        // A third of a turn about (1, 1, 1), which cycles the axes.
+        let rotation = mint::Quaternion { v: [0.5, 0.5, 0.5].into(), s: 0.5 };
+        let model = super::transform_3d([0.0, 0.0, 5.0].into(), rotation, [1.0, 2.0, 3.0].into());
+        let columns: [[f32; 4]; 4] = model.into();
+        assert_eq!(
+            columns,
+            [
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 2.0, 0.0],
+                [3.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 5.0, 1.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn inverse() {
+        let rotation = mint::Quaternion { v: [0.5, 0.5, 0.5].into(), s: 0.5 };
+        let model = super::transform_3d([1.0, -2.0, 5.0].into(), rotation, [1.0, 2.0, 4.0].into());
+        let inverse = super::inverse(&model).unwrap();
+        let identity: [[f32; 4]; 4] = super::multiply(&inverse, &model).into();
+        for (i, column) in identity.iter().enumerate() {
+            for (j, value) in column.iter().enumerate() {
+                assert!((value - if i == j { 1.0 } else { 0.0 }).abs() < 1e-6);
+            }
+        }
+        assert_eq!(super::inverse(&[[1.0; 4]; 4].into()), None);
+    }
+
+    #[test]
+    fn compose_2d() {
+        let quarter_turn = core::f32::consts::FRAC_PI_2;
+        let transform = super::transform_2d([1.0, 2.0].into(), quarter_turn, [2.0, 1.0].into());
+        // The point (1, 1) is scaled to (2, 1), rotated to (-1, 2) and translated to (0, 4).
+        let point = [1.0, 1.0, 1.0];
+        let columns = [transform.x, transform.y, transform.z];
+        let moved: [f32; 3] = core::array::from_fn(|row| {
+            columns.iter().zip(point).map(|(column, p)| <[f32; 3]>::from(*column)[row] * p).sum()
+        });
+        assert!(moved.iter().zip([0.0, 4.0, 1.0]).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Endian, Std140Bytes};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// A std140 block built field by field at runtime, usually through the [uniform!](crate::uniform)
+/// macro.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UniformBlock {
+    /// The packed block, padded to a multiple of 16 bytes.
+    pub bytes: Vec<u8>,
+    /// The offset of each field in bytes.
+    pub offsets: BTreeMap<&'static str, usize>,
+}
+
+impl UniformBlock {
+    /// Create an empty block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `value` as the field `name`, in the given byte order, with the padding that std140
+    /// requires before it. Returns the offset of the field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block already has a field called `name`.
+    pub fn push<T: Std140Bytes>(&mut self, name: &'static str, value: &T, endian: Endian) -> usize {
+        let offset = self.bytes.len().next_multiple_of(T::ALIGN);
+        self.bytes.resize(offset + T::SIZE, 0);
+        value.write_std140(&mut self.bytes[offset..], endian);
+        assert!(self.offsets.insert(name, offset).is_none(), "duplicate uniform field {}", name);
+        offset
+    }
+
+    /// The offset of the field `name` in bytes.
+    pub fn offset_of(&self, name: &str) -> Option<usize> {
+        self.offsets.get(name).copied()
+    }
+
+    /// Consume the block, padding it to a multiple of 16 bytes.
+    pub fn finish(mut self) -> Self {
+        self.bytes.resize(self.bytes.len().next_multiple_of(16), 0);
+        self
+    }
+}
+
+/// Pack an ad hoc std140 block without defining a struct for it, returning a [UniformBlock] with
+/// the bytes in the host byte order and the offset of each field.
+///
+/// Each field is written as `name: type = value`, where `type` is a GLSL type name such as `float`,
+/// `uvec2` or `mat4`, optionally followed by an array length as in `vec4[4]`. The value must have
+/// the matching mint type, such as `mint::ColumnMatrix4<f32>` for `mat4`. Fields are laid out in
+/// order, as in a GLSL block declaring the same members.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::uniform;
+///
+/// let color = mint::Vector3 { x: 1.0f32, y: 0.5, z: 0.0 };
+/// let block = uniform! {
+///     color: vec3 = color,
+///     time: float = 2.5,
+///     weights: float[2] = [0.25, 0.75],
+/// };
+/// assert_eq!(block.offset_of("time"), Some(12));
+/// assert_eq!(block.offset_of("weights"), Some(16));
+/// assert_eq!(block.bytes.len(), 48);
+/// assert_eq!(&block.bytes[12..16], &2.5f32.to_ne_bytes());
+/// ```
+#[macro_export]
+macro_rules! uniform {
+    ($($name:ident : $glsl_type:ident $([$len:expr])? = $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut block = $crate::UniformBlock::new();
+        $(
+            let value: $crate::uniform!(@type $glsl_type $([$len])?) = $value;
+            block.push(stringify!($name), &value, $crate::Endian::NATIVE);
+        )*
+        block.finish()
+    }};
+    (@type $glsl_type:ident) => {
+        $crate::__private::glsl_types::$glsl_type
+    };
+    (@type $glsl_type:ident [$len:expr]) => {
+        [$crate::__private::glsl_types::$glsl_type; $len]
+    };
+}
+
+/// The mint types that [uniform!](crate::uniform) accepts for each GLSL type name.
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+pub mod glsl_types {
+    pub type float = f32;
+    pub type int = i32;
+    pub type uint = u32;
+    pub type bool = core::primitive::bool;
+    pub type vec2 = mint::Vector2<f32>;
+    pub type vec3 = mint::Vector3<f32>;
+    pub type vec4 = mint::Vector4<f32>;
+    pub type ivec2 = mint::Vector2<i32>;
+    pub type ivec3 = mint::Vector3<i32>;
+    pub type ivec4 = mint::Vector4<i32>;
+    pub type uvec2 = mint::Vector2<u32>;
+    pub type uvec3 = mint::Vector3<u32>;
+    pub type uvec4 = mint::Vector4<u32>;
+    pub type bvec2 = mint::Vector2<bool>;
+    pub type bvec3 = mint::Vector3<bool>;
+    pub type bvec4 = mint::Vector4<bool>;
+    pub type mat2 = mint::ColumnMatrix2<f32>;
+    pub type mat3 = mint::ColumnMatrix3<f32>;
+    pub type mat4 = mint::ColumnMatrix4<f32>;
+    pub type mat2x2 = mint::ColumnMatrix2<f32>;
+    pub type mat2x3 = mint::ColumnMatrix3x2<f32>;
+    pub type mat2x4 = mint::ColumnMatrix4x2<f32>;
+    pub type mat3x2 = mint::ColumnMatrix2x3<f32>;
+    pub type mat3x3 = mint::ColumnMatrix3<f32>;
+    pub type mat3x4 = mint::ColumnMatrix4x3<f32>;
+    pub type mat4x2 = mint::ColumnMatrix2x4<f32>;
+    pub type mat4x3 = mint::ColumnMatrix3x4<f32>;
+    pub type mat4x4 = mint::ColumnMatrix4<f32>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Endian, FromStd140, Std140Sized};
+
+    #[test]
+    fn layout() {
+        let mvp = mint::ColumnMatrix4::from([[1.0f32; 4]; 4]);
+        let block = uniform! {
+            enabled: bool = true,
+            mvp: mat4 = mvp,
+            offset: ivec2 = mint::Vector2 { x: -1, y: 1 },
+            scale: float = 2.0,
+            normal: mat3 = mint::ColumnMatrix3::from([[0.0; 3]; 3]),
+        };
+        assert_eq!(block.offset_of("mvp"), Some(16));
+        assert_eq!(block.offset_of("offset"), Some(80));
+        assert_eq!(block.offset_of("scale"), Some(88));
+        assert_eq!(block.offset_of("normal"), Some(96));
+        assert_eq!(block.bytes.len(), 96 + mint::ColumnMatrix3::<f32>::SIZE);
+        assert_eq!(
+            mint::ColumnMatrix4::<f32>::from_std140_bytes(&block.bytes[16..], Endian::NATIVE),
+            mvp
+        );
+        assert!(uniform! {}.bytes.is_empty());
+    }
+}
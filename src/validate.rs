@@ -0,0 +1,199 @@
+use crate::Std140Error;
+
+/// A struct whose fields can be checked against their `#[std140(range = ...)]` and
+/// `#[std140(assert_finite)]` attributes, implemented by `#[derive(AsStd140)]`.
+///
+/// The derived implementation also validates fields holding nested structs or arrays of them,
+/// through [Std140Layout::validate_fields](crate::Std140Layout::validate_fields).
+///
+/// In builds with debug assertions, derived [Std140Bytes](crate::Std140Bytes) implementations
+/// panic when packing a value that fails validation.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use mint_std140::{AsStd140, Std140Validate};
+///
+/// #[derive(AsStd140)]
+/// struct Material {
+///     #[std140(range = 0.0..=1.0)]
+///     roughness: f32,
+///     #[std140(assert_finite)]
+///     emission: mint::Vector3<f32>,
+/// }
+///
+/// let material = Material { roughness: 255.0, emission: [0.0, 0.0, 0.0].into() };
+/// let error = material.validate().unwrap_err();
+/// assert_eq!(error.to_string(), "roughness holds 255, expected 0.0..=1.0");
+/// # }
+/// ```
+pub trait Std140Validate {
+    /// Check every annotated field and return the first violation.
+    fn validate(&self) -> Result<(), Std140Error>;
+}
+
+/// Types made of numeric scalars, which `#[std140(range = ...)]` and `#[std140(assert_finite)]`
+/// check one by one.
+pub trait Std140Scalars {
+    /// The first scalar, converted to `f64`, for which `predicate` returns `true`.
+    fn find_scalar(&self, predicate: &mut dyn FnMut(f64) -> bool) -> Option<f64>;
+}
+
+macro_rules! impl_std140_scalars_for_scalar {
+    ($($rust_type:ty),+) => {
+        $(
+            impl Std140Scalars for $rust_type {
+                fn find_scalar(&self, predicate: &mut dyn FnMut(f64) -> bool) -> Option<f64> {
+                    let value = f64::from(*self);
+                    if predicate(value) {
+                        Some(value)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_std140_scalars_for_scalar!(f32, i32, u32);
+
+macro_rules! impl_std140_scalars_for_fields {
+    ($mint_type:ident, [$($field:ident),+]) => {
+        impl<T: Std140Scalars> Std140Scalars for mint::$mint_type<T> {
+            fn find_scalar(&self, predicate: &mut dyn FnMut(f64) -> bool) -> Option<f64> {
+                None$(.or_else(|| self.$field.find_scalar(predicate)))+
+            }
+        }
+    };
+}
+
+impl_std140_scalars_for_fields!(Vector2, [x, y]);
+impl_std140_scalars_for_fields!(Vector3, [x, y, z]);
+impl_std140_scalars_for_fields!(Vector4, [x, y, z, w]);
+impl_std140_scalars_for_fields!(Quaternion, [v, s]);
+impl_std140_scalars_for_fields!(ColumnMatrix2, [x, y]);
+impl_std140_scalars_for_fields!(ColumnMatrix3x2, [x, y]);
+impl_std140_scalars_for_fields!(ColumnMatrix4x2, [x, y]);
+impl_std140_scalars_for_fields!(ColumnMatrix2x3, [x, y, z]);
+impl_std140_scalars_for_fields!(ColumnMatrix3, [x, y, z]);
+impl_std140_scalars_for_fields!(ColumnMatrix4x3, [x, y, z]);
+impl_std140_scalars_for_fields!(ColumnMatrix2x4, [x, y, z, w]);
+impl_std140_scalars_for_fields!(ColumnMatrix3x4, [x, y, z, w]);
+impl_std140_scalars_for_fields!(ColumnMatrix4, [x, y, z, w]);
+
+impl<T: Std140Scalars, const N: usize> Std140Scalars for [T; N] {
+    fn find_scalar(&self, predicate: &mut dyn FnMut(f64) -> bool) -> Option<f64> {
+        self.iter().find_map(|element| element.find_scalar(predicate))
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::Std140Validate;
+    use crate::{AsStd140, Std140Error};
+
+    #[derive(AsStd140, Clone, Copy)]
+    struct Light {
+        #[std140(assert_finite, range = 0.0..)]
+        intensities: [f32; 2],
+        #[std140(range = -1.0..=1.0)]
+        direction: mint::Vector3<f32>,
+        count: u32,
+    }
+
+    #[test]
+    fn validate() {
+        let mut light =
+            Light { intensities: [1.0, 2.0], direction: [0.0, -1.0, 0.0].into(), count: 2 };
+        assert_eq!(light.validate(), Ok(()));
+
+        light.direction.z = 1.5;
+        assert_eq!(
+            light.validate(),
+            Err(Std140Error::OutOfRange {
+                field: "direction".into(),
+                value: 1.5,
+                expected: "-1.0..=1.0"
+            })
+        );
+
+        light.intensities[1] = f32::INFINITY;
+        assert!(matches!(
+            light.validate(),
+            Err(Std140Error::OutOfRange { field, expected: "a finite value", .. })
+                if field == "intensities"
+        ));
+    }
+
+    #[test]
+    fn nested() {
+        use crate::{
+            Endian, FromStd140, Layout, ScalarKind, Std140Bytes, Std140Layout, Std140Sized,
+        };
+
+        // A hand-written leaf type, which needs no `Std140Validate`.
+        struct Meters(f32);
+
+        impl Std140Sized for Meters {
+            const SIZE: usize = 4;
+            const ALIGN: usize = 4;
+        }
+
+        impl Std140Bytes for Meters {
+            fn write_std140(&self, bytes: &mut [u8], endian: Endian) {
+                self.0.write_std140(bytes, endian);
+            }
+        }
+
+        impl FromStd140 for Meters {
+            fn from_std140_bytes(bytes: &[u8], endian: Endian) -> Self {
+                Meters(f32::from_std140_bytes(bytes, endian))
+            }
+        }
+
+        impl Std140Layout for Meters {
+            fn layout() -> Layout {
+                Layout::scalar(ScalarKind::Float)
+            }
+        }
+
+        #[derive(AsStd140)]
+        struct Lights {
+            main: Light,
+            fill: [Light; 2],
+            range: Meters,
+        }
+
+        let light = Light { intensities: [1.0, 2.0], direction: [0.0, -1.0, 0.0].into(), count: 2 };
+        let mut lights = Lights { main: light, fill: [light; 2], range: Meters(-1.0) };
+        assert_eq!(lights.validate(), Ok(()));
+
+        lights.fill[1].direction.x = -2.0;
+        assert_eq!(
+            lights.validate(),
+            Err(Std140Error::OutOfRange {
+                field: "fill[1].direction".into(),
+                value: -2.0,
+                expected: "-1.0..=1.0"
+            })
+        );
+        lights.main.direction.y = 2.0;
+        assert_eq!(
+            lights.validate().unwrap_err().to_string(),
+            "main.direction holds 2, expected -1.0..=1.0"
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "intensities holds -1, expected 0.0..")]
+    fn debug_panic() {
+        use crate::Std140Bytes;
+
+        let light =
+            Light { intensities: [-1.0, 2.0], direction: [0.0, -1.0, 0.0].into(), count: 2 };
+        light.as_std140_bytes();
+    }
+}
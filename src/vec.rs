@@ -0,0 +1,163 @@
+use crate::bytes::array_stride;
+use crate::{Endian, Std140Bytes};
+use alloc::vec::Vec;
+use core::ops::Index;
+
+/// A growable list of values that keeps a std140 array of them packed alongside.
+///
+/// Elements are stored at the std140 array stride, so [Std140Vec::as_bytes] can be uploaded as
+/// the contents of a GLSL array without further conversion.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140Vec;
+///
+/// let mut lights = Std140Vec::new();
+/// lights.push(mint::Vector2 { x: 1.0f32, y: 2.0f32 });
+/// lights.push(mint::Vector2 { x: 3.0f32, y: 4.0f32 });
+/// lights.update(0, |light| light.y = 5.0);
+/// assert_eq!(lights[0].y, 5.0);
+/// assert_eq!(lights.as_bytes().len(), 32);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Std140Vec<T> {
+    values: Vec<T>,
+    bytes: Vec<u8>,
+    endian: Endian,
+}
+
+impl<T: Std140Bytes> Std140Vec<T> {
+    /// The distance in bytes between consecutive elements.
+    pub const STRIDE: usize = array_stride(T::SIZE);
+
+    /// Create an empty list that packs in the host byte order.
+    pub fn new() -> Self {
+        Self::with_endian(Endian::NATIVE)
+    }
+
+    /// Create an empty list that packs in the given byte order.
+    pub fn with_endian(endian: Endian) -> Self {
+        Std140Vec { values: Vec::new(), bytes: Vec::new(), endian }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the list has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Append an element.
+    pub fn push(&mut self, value: T) {
+        let offset = self.bytes.len();
+        self.bytes.resize(offset + Self::STRIDE, 0);
+        value.write_std140(&mut self.bytes[offset..], self.endian);
+        self.values.push(value);
+    }
+
+    /// Remove and return the last element.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.values.pop()?;
+        self.bytes.truncate(self.values.len() * Self::STRIDE);
+        Some(value)
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.bytes.clear();
+    }
+
+    /// Replace the element at `index` and repack it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.values[index] = value;
+        self.repack(index);
+    }
+
+    /// Modify the element at `index` in place and repack it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn update<R>(&mut self, index: usize, f: impl FnOnce(&mut T) -> R) -> R {
+        let result = f(&mut self.values[index]);
+        self.repack(index);
+        result
+    }
+
+    /// The elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// The packed std140 array.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn repack(&mut self, index: usize) {
+        let bytes = &mut self.bytes[index * Self::STRIDE..(index + 1) * Self::STRIDE];
+        bytes.fill(0);
+        self.values[index].write_std140(bytes, self.endian);
+    }
+}
+
+impl<T: Std140Bytes> Default for Std140Vec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for Std140Vec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<T: Std140Bytes> Extend<T> for Std140Vec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Std140Bytes> core::iter::FromIterator<T> for Std140Vec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140Vec;
+    use crate::Endian;
+
+    #[test]
+    fn stride() {
+        let mut vec = Std140Vec::with_endian(Endian::Little);
+        vec.extend((0..3u32).map(|i| mint::Vector3 { x: i, y: i, z: i }));
+        assert_eq!(Std140Vec::<mint::Vector3<u32>>::STRIDE, 16);
+        assert_eq!(vec.as_bytes().len(), 48);
+        assert_eq!(&vec.as_bytes()[32..36], &2u32.to_le_bytes());
+
+        vec.set(1, mint::Vector3 { x: 7, y: 8, z: 9 });
+        assert_eq!(&vec.as_bytes()[16..20], &7u32.to_le_bytes());
+        assert_eq!(&vec.as_bytes()[28..32], &[0; 4]);
+
+        assert_eq!(vec.pop().map(|v| v.x), Some(2));
+        assert_eq!(vec.as_bytes().len(), 32);
+    }
+}
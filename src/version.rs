@@ -0,0 +1,140 @@
+use crate::cache::fnv1a;
+use crate::{layout_snapshot, Std140Layout};
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// A struct with a `uint` member holding its [layout fingerprint](layout_fingerprint) truncated
+/// to 32 bits, derived for a `u32` field marked with `#[std140(layout_version)]`.
+///
+/// The derived packing always writes [Std140LayoutVersion::layout_version] to that member and
+/// ignores the value of the field, so a shader checking it with the code generated by
+/// [glsl_layout_version_check] detects when it was built against a different layout.
+pub trait Std140LayoutVersion: Std140Layout + Sized {
+    /// The name of the member holding the layout version.
+    const VERSION_FIELD: &'static str;
+
+    /// The layout fingerprint of this struct, truncated to 32 bits. The derived implementation
+    /// computes it once and caches it.
+    fn layout_version() -> u32 {
+        layout_fingerprint::<Self>() as u32
+    }
+}
+
+/// The layout version of one struct, computed on first use. Threads racing to compute it store
+/// the same value.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct LayoutVersionCache {
+    ready: AtomicBool,
+    version: AtomicU32,
+}
+
+impl LayoutVersionCache {
+    pub const fn new() -> Self {
+        LayoutVersionCache { ready: AtomicBool::new(false), version: AtomicU32::new(0) }
+    }
+
+    pub fn get<T: Std140Layout>(&self) -> u32 {
+        if self.ready.load(Ordering::Acquire) {
+            return self.version.load(Ordering::Relaxed);
+        }
+        let version = layout_fingerprint::<T>() as u32;
+        self.version.store(version, Ordering::Relaxed);
+        self.ready.store(true, Ordering::Release);
+        version
+    }
+}
+
+/// A 64-bit FNV-1a hash of the [layout snapshot](layout_snapshot) of `T`, which changes with the
+/// name, type or offset of any member.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::layout_fingerprint;
+///
+/// assert_eq!(layout_fingerprint::<f32>(), layout_fingerprint::<f32>());
+/// assert_ne!(layout_fingerprint::<f32>(), layout_fingerprint::<u32>());
+/// ```
+pub fn layout_fingerprint<T: Std140Layout>() -> u64 {
+    fnv1a(layout_snapshot::<T>().as_bytes())
+}
+
+/// Generate GLSL declaring the layout version of `T` as a constant named
+/// `<BLOCK_NAME>_LAYOUT_VERSION` and a function `<block_name>_layout_version_matches()` comparing
+/// it with the member of the uniform block declared by [glsl_block](crate::glsl_block).
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::{glsl_layout_version_check, AsStd140, Std140LayoutVersion};
+///
+/// #[derive(AsStd140)]
+/// struct Globals {
+///     #[std140(layout_version)]
+///     layout_version: u32,
+///     time: f32,
+/// }
+///
+/// let glsl = glsl_layout_version_check::<Globals>("Globals");
+/// let version = format!("0x{:08x}u", Globals::layout_version());
+/// assert_eq!(
+///     glsl,
+///     format!(
+///         "const uint GLOBALS_LAYOUT_VERSION = {};\n\n\
+///          bool Globals_layout_version_matches() {{\n    \
+///              return layout_version == GLOBALS_LAYOUT_VERSION;\n\
+///          }}\n",
+///         version
+///     )
+/// );
+/// ```
+pub fn glsl_layout_version_check<T: Std140LayoutVersion>(block_name: &str) -> String {
+    let constant = format!("{}_LAYOUT_VERSION", block_name.to_uppercase());
+    let mut glsl = String::new();
+    writeln!(glsl, "const uint {} = 0x{:08x}u;\n", constant, T::layout_version()).unwrap();
+    writeln!(glsl, "bool {}_layout_version_matches() {{", block_name).unwrap();
+    writeln!(glsl, "    return {} == {};", T::VERSION_FIELD, constant).unwrap();
+    glsl.push_str("}\n");
+    glsl
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::{layout_fingerprint, Std140LayoutVersion};
+    use crate::{AsStd140, Endian, FromStd140, Std140Bytes};
+
+    #[derive(AsStd140)]
+    struct Stamped {
+        tint: mint::Vector3<f32>,
+        #[std140(layout_version)]
+        layout_version: u32,
+    }
+
+    #[derive(AsStd140)]
+    struct Renamed {
+        tint: mint::Vector3<f32>,
+        #[std140(layout_version)]
+        version: u32,
+    }
+
+    #[test]
+    fn stamp() {
+        let version = Stamped::layout_version();
+        assert_eq!(version, layout_fingerprint::<Stamped>() as u32);
+        assert_eq!(Stamped::layout_version(), version);
+
+        let mut bytes = [0; 16];
+        Stamped { tint: [1.0; 3].into(), layout_version: 7 }.write_std140(&mut bytes, Endian::Big);
+        assert_eq!(&bytes[12..], &version.to_be_bytes());
+        assert_eq!(Stamped::from_std140_bytes(&bytes, Endian::Big).layout_version, version);
+
+        // The fingerprint covers the name of the member.
+        Renamed { tint: [1.0; 3].into(), version: 7 }.write_std140(&mut bytes, Endian::Big);
+        let renamed = Renamed::from_std140_bytes(&bytes, Endian::Big).version;
+        assert_eq!(renamed, Renamed::layout_version());
+        assert_ne!(renamed, version);
+    }
+}
@@ -0,0 +1,242 @@
+//! Helpers for uploading std140 data and specialization constants through the raw Vulkan API,
+//! with the valid usage rules that are easy to forget checked up front.
+
+#[cfg(feature = "alloc")]
+use crate::bytes::Scalar;
+use crate::Std140Sized;
+#[cfg(feature = "alloc")]
+use crate::{Endian, Std140Bytes};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+/// The largest `dataSize` accepted by `vkCmdUpdateBuffer`.
+pub const MAX_UPDATE_BUFFER_SIZE: usize = 65536;
+
+/// A violation of a Vulkan valid usage rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VulkanError {
+    /// The data passed to `vkCmdUpdateBuffer` is empty.
+    EmptyUpdate,
+    /// The data passed to `vkCmdUpdateBuffer` is larger than [MAX_UPDATE_BUFFER_SIZE].
+    UpdateTooLarge(usize),
+    /// The size of the data passed to `vkCmdUpdateBuffer` is not a multiple of 4.
+    UpdateSizeNotMultipleOfFour(usize),
+    /// The destination offset of `vkCmdUpdateBuffer` is not a multiple of 4.
+    UpdateOffsetNotMultipleOfFour(u64),
+    /// A descriptor offset is not a multiple of `minUniformBufferOffsetAlignment`.
+    MisalignedDescriptorOffset {
+        /// The requested offset.
+        offset: u64,
+        /// The required alignment.
+        alignment: u64,
+    },
+}
+
+impl fmt::Display for VulkanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VulkanError::EmptyUpdate => write!(f, "vkCmdUpdateBuffer data is empty"),
+            VulkanError::UpdateTooLarge(size) => write!(
+                f,
+                "vkCmdUpdateBuffer data is {} bytes, more than the limit of {}",
+                size, MAX_UPDATE_BUFFER_SIZE
+            ),
+            VulkanError::UpdateSizeNotMultipleOfFour(size) => {
+                write!(f, "vkCmdUpdateBuffer data is {} bytes, not a multiple of 4", size)
+            }
+            VulkanError::UpdateOffsetNotMultipleOfFour(offset) => {
+                write!(f, "vkCmdUpdateBuffer offset {} is not a multiple of 4", offset)
+            }
+            VulkanError::MisalignedDescriptorOffset { offset, alignment } => write!(
+                f,
+                "descriptor offset {} is not a multiple of minUniformBufferOffsetAlignment {}",
+                offset, alignment
+            ),
+        }
+    }
+}
+
+impl Error for VulkanError {}
+
+/// Check that `data` can be written at `dst_offset` with `vkCmdUpdateBuffer`.
+pub fn check_update_buffer(dst_offset: u64, data: &[u8]) -> Result<(), VulkanError> {
+    if !dst_offset.is_multiple_of(4) {
+        Err(VulkanError::UpdateOffsetNotMultipleOfFour(dst_offset))
+    } else if data.is_empty() {
+        Err(VulkanError::EmptyUpdate)
+    } else if data.len() > MAX_UPDATE_BUFFER_SIZE {
+        Err(VulkanError::UpdateTooLarge(data.len()))
+    } else if !data.len().is_multiple_of(4) {
+        Err(VulkanError::UpdateSizeNotMultipleOfFour(data.len()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Pack `value` into bytes that can be written at `dst_offset` with `vkCmdUpdateBuffer`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::vulkan::update_buffer_data;
+///
+/// let data = update_buffer_data(0, &mint::Vector3 { x: 1.0f32, y: 2.0, z: 3.0 }).unwrap();
+/// assert_eq!(data.len(), 12);
+/// assert!(update_buffer_data(2, &1.0f32).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn update_buffer_data<T: Std140Bytes>(
+    dst_offset: u64,
+    value: &T,
+) -> Result<Vec<u8>, VulkanError> {
+    let data = value.as_std140_bytes();
+    check_update_buffer(dst_offset, &data)?;
+    Ok(data)
+}
+
+/// The `offset` and `range` of a `VkDescriptorBufferInfo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorRange {
+    /// The offset of the block in the buffer.
+    pub offset: u64,
+    /// The size of the block.
+    pub range: u64,
+}
+
+/// The descriptor range of a block of type `T` at `offset`, checked against the device's
+/// `minUniformBufferOffsetAlignment`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::vulkan::{descriptor_range, DescriptorRange};
+///
+/// let range = descriptor_range::<mint::ColumnMatrix4<f32>>(256, 256).unwrap();
+/// assert_eq!(range, DescriptorRange { offset: 256, range: 64 });
+/// assert!(descriptor_range::<mint::ColumnMatrix4<f32>>(64, 256).is_err());
+/// ```
+pub fn descriptor_range<T: Std140Sized>(
+    offset: u64,
+    min_uniform_buffer_offset_alignment: u64,
+) -> Result<DescriptorRange, VulkanError> {
+    let alignment = min_uniform_buffer_offset_alignment.max(1);
+    if !offset.is_multiple_of(alignment) {
+        return Err(VulkanError::MisalignedDescriptorOffset { offset, alignment });
+    }
+    Ok(DescriptorRange { offset, range: T::SIZE as u64 })
+}
+
+/// A `VkSpecializationMapEntry`, with the same memory layout so a slice of them can be passed as
+/// `pMapEntries` directly.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpecializationMapEntry {
+    /// The `constant_id` of the constant in the shader.
+    pub constant_id: u32,
+    /// The offset of the value in the data blob.
+    pub offset: u32,
+    /// The size of the value in bytes.
+    pub size: usize,
+}
+
+/// The data blob and map entries of a `VkSpecializationInfo`, packed from scalars with the same
+/// conversions as std140 blocks, in the host byte order the driver reads them in. Booleans become
+/// 4-byte `VkBool32`s.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::vulkan::{SpecializationConstants, SpecializationMapEntry};
+///
+/// let mut constants = SpecializationConstants::new();
+/// constants.push(0, true);
+/// constants.push(3, 64u32);
+/// constants.push(1, 0.5f32);
+///
+/// assert_eq!(constants.data().len(), 12);
+/// assert_eq!(constants.data()[4..8], 64u32.to_ne_bytes());
+/// assert_eq!(
+///     constants.entries()[2],
+///     SpecializationMapEntry { constant_id: 1, offset: 8, size: 4 }
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SpecializationConstants {
+    data: Vec<u8>,
+    entries: Vec<SpecializationMapEntry>,
+}
+
+#[cfg(feature = "alloc")]
+impl SpecializationConstants {
+    /// Create an empty set of constants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a scalar such as a `bool`, `i32`, `u32` or `f32` as the value of the constant
+    /// `constant_id` and return its offset in the data blob.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value for `constant_id` was already pushed, since Vulkan requires every map
+    /// entry to have a distinct `constantID`.
+    pub fn push<T: Scalar>(&mut self, constant_id: u32, value: T) -> usize {
+        assert!(
+            self.entries.iter().all(|entry| entry.constant_id != constant_id),
+            "specialization constant {} is already set",
+            constant_id
+        );
+        let offset = self.data.len();
+        self.data.resize(offset + 4, 0);
+        Endian::NATIVE.write_u32(&mut self.data[offset..], value.to_bits());
+        self.entries.push(SpecializationMapEntry { constant_id, offset: offset as u32, size: 4 });
+        offset
+    }
+
+    /// The data blob, to pass as `pData` with `dataSize` set to its length.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The map entries, to pass as `pMapEntries` with `mapEntryCount` set to their number.
+    pub fn entries(&self) -> &[SpecializationMapEntry] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use super::SpecializationConstants;
+    use super::{check_update_buffer, VulkanError, MAX_UPDATE_BUFFER_SIZE};
+
+    #[test]
+    fn update_buffer() {
+        assert_eq!(check_update_buffer(4, &[0; 8]), Ok(()));
+        assert_eq!(check_update_buffer(0, &[]), Err(VulkanError::EmptyUpdate));
+        assert_eq!(
+            check_update_buffer(0, &[0; 6]),
+            Err(VulkanError::UpdateSizeNotMultipleOfFour(6))
+        );
+        assert_eq!(
+            check_update_buffer(1, &[0; 4]),
+            Err(VulkanError::UpdateOffsetNotMultipleOfFour(1))
+        );
+        assert_eq!(
+            check_update_buffer(0, &vec![0; MAX_UPDATE_BUFFER_SIZE + 4]),
+            Err(VulkanError::UpdateTooLarge(MAX_UPDATE_BUFFER_SIZE + 4))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "specialization constant 2 is already set")]
+    fn duplicate_specialization_constant() {
+        let mut constants = SpecializationConstants::new();
+        constants.push(2, 1i32);
+        constants.push(2, false);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::bytes::debug_check_offset;
+use crate::{Endian, Std140Bytes, Std140Error};
+use core::any::type_name;
+
+/// Writes std140 values one after another into a byte slice, inserting the padding required by
+/// each value's std140 alignment.
+///
+/// # Examples
+///
+/// ```rust
+/// use mint_std140::Std140Writer;
+///
+/// let mut bytes = [0u8; 32];
+/// let mut writer = Std140Writer::new(&mut bytes);
+/// writer.write(&mint::Vector2 { x: 1.0f32, y: 2.0f32 });
+/// let offset = writer.write(&mint::Vector3 { x: 3.0f32, y: 4.0f32, z: 5.0f32 });
+/// assert_eq!(offset, 16);
+/// assert_eq!(writer.offset(), 28);
+/// ```
+#[derive(Debug)]
+pub struct Std140Writer<'a> {
+    bytes: &'a mut [u8],
+    offset: usize,
+    endian: Endian,
+}
+
+impl<'a> Std140Writer<'a> {
+    /// Create a writer that starts at the beginning of `bytes` and writes in the host byte order.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        Self::with_endian(bytes, Endian::NATIVE)
+    }
+
+    /// Create a writer that starts at the beginning of `bytes` and writes in the given byte order.
+    pub fn with_endian(bytes: &'a mut [u8], endian: Endian) -> Self {
+        Std140Writer { bytes, offset: 0, endian }
+    }
+
+    /// The offset at which the next value would be written, before alignment.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The byte order used by this writer.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Create a writer that starts `offset` bytes into `bytes`, such as at a dynamic uniform
+    /// buffer offset, and writes in the given byte order.
+    ///
+    /// Returns [Std140Error::Misaligned] if `offset` is not a multiple of 16, the alignment of a
+    /// std140 block, and [Std140Error::Overflow] if it is past the end of `bytes`.
+    pub fn with_offset(
+        bytes: &'a mut [u8],
+        offset: usize,
+        endian: Endian,
+    ) -> Result<Self, Std140Error> {
+        if !offset.is_multiple_of(16) {
+            return Err(Std140Error::Misaligned { field: "", offset, align: 16 });
+        }
+        if offset > bytes.len() {
+            return Err(Std140Error::Overflow {
+                field: "",
+                offset,
+                required: offset,
+                available: bytes.len(),
+            });
+        }
+        Ok(Std140Writer { bytes, offset, endian })
+    }
+
+    /// Zero bytes up to the next multiple of `align` and return the new offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two or the destination is too small.
+    pub fn align_to(&mut self, align: usize) -> usize {
+        self.try_align_to(align).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like [align_to](Self::align_to), but return [Std140Error::Overflow] instead of panicking
+    /// if the destination is too small.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn try_align_to(&mut self, align: usize) -> Result<usize, Std140Error> {
+        self.pad_to("padding", align)
+    }
+
+    /// Write `value` at its next aligned offset and return that offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the destination is too small.
+    pub fn write<T: Std140Bytes>(&mut self, value: &T) -> usize {
+        self.try_write(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like [write](Self::write), but return [Std140Error::Overflow] naming the type of `value`
+    /// instead of panicking if the destination is too small.
+    pub fn try_write<T: Std140Bytes>(&mut self, value: &T) -> Result<usize, Std140Error> {
+        self.try_write_field(type_name::<T>(), value)
+    }
+
+    /// Like [try_write](Self::try_write), but name `field` in the error, such as the struct
+    /// member being written.
+    pub fn try_write_field<T: Std140Bytes>(
+        &mut self,
+        field: &'static str,
+        value: &T,
+    ) -> Result<usize, Std140Error> {
+        let start = self.offset;
+        let offset = self.pad_to(field, T::ALIGN)?;
+        let required = offset + T::SIZE;
+        if required > self.bytes.len() {
+            self.offset = start;
+            return Err(Std140Error::Overflow {
+                field,
+                offset,
+                required,
+                available: self.bytes.len(),
+            });
+        }
+        debug_check_offset::<T>(offset);
+        value.write_std140(&mut self.bytes[offset..], self.endian);
+        #[cfg(feature = "stats")]
+        crate::stats::record_block(type_name::<T>(), T::SIZE);
+        self.offset = required;
+        Ok(offset)
+    }
+
+    fn pad_to(&mut self, field: &'static str, align: usize) -> Result<usize, Std140Error> {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        let aligned = (self.offset + align - 1) & !(align - 1);
+        if aligned > self.bytes.len() {
+            return Err(Std140Error::Overflow {
+                field,
+                offset: self.offset,
+                required: aligned,
+                available: self.bytes.len(),
+            });
+        }
+        self.bytes[self.offset..aligned].fill(0);
+        self.offset = aligned;
+        Ok(aligned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Std140Writer;
+    use crate::{Endian, Std140Error};
+
+    #[test]
+    fn padding() {
+        let mut bytes = [0xffu8; 48];
+        let mut writer = Std140Writer::with_endian(&mut bytes, Endian::Little);
+        assert_eq!(writer.write(&mint::Vector3 { x: 1.0f32, y: 2.0f32, z: 3.0f32 }), 0);
+        assert_eq!(writer.write(&mint::Vector2 { x: 4u32, y: 5u32 }), 16);
+        assert_eq!(writer.align_to(16), 32);
+        assert_eq!(writer.write(&mint::Vector4 { x: 6i32, y: 7i32, z: 8i32, w: 9i32 }), 32);
+        assert_eq!(writer.offset(), 48);
+
+        assert_eq!(&bytes[12..16], &[0; 4]);
+        assert_eq!(&bytes[16..20], &4u32.to_le_bytes());
+        assert_eq!(&bytes[24..32], &[0; 8]);
+        assert_eq!(&bytes[44..48], &9i32.to_le_bytes());
+    }
+
+    #[test]
+    fn errors() {
+        let mut bytes = [0u8; 40];
+        assert_eq!(
+            Std140Writer::with_offset(&mut bytes, 8, Endian::Little).unwrap_err(),
+            Std140Error::Misaligned { field: "", offset: 8, align: 16 }
+        );
+
+        let mut writer = Std140Writer::with_offset(&mut bytes, 16, Endian::Little).unwrap();
+        assert_eq!(writer.try_write_field("scale", &1.0f32), Ok(16));
+        let error =
+            writer.try_write_field("direction", &mint::Vector3 { x: 0.0f32, y: 0.0, z: 1.0 });
+        assert_eq!(
+            error,
+            Err(Std140Error::Overflow {
+                field: "direction",
+                offset: 32,
+                required: 44,
+                available: 40
+            })
+        );
+        assert_eq!(writer.offset(), 20);
+        assert!(writer.try_write(&mint::Vector4 { x: 0u32, y: 0, z: 0, w: 0 }).is_err());
+        assert_eq!(writer.try_write(&mint::Vector2 { x: 0u32, y: 0 }), Ok(24));
+    }
+}